@@ -0,0 +1,273 @@
+//! Procedural macros for the `term_ansi` crate.
+//!
+//! Enable them with the `macros` feature of `term_ansi` and use them from
+//! there rather than depending on this crate directly: the macros there pass
+//! their own crate path in, so the expansion names the right crate even when
+//! the dependency is renamed.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenTree;
+use quote::{format_ident, quote};
+use syn::parse::{Parse, ParseStream};
+use syn::{parse_macro_input, LitStr, Token};
+
+/// The attribute names accepted in tags, with their SGR parameters, in the
+/// order `Style` writes them.
+const ATTRIBUTES: [(&str, u8); 10] = [
+    ("bold", 1),
+    ("dim", 2),
+    ("italic", 3),
+    ("underline", 4),
+    ("blink", 5),
+    ("reverse", 7),
+    ("conceal", 8),
+    ("strikethrough", 9),
+    ("double_underline", 21),
+    ("overline", 53),
+];
+
+/// The standard color names, in SGR order.
+const COLOURS: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+/// The reset written by every closing tag.
+const RESET: &str = "\x1b[0m";
+
+struct StyledInput {
+    /// The path of the `term_ansi` crate, such as `$crate`.
+    krate: proc_macro2::TokenStream,
+    template: LitStr,
+    args: Vec<TokenTree>,
+}
+
+impl Parse for StyledInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut krate = proc_macro2::TokenStream::new();
+        while !input.peek(Token![;]) {
+            krate.extend([input.parse::<TokenTree>()?]);
+        }
+        input.parse::<Token![;]>()?;
+        let template: LitStr = input.parse()?;
+        let mut args: Vec<TokenTree> = Vec::new();
+        if !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            let rest: proc_macro2::TokenStream = input.parse()?;
+            args.extend(rest);
+            if matches!(args.last(), Some(TokenTree::Punct(p)) if p.as_char() == ',') {
+                args.pop();
+            }
+        }
+        Ok(StyledInput {
+            krate,
+            template,
+            args,
+        })
+    }
+}
+
+/// Returns the SGR parameters of a color name in a tag.
+fn color_params(name: &str, background: bool) -> Result<String, String> {
+    let (basic, bright, extended): (u8, u8, u8) = if background {
+        (40, 100, 48)
+    } else {
+        (30, 90, 38)
+    };
+
+    if let Some(digits) = name.strip_prefix('#') {
+        if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(format!("invalid hex color `{}`", name));
+        }
+        let channel = |range: std::ops::Range<usize>, scale: u8| {
+            u8::from_str_radix(&digits[range], 16)
+                .map(|v| v * scale)
+                .map_err(|_| format!("invalid hex color `{}`", name))
+        };
+        let (r, g, b) = match digits.len() {
+            6 => (channel(0..2, 1)?, channel(2..4, 1)?, channel(4..6, 1)?),
+            3 => (channel(0..1, 17)?, channel(1..2, 17)?, channel(2..3, 17)?),
+            _ => return Err(format!("invalid hex color `{}`", name)),
+        };
+        return Ok(format!("{};2;{};{};{}", extended, r, g, b));
+    }
+    if name.chars().all(|c| c.is_ascii_digit()) {
+        let index: u8 = name
+            .parse()
+            .map_err(|_| format!("color index `{}` is not in 0-255", name))?;
+        return Ok(format!("{};5;{}", extended, index));
+    }
+
+    let (colour, offset) = match name
+        .strip_prefix("bright_")
+        .or_else(|| name.strip_prefix("bright-"))
+    {
+        Some(colour) => (colour, bright),
+        None => (name, basic),
+    };
+    match COLOURS.iter().position(|&c| c == colour) {
+        Some(i) => Ok((offset + i as u8).to_string()),
+        None => Err(format!(
+            "unknown color or attribute `{}`; theme roles and CSS color names \
+             are only resolved at runtime, by `markup!`",
+            name
+        )),
+    }
+}
+
+/// Returns the escape code that enables the style spec in a tag, such as
+/// `bold red on blue`.
+fn tag_code(tag: &str) -> Result<String, String> {
+    let words: Vec<String> = tag
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_ascii_lowercase())
+        .collect();
+    if words.is_empty() {
+        return Err(String::from("empty tag `<>`"));
+    }
+
+    let mut attributes = [false; ATTRIBUTES.len()];
+    let mut fg: Option<String> = None;
+    let mut bg: Option<String> = None;
+    let mut words = words.iter();
+    while let Some(word) = words.next() {
+        if word == "on" {
+            let color = words
+                .next()
+                .ok_or_else(|| format!("expected a color after `on` in `<{}>`", tag))?;
+            bg = Some(color_params(color, true)?);
+            continue;
+        }
+        let name = match word.as_str() {
+            "b" | "strong" => "bold",
+            "i" | "em" => "italic",
+            "u" => "underline",
+            "s" | "del" => "strikethrough",
+            "hidden" => "conceal",
+            word => word,
+        };
+        match ATTRIBUTES.iter().position(|&(n, _)| n == name) {
+            Some(i) => attributes[i] = true,
+            None => fg = Some(color_params(word, false)?),
+        }
+    }
+
+    let mut params: Vec<String> = ATTRIBUTES
+        .iter()
+        .zip(attributes)
+        .filter(|&(_, set)| set)
+        .map(|(&(_, sgr), _)| sgr.to_string())
+        .collect();
+    params.extend(fg);
+    params.extend(bg);
+    Ok(format!("\x1b[{}m", params.join(";")))
+}
+
+/// Rewrites the tags in `markup` as placeholders, returning the format
+/// string and the escape code of each placeholder.
+fn compile(markup: &str) -> Result<(String, Vec<String>), String> {
+    let mut format = String::with_capacity(markup.len());
+    let mut codes: Vec<String> = Vec::new();
+    let mut open: Vec<(&str, String)> = Vec::new();
+    let mut rest = markup;
+    while let Some(c) = rest.chars().next() {
+        if c == '{' {
+            let end = if rest.starts_with("{{") {
+                2
+            } else {
+                rest.find('}').map_or(rest.len(), |i| i + 1)
+            };
+            format.push_str(&rest[..end]);
+            rest = &rest[end..];
+            continue;
+        }
+        if c != '<' {
+            format.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+        if rest.starts_with("<<") {
+            format.push('<');
+            rest = &rest[2..];
+            continue;
+        }
+
+        let end = rest
+            .find('>')
+            .ok_or_else(|| format!("unterminated tag `{}`; write `<<` for a literal `<`", rest))?;
+        let tag = &rest[1..end];
+        let code = match tag.strip_prefix('/') {
+            Some(name) => {
+                let (opened, _) = open
+                    .pop()
+                    .ok_or_else(|| format!("closing tag `<{}>` has no opening tag", tag))?;
+                if !name.is_empty() && name != opened {
+                    return Err(format!(
+                        "closing tag `<{}>` does not match the open tag `<{}>`",
+                        tag, opened
+                    ));
+                }
+                let mut code = String::from(RESET);
+                code.extend(open.iter().map(|(_, code)| code.as_str()));
+                code
+            }
+            None => {
+                let code = tag_code(tag)?;
+                open.push((tag, code.clone()));
+                code
+            }
+        };
+        format.push_str(&format!("{{__term_ansi_{}}}", codes.len()));
+        codes.push(code);
+        rest = &rest[end + 1..];
+    }
+
+    match open.last() {
+        Some((tag, _)) => Err(format!("tag `<{}>` is never closed", tag)),
+        None => Ok((format, codes)),
+    }
+}
+
+/// Expands `term_ansi::styled!`, which passes the path of the `term_ansi`
+/// crate and a `;` before the template and its arguments.
+///
+/// ```
+/// let count = 3;
+/// assert_eq!(
+///     term_ansi::styled!("<bold red>{count}</bold red> errors").to_string(),
+///     "\x1b[1;31m3\x1b[0m errors"
+/// );
+/// ```
+///
+/// A color that is not valid hex does not compile:
+///
+/// ```compile_fail
+/// let _ = term_ansi::styled!("<#aébcd>oops</>");
+/// ```
+#[proc_macro]
+pub fn styled(input: TokenStream) -> TokenStream {
+    let StyledInput {
+        krate,
+        template,
+        args,
+    } = parse_macro_input!(input as StyledInput);
+    let (format, codes) = match compile(&template.value()) {
+        Ok(compiled) => compiled,
+        Err(message) => {
+            return syn::Error::new(template.span(), message)
+                .to_compile_error()
+                .into()
+        }
+    };
+
+    let format = LitStr::new(&format, template.span());
+    let names = (0..codes.len()).map(|i| format_ident!("__term_ansi_{}", i));
+    let args = (!args.is_empty()).then(|| quote!(, #(#args)*));
+    quote! {
+        ::core::format_args!(
+            #format #args
+            #(, #names = #krate::MarkupCode(#codes))*
+        )
+    }
+    .into()
+}
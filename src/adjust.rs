@@ -0,0 +1,284 @@
+//! Deriving new colors from existing ones.
+
+use crate::convert::{oklab_to_rgb, rgb_to_oklab};
+use crate::{hsl_to_rgb, linear_to_srgb, rgb_to_hsl, srgb_to_linear};
+
+/// Returns the color a fraction `t` of the way from `a` to `b`, interpolating
+/// each RGB channel linearly.
+///
+/// `t` is clamped to 0-1, so `0.0` gives `a` and `1.0` gives `b`. This is the
+/// interpolation [`gradient`](crate::gradient) uses. Interpolating raw RGB can
+/// pass through dull, grayish midpoints; see [`lerp_color_oklab`] for an
+/// alternative.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{lerp_color, rgb};
+///
+/// assert_eq!(lerp_color((0, 0, 0), (255, 100, 10), 0.5), (128, 50, 5));
+///
+/// for load in [0.1, 0.5, 0.9] {
+///     let (r, g, b) = lerp_color((0, 200, 0), (220, 0, 0), load);
+///     println!("{}", rgb!(r, g, b, "load {:.0}%", load * 100.0));
+/// }
+/// ```
+pub fn lerp_color(a: (u8, u8, u8), b: (u8, u8, u8), t: f64) -> (u8, u8, u8) {
+    let t: f64 = t.clamp(0.0, 1.0);
+    let channel = |x: u8, y: u8| (x as f64 + (y as f64 - x as f64) * t).round() as u8;
+    (channel(a.0, b.0), channel(a.1, b.1), channel(a.2, b.2))
+}
+
+/// Returns the color a fraction `t` of the way from `a` to `b`, interpolating
+/// in the OKLab color space.
+///
+/// Midpoints keep their brightness and saturation better than with
+/// [`lerp_color`], which makes for smoother gradients and heatmaps. `t` is
+/// clamped to 0-1.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::lerp_color_oklab;
+///
+/// assert_eq!(lerp_color_oklab((255, 0, 0), (0, 0, 255), 0.0), (255, 0, 0));
+/// assert_eq!(lerp_color_oklab((255, 0, 0), (0, 0, 255), 1.0), (0, 0, 255));
+/// let (r, _, b) = lerp_color_oklab((255, 0, 0), (0, 0, 255), 0.5);
+/// assert!(r > 128 && b > 128);
+/// ```
+pub fn lerp_color_oklab(a: (u8, u8, u8), b: (u8, u8, u8), t: f64) -> (u8, u8, u8) {
+    let t: f64 = t.clamp(0.0, 1.0);
+    let (l1, a1, b1) = rgb_to_oklab(a.0, a.1, a.2);
+    let (l2, a2, b2) = rgb_to_oklab(b.0, b.1, b.2);
+    oklab_to_rgb(l1 + (l2 - l1) * t, a1 + (a2 - a1) * t, b1 + (b2 - b1) * t)
+}
+
+/// Blends two colors, with `weight` the share of `a` in the result (0-1).
+///
+/// The channels are mixed in linear light, as paint or light would, rather
+/// than on the gamma-encoded values. Mixing a status color with the
+/// background gives a muted variant of it.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{mix, rgb};
+///
+/// let background = (30, 30, 30);
+/// let (r, g, b) = mix((220, 50, 50), background, 0.4);
+/// println!("{}", rgb!(r, g, b, "muted error"));
+///
+/// assert_eq!(mix((255, 0, 0), (0, 0, 255), 1.0), (255, 0, 0));
+/// assert_eq!(mix((255, 255, 255), (0, 0, 0), 0.5), (188, 188, 188));
+/// ```
+pub fn mix(a: (u8, u8, u8), b: (u8, u8, u8), weight: f64) -> (u8, u8, u8) {
+    let weight: f64 = weight.clamp(0.0, 1.0);
+    let channel = |x: u8, y: u8| {
+        let x: f64 = srgb_to_linear(x as f64 / 255.0);
+        let y: f64 = srgb_to_linear(y as f64 / 255.0);
+        (linear_to_srgb(x * weight + y * (1.0 - weight)) * 255.0).round() as u8
+    };
+    (channel(a.0, b.0), channel(a.1, b.1), channel(a.2, b.2))
+}
+
+/// Makes a color lighter by raising its OKLab lightness by `amount` (0-1).
+///
+/// Hue and chroma are kept, so the result reads as the same color, only
+/// brighter. Lightness is capped at white.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{darken, lighten, rgb};
+///
+/// let accent = (40, 110, 200);
+/// let (r, g, b) = lighten(accent, 0.15);
+/// println!("{} {}", rgb!(40, 110, 200, "normal"), rgb!(r, g, b, "hover"));
+///
+/// assert_eq!(lighten((128, 128, 128), 1.0), (255, 255, 255));
+/// assert_eq!(darken((128, 128, 128), 1.0), (0, 0, 0));
+/// ```
+pub fn lighten(rgb: (u8, u8, u8), amount: f64) -> (u8, u8, u8) {
+    let (l, a, b) = rgb_to_oklab(rgb.0, rgb.1, rgb.2);
+    oklab_to_rgb((l + amount).clamp(0.0, 1.0), a, b)
+}
+
+/// Makes a color darker by lowering its OKLab lightness by `amount` (0-1).
+///
+/// The inverse of [`lighten`].
+pub fn darken(rgb: (u8, u8, u8), amount: f64) -> (u8, u8, u8) {
+    lighten(rgb, -amount)
+}
+
+/// Makes a color more vivid by scaling its OKLab chroma up by `amount`.
+///
+/// An `amount` of `0.5` gives 50% more chroma. Lightness and hue are kept;
+/// grays stay gray since they have no chroma to scale. Colors pushed out of
+/// the sRGB gamut are clamped.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{desaturate, rgb, saturate};
+///
+/// let brand = (200, 80, 60);
+/// for (r, g, b) in [desaturate(brand, 0.6), brand, saturate(brand, 0.2)] {
+///     print!("{} ", rgb!(r, g, b, "███"));
+/// }
+/// println!();
+/// ```
+pub fn saturate(rgb: (u8, u8, u8), amount: f64) -> (u8, u8, u8) {
+    let (l, a, b) = rgb_to_oklab(rgb.0, rgb.1, rgb.2);
+    let scale: f64 = (1.0 + amount).max(0.0);
+    oklab_to_rgb(l, a * scale, b * scale)
+}
+
+/// Makes a color duller by scaling its OKLab chroma down by `amount` (0-1).
+///
+/// An `amount` of `1.0` leaves a gray of the same lightness. The inverse of
+/// [`saturate`].
+pub fn desaturate(rgb: (u8, u8, u8), amount: f64) -> (u8, u8, u8) {
+    saturate(rgb, -amount)
+}
+
+/// Returns the photographic negative of a color, with every channel flipped.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::invert;
+///
+/// assert_eq!(invert((255, 200, 0)), (0, 55, 255));
+/// ```
+pub fn invert(rgb: (u8, u8, u8)) -> (u8, u8, u8) {
+    (255 - rgb.0, 255 - rgb.1, 255 - rgb.2)
+}
+
+/// Returns the complementary color: the same saturation and lightness with
+/// the HSL hue rotated by 180°.
+///
+/// Unlike [`invert`], the complement of a dark color stays dark, which makes
+/// it a good selection highlight on the same background.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::complement;
+///
+/// assert_eq!(complement((255, 0, 0)), (0, 255, 255));
+/// assert_eq!(complement((100, 100, 100)), (100, 100, 100));
+/// ```
+pub fn complement(rgb: (u8, u8, u8)) -> (u8, u8, u8) {
+    rotate_hue(rgb, 180.0)
+}
+
+/// Rotates the HSL hue of a color by `degrees`.
+fn rotate_hue(rgb: (u8, u8, u8), degrees: f64) -> (u8, u8, u8) {
+    let (h, s, l) = rgb_to_hsl(rgb.0, rgb.1, rgb.2);
+    hsl_to_rgb((h + degrees).rem_euclid(360.0), s, l)
+}
+
+/// Returns `n` colors stepping evenly from `base` toward black.
+///
+/// The first entry is `base` itself and each following one is darker; black
+/// is never reached. Steps are taken in OKLab, so they look evenly spaced.
+/// Together with [`tints`] this builds a ladder of related colors, for
+/// example one per severity level.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{rgb, shades};
+///
+/// let ladder = shades((230, 80, 60), 4);
+/// assert_eq!(ladder.len(), 4);
+/// assert_eq!(ladder[0], (230, 80, 60));
+/// for (level, (r, g, b)) in ladder.into_iter().enumerate() {
+///     println!("{}", rgb!(r, g, b, "level {}", level));
+/// }
+/// ```
+pub fn shades(base: (u8, u8, u8), n: usize) -> Vec<(u8, u8, u8)> {
+    ladder(base, (0, 0, 0), n)
+}
+
+/// Returns `n` colors stepping evenly from `base` toward white.
+///
+/// The lighter counterpart of [`shades`]: the first entry is `base` and white
+/// is never reached.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::tints;
+///
+/// let ladder = tints((40, 90, 200), 3);
+/// assert_eq!(ladder[0], (40, 90, 200));
+/// assert!(ladder[2].0 > ladder[1].0);
+/// ```
+pub fn tints(base: (u8, u8, u8), n: usize) -> Vec<(u8, u8, u8)> {
+    ladder(base, (255, 255, 255), n)
+}
+
+fn ladder(base: (u8, u8, u8), target: (u8, u8, u8), n: usize) -> Vec<(u8, u8, u8)> {
+    (0..n)
+        .map(|i| lerp_color_oklab(base, target, i as f64 / n as f64))
+        .collect()
+}
+
+/// Returns `seed` followed by its two neighbors 30° away on the hue wheel.
+///
+/// Analogous colors sit close together and look calm next to each other.
+/// Like the other harmony generators, this keeps the seed's HSL saturation
+/// and lightness and starts the palette with `seed` itself.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::analogous;
+///
+/// assert_eq!(
+///     analogous((255, 0, 0)),
+///     vec![(255, 0, 0), (255, 128, 0), (255, 0, 128)]
+/// );
+/// ```
+pub fn analogous(seed: (u8, u8, u8)) -> Vec<(u8, u8, u8)> {
+    harmony(seed, &[30.0, -30.0])
+}
+
+/// Returns `seed` and the two colors 120° and 240° away on the hue wheel.
+///
+/// The three hues are spread evenly, which gives clearly distinct chart
+/// series.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{rgb, triadic};
+///
+/// for (series, (r, g, b)) in triadic((220, 60, 60)).into_iter().enumerate() {
+///     println!("{}", rgb!(r, g, b, "series {}", series));
+/// }
+/// ```
+pub fn triadic(seed: (u8, u8, u8)) -> Vec<(u8, u8, u8)> {
+    harmony(seed, &[120.0, 240.0])
+}
+
+/// Returns `seed` and the three colors 90°, 180° and 270° away on the hue wheel.
+pub fn tetradic(seed: (u8, u8, u8)) -> Vec<(u8, u8, u8)> {
+    harmony(seed, &[90.0, 180.0, 270.0])
+}
+
+/// Returns `seed` and the two colors on either side of its complement, 150°
+/// and 210° away on the hue wheel.
+///
+/// This contrasts almost as strongly as [`complement`] while leaving room for
+/// a third color.
+pub fn split_complement(seed: (u8, u8, u8)) -> Vec<(u8, u8, u8)> {
+    harmony(seed, &[150.0, 210.0])
+}
+
+fn harmony(seed: (u8, u8, u8), offsets: &[f64]) -> Vec<(u8, u8, u8)> {
+    std::iter::once(seed)
+        .chain(offsets.iter().map(|&degrees| rotate_hue(seed, degrees)))
+        .collect()
+}
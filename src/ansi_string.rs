@@ -0,0 +1,228 @@
+//! Rich text stored as plain text plus style spans.
+
+use std::fmt;
+use std::ops::{Add, AddAssign, Bound, RangeBounds};
+
+use crate::text::char_width;
+use crate::{codes, color_support, colors_enabled, AnsiParser, ColorSupport, Style};
+
+/// Text with styled spans, rendered to escape codes only when displayed.
+///
+/// Unlike the `String`s returned by the macros, an `AnsiString` knows its
+/// plain text and styles separately. Length queries, concatenation and
+/// slicing work on the visible text, and it can be rendered for any
+/// [`ColorSupport`] level.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{AnsiString, ColorSupport, Colours, Style};
+///
+/// let mut status = AnsiString::styled(Style::new().fg(Colours::Green).bold(), "ok");
+/// status += AnsiString::from(" 3 tests");
+/// assert_eq!(status.as_str(), "ok 3 tests");
+/// assert_eq!(status.width(), 10);
+/// assert_eq!(status.slice(..2).as_str(), "ok");
+/// assert_eq!(status.render(ColorSupport::Basic16), "\x1b[1;32mok\x1b[0m 3 tests");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct AnsiString {
+    text: String,
+    /// Byte offset where each span starts, with its style. Spans are never
+    /// empty and adjacent spans never share a style.
+    spans: Vec<(usize, Style)>,
+}
+
+impl AnsiString {
+    /// Creates an empty string.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a string with `text` in a single style.
+    pub fn styled(style: impl Into<Style>, text: &str) -> Self {
+        let mut string = Self::new();
+        string.push_styled(style, text);
+        string
+    }
+
+    /// Builds a string from text containing escape codes, as read by [`AnsiParser`].
+    pub fn parse(text: &str) -> Self {
+        let mut string = Self::new();
+        for (style, span) in AnsiParser::new(text) {
+            string.push_styled(style, span);
+        }
+        string
+    }
+
+    /// Appends `text` in the given style.
+    pub fn push_styled(&mut self, style: impl Into<Style>, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        let style = style.into();
+        if self.spans.last().map(|&(_, last)| last) != Some(style) {
+            self.spans.push((self.text.len(), style));
+        }
+        self.text.push_str(text);
+    }
+
+    /// Appends unstyled text.
+    pub fn push_str(&mut self, text: &str) {
+        self.push_styled(Style::new(), text);
+    }
+
+    /// Returns the plain text, without any escape codes.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the length of the plain text in bytes.
+    pub fn len(&self) -> usize {
+        self.text.len()
+    }
+
+    /// Returns whether there is no text.
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Returns the number of terminal cells the text occupies; see [`visible_width`](crate::visible_width).
+    pub fn width(&self) -> usize {
+        self.text.chars().map(char_width).sum()
+    }
+
+    /// Returns each styled span of the text.
+    pub fn spans(&self) -> impl Iterator<Item = (Style, &str)> {
+        self.spans.iter().enumerate().map(|(i, &(start, style))| {
+            let end = self
+                .spans
+                .get(i + 1)
+                .map_or(self.text.len(), |&(end, _)| end);
+            (style, &self.text[start..end])
+        })
+    }
+
+    /// Returns the text within a range of visible columns, keeping its styles.
+    ///
+    /// Like [`slice_ansi`](crate::slice_ansi), a wide character cut by either
+    /// boundary is replaced with spaces in its style.
+    pub fn slice(&self, columns: impl RangeBounds<usize>) -> Self {
+        let start = match columns.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match columns.end_bound() {
+            Bound::Included(&n) => n.saturating_add(1),
+            Bound::Excluded(&n) => n,
+            Bound::Unbounded => usize::MAX,
+        };
+
+        let mut sliced = Self::new();
+        let mut column = 0;
+        for (style, span) in self.spans() {
+            for (i, c) in span.char_indices() {
+                let width = char_width(c);
+                let next = column + width;
+                if column < end && (next > start || (width == 0 && column >= start)) {
+                    if column < start || next > end {
+                        let visible = next.min(end) - column.max(start);
+                        sliced.push_styled(style, &" ".repeat(visible));
+                    } else {
+                        sliced.push_styled(style, &span[i..i + c.len_utf8()]);
+                    }
+                }
+                column = next;
+            }
+        }
+        sliced
+    }
+
+    /// Renders the text with escape codes for the given color depth.
+    ///
+    /// Colors beyond what `support` allows are mapped to the nearest available
+    /// palette entry, and [`ColorSupport::None`] yields the plain text.
+    pub fn render(&self, support: ColorSupport) -> String {
+        let mut rendered = String::with_capacity(self.text.len());
+        let _ = self.write_to(&mut rendered, support);
+        rendered
+    }
+
+    fn write_to(&self, f: &mut dyn fmt::Write, support: ColorSupport) -> fmt::Result {
+        if support == ColorSupport::None {
+            return f.write_str(&self.text);
+        }
+        let mut styled = false;
+        for (style, span) in self.spans() {
+            if styled {
+                f.write_str(codes::RESET)?;
+            }
+            style.write_code(f, support)?;
+            f.write_str(span)?;
+            styled = !style.is_plain();
+        }
+        if styled {
+            f.write_str(codes::RESET)?;
+        }
+        Ok(())
+    }
+}
+
+/// Renders for the detected [`color_support`], or as plain text when colors are disabled.
+impl fmt::Display for AnsiString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let support = if colors_enabled() {
+            color_support()
+        } else {
+            ColorSupport::None
+        };
+        self.write_to(f, support)
+    }
+}
+
+impl From<&str> for AnsiString {
+    fn from(text: &str) -> Self {
+        let mut string = Self::new();
+        string.push_str(text);
+        string
+    }
+}
+
+impl From<String> for AnsiString {
+    fn from(text: String) -> Self {
+        Self::from(text.as_str())
+    }
+}
+
+impl AddAssign<&AnsiString> for AnsiString {
+    fn add_assign(&mut self, other: &AnsiString) {
+        for (style, span) in other.spans() {
+            self.push_styled(style, span);
+        }
+    }
+}
+
+impl AddAssign for AnsiString {
+    fn add_assign(&mut self, other: AnsiString) {
+        *self += &other;
+    }
+}
+
+impl Add<&AnsiString> for AnsiString {
+    type Output = AnsiString;
+
+    fn add(mut self, other: &AnsiString) -> AnsiString {
+        self += other;
+        self
+    }
+}
+
+impl Add for AnsiString {
+    type Output = AnsiString;
+
+    fn add(mut self, other: AnsiString) -> AnsiString {
+        self += &other;
+        self
+    }
+}
@@ -0,0 +1,112 @@
+//! The escape codes shared by the macros, [`Colorize`](crate::Colorize) and the color types.
+
+/// Resets all colors and attributes.
+pub const RESET: &str = "\x1b[0m";
+/// SGR 30: black foreground.
+pub const BLACK: &str = "\x1b[30m";
+/// SGR 31: red foreground.
+pub const RED: &str = "\x1b[31m";
+/// SGR 32: green foreground.
+pub const GREEN: &str = "\x1b[32m";
+/// SGR 33: yellow foreground.
+pub const YELLOW: &str = "\x1b[33m";
+/// SGR 34: blue foreground.
+pub const BLUE: &str = "\x1b[34m";
+/// SGR 35: magenta foreground.
+pub const MAGENTA: &str = "\x1b[35m";
+/// SGR 36: cyan foreground.
+pub const CYAN: &str = "\x1b[36m";
+/// SGR 37: white foreground.
+pub const WHITE: &str = "\x1b[37m";
+/// SGR 39: the terminal's default foreground.
+pub const DEFAULT_FG: &str = "\x1b[39m";
+/// SGR 40: black background.
+pub const BG_BLACK: &str = "\x1b[40m";
+/// SGR 41: red background.
+pub const BG_RED: &str = "\x1b[41m";
+/// SGR 42: green background.
+pub const BG_GREEN: &str = "\x1b[42m";
+/// SGR 43: yellow background.
+pub const BG_YELLOW: &str = "\x1b[43m";
+/// SGR 44: blue background.
+pub const BG_BLUE: &str = "\x1b[44m";
+/// SGR 45: magenta background.
+pub const BG_MAGENTA: &str = "\x1b[45m";
+/// SGR 46: cyan background.
+pub const BG_CYAN: &str = "\x1b[46m";
+/// SGR 47: white background.
+pub const BG_WHITE: &str = "\x1b[47m";
+/// SGR 49: the terminal's default background.
+pub const DEFAULT_BG: &str = "\x1b[49m";
+/// SGR 90: bright black foreground.
+pub const BRIGHT_BLACK: &str = "\x1b[90m";
+/// SGR 91: bright red foreground.
+pub const BRIGHT_RED: &str = "\x1b[91m";
+/// SGR 92: bright green foreground.
+pub const BRIGHT_GREEN: &str = "\x1b[92m";
+/// SGR 93: bright yellow foreground.
+pub const BRIGHT_YELLOW: &str = "\x1b[93m";
+/// SGR 94: bright blue foreground.
+pub const BRIGHT_BLUE: &str = "\x1b[94m";
+/// SGR 95: bright magenta foreground.
+pub const BRIGHT_MAGENTA: &str = "\x1b[95m";
+/// SGR 96: bright cyan foreground.
+pub const BRIGHT_CYAN: &str = "\x1b[96m";
+/// SGR 97: bright white foreground.
+pub const BRIGHT_WHITE: &str = "\x1b[97m";
+/// SGR 100: bright black background.
+pub const BG_BRIGHT_BLACK: &str = "\x1b[100m";
+/// SGR 101: bright red background.
+pub const BG_BRIGHT_RED: &str = "\x1b[101m";
+/// SGR 102: bright green background.
+pub const BG_BRIGHT_GREEN: &str = "\x1b[102m";
+/// SGR 103: bright yellow background.
+pub const BG_BRIGHT_YELLOW: &str = "\x1b[103m";
+/// SGR 104: bright blue background.
+pub const BG_BRIGHT_BLUE: &str = "\x1b[104m";
+/// SGR 105: bright magenta background.
+pub const BG_BRIGHT_MAGENTA: &str = "\x1b[105m";
+/// SGR 106: bright cyan background.
+pub const BG_BRIGHT_CYAN: &str = "\x1b[106m";
+/// SGR 107: bright white background.
+pub const BG_BRIGHT_WHITE: &str = "\x1b[107m";
+/// SGR 1: bold.
+pub const BOLD: &str = "\x1b[1m";
+/// SGR 2: dim (faint).
+pub const DIM: &str = "\x1b[2m";
+/// SGR 3: italic.
+pub const ITALIC: &str = "\x1b[3m";
+/// SGR 4: underline.
+pub const UNDERLINE: &str = "\x1b[4m";
+/// SGR 5: blink.
+pub const BLINK: &str = "\x1b[5m";
+/// SGR 6: rapid blink.
+pub const RAPID_BLINK: &str = "\x1b[6m";
+/// SGR 7: reverse video.
+pub const REVERSE: &str = "\x1b[7m";
+/// SGR 8: conceal.
+pub const CONCEAL: &str = "\x1b[8m";
+/// SGR 9: strikethrough.
+pub const STRIKETHROUGH: &str = "\x1b[9m";
+/// SGR 21: double underline.
+pub const DOUBLE_UNDERLINE: &str = "\x1b[21m";
+/// SGR 53: overline.
+pub const OVERLINE: &str = "\x1b[53m";
+/// EL 2: erases the whole line the cursor is on.
+pub const CLEAR_LINE: &str = "\x1b[2K";
+/// EL 0: erases from the cursor to the end of the line.
+pub const CLEAR_LINE_RIGHT: &str = "\x1b[0K";
+/// EL 1: erases from the start of the line to the cursor.
+pub const CLEAR_LINE_LEFT: &str = "\x1b[1K";
+/// ED 2: erases the whole screen, leaving the cursor where it is.
+pub const CLEAR_SCREEN: &str = "\x1b[2J";
+/// ED 2 followed by CUP: erases the whole screen and moves the cursor to the
+/// top left corner, like the `clear` command. Unlike [`CLEAR_SCREEN`], the
+/// next output starts at the top of the screen.
+pub const CLEAR_SCREEN_HOME: &str = "\x1b[2J\x1b[H";
+/// ED 0: erases from the cursor to the end of the screen.
+pub const CLEAR_SCREEN_BELOW: &str = "\x1b[0J";
+/// ED 1: erases from the start of the screen to the cursor.
+pub const CLEAR_SCREEN_ABOVE: &str = "\x1b[1J";
+/// ED 3: erases the scrollback buffer, where supported.
+pub const CLEAR_SCROLLBACK: &str = "\x1b[3J";
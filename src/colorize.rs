@@ -0,0 +1,143 @@
+//! Method-call syntax for coloring values, e.g. `"error".red().bold()`.
+
+use crate::{codes, Color, Style};
+
+macro_rules! colorize_methods {
+    ($($(#[$doc:meta])* $name:ident => $code:path;)*) => {
+        $(
+            $(#[$doc])*
+            fn $name(&self) -> String {
+                crate::apply_color!($code, "{}", self)
+            }
+        )*
+    };
+}
+
+/// Extension methods for coloring anything that implements [`Display`](std::fmt::Display).
+///
+/// Each method behaves like the macro of the same name, so calls can be chained and
+/// nested with the macros.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::Colorize;
+///
+/// println!("{}", "error".red().bold());
+/// println!("{}", 42.to_string().green());
+/// ```
+pub trait Colorize: std::fmt::Display {
+    colorize_methods! {
+        /// Applies red color, like [`red!`](crate::red).
+        red => codes::RED;
+        /// Applies green color, like [`green!`](crate::green).
+        green => codes::GREEN;
+        /// Applies blue color, like [`blue!`](crate::blue).
+        blue => codes::BLUE;
+        /// Applies white color, like [`white!`](crate::white).
+        white => codes::WHITE;
+        /// Applies black color, like [`black!`](crate::black).
+        black => codes::BLACK;
+        /// Applies yellow color, like [`yellow!`](crate::yellow).
+        yellow => codes::YELLOW;
+        /// Applies magenta color, like [`magenta!`](crate::magenta).
+        magenta => codes::MAGENTA;
+        /// Applies cyan color, like [`cyan!`](crate::cyan).
+        cyan => codes::CYAN;
+        /// Applies bright red color, like [`bright_red!`](crate::bright_red).
+        bright_red => codes::BRIGHT_RED;
+        /// Applies bright green color, like [`bright_green!`](crate::bright_green).
+        bright_green => codes::BRIGHT_GREEN;
+        /// Applies bright blue color, like [`bright_blue!`](crate::bright_blue).
+        bright_blue => codes::BRIGHT_BLUE;
+        /// Applies bright white color, like [`bright_white!`](crate::bright_white).
+        bright_white => codes::BRIGHT_WHITE;
+        /// Applies bright black (gray) color, like [`bright_black!`](crate::bright_black).
+        bright_black => codes::BRIGHT_BLACK;
+        /// Applies bright yellow color, like [`bright_yellow!`](crate::bright_yellow).
+        bright_yellow => codes::BRIGHT_YELLOW;
+        /// Applies bright magenta color, like [`bright_magenta!`](crate::bright_magenta).
+        bright_magenta => codes::BRIGHT_MAGENTA;
+        /// Applies bright cyan color, like [`bright_cyan!`](crate::bright_cyan).
+        bright_cyan => codes::BRIGHT_CYAN;
+        /// Applies a red background, like [`bg_red!`](crate::bg_red).
+        bg_red => codes::BG_RED;
+        /// Applies a green background, like [`bg_green!`](crate::bg_green).
+        bg_green => codes::BG_GREEN;
+        /// Applies a blue background, like [`bg_blue!`](crate::bg_blue).
+        bg_blue => codes::BG_BLUE;
+        /// Applies a white background, like [`bg_white!`](crate::bg_white).
+        bg_white => codes::BG_WHITE;
+        /// Applies a black background, like [`bg_black!`](crate::bg_black).
+        bg_black => codes::BG_BLACK;
+        /// Applies a yellow background, like [`bg_yellow!`](crate::bg_yellow).
+        bg_yellow => codes::BG_YELLOW;
+        /// Applies a magenta background, like [`bg_magenta!`](crate::bg_magenta).
+        bg_magenta => codes::BG_MAGENTA;
+        /// Applies a cyan background, like [`bg_cyan!`](crate::bg_cyan).
+        bg_cyan => codes::BG_CYAN;
+        /// Applies a bright red background, like [`bg_bright_red!`](crate::bg_bright_red).
+        bg_bright_red => codes::BG_BRIGHT_RED;
+        /// Applies a bright green background, like [`bg_bright_green!`](crate::bg_bright_green).
+        bg_bright_green => codes::BG_BRIGHT_GREEN;
+        /// Applies a bright blue background, like [`bg_bright_blue!`](crate::bg_bright_blue).
+        bg_bright_blue => codes::BG_BRIGHT_BLUE;
+        /// Applies a bright white background, like [`bg_bright_white!`](crate::bg_bright_white).
+        bg_bright_white => codes::BG_BRIGHT_WHITE;
+        /// Applies a bright black background, like [`bg_bright_black!`](crate::bg_bright_black).
+        bg_bright_black => codes::BG_BRIGHT_BLACK;
+        /// Applies a bright yellow background, like [`bg_bright_yellow!`](crate::bg_bright_yellow).
+        bg_bright_yellow => codes::BG_BRIGHT_YELLOW;
+        /// Applies a bright magenta background, like [`bg_bright_magenta!`](crate::bg_bright_magenta).
+        bg_bright_magenta => codes::BG_BRIGHT_MAGENTA;
+        /// Applies a bright cyan background, like [`bg_bright_cyan!`](crate::bg_bright_cyan).
+        bg_bright_cyan => codes::BG_BRIGHT_CYAN;
+        /// Applies bold formatting, like [`bold!`](crate::bold).
+        bold => codes::BOLD;
+        /// Applies dim formatting, like [`dim!`](crate::dim).
+        dim => codes::DIM;
+        /// Applies italic formatting, like [`italic!`](crate::italic).
+        italic => codes::ITALIC;
+        /// Applies underline formatting, like [`underline!`](crate::underline).
+        underline => codes::UNDERLINE;
+        /// Applies double underline formatting, like [`double_underline!`](crate::double_underline).
+        double_underline => codes::DOUBLE_UNDERLINE;
+        /// Applies strikethrough formatting, like [`strikethrough!`](crate::strikethrough).
+        strikethrough => codes::STRIKETHROUGH;
+        /// Applies blinking formatting, like [`blink!`](crate::blink).
+        blink => codes::BLINK;
+        /// Applies reverse video formatting, like [`reverse!`](crate::reverse).
+        reverse => codes::REVERSE;
+        /// Applies concealed formatting, like [`conceal!`](crate::conceal).
+        conceal => codes::CONCEAL;
+        /// Applies overline formatting, like [`overline!`](crate::overline).
+        overline => codes::OVERLINE;
+    }
+
+    /// Applies a custom RGB color, like [`rgb!`](crate::rgb).
+    fn rgb(&self, r: u8, g: u8, b: u8) -> String {
+        crate::rgb!(r, g, b, "{}", self)
+    }
+
+    /// Applies a custom RGB background, like [`bg_rgb!`](crate::bg_rgb).
+    fn bg_rgb(&self, r: u8, g: u8, b: u8) -> String {
+        crate::bg_rgb!(r, g, b, "{}", self)
+    }
+
+    /// Applies any [`Color`] as the foreground.
+    fn color(&self, color: impl Into<Color>) -> String {
+        crate::apply_color!(color.into(), "{}", self)
+    }
+
+    /// Applies any [`Color`] as the background.
+    fn on_color(&self, color: impl Into<Color>) -> String {
+        crate::apply_color!(color.into().bg_code(), "{}", self)
+    }
+
+    /// Applies a [`Style`].
+    fn style(&self, style: Style) -> String {
+        style.apply(&self.to_string())
+    }
+}
+
+impl<T: std::fmt::Display + ?Sized> Colorize for T {}
@@ -0,0 +1,168 @@
+//! Measuring how colors relate to each other.
+
+use crate::{rgb_to_lab, srgb_to_linear, CSS_COLORS};
+
+/// Returns the perceived difference between two colors, using the CIEDE2000
+/// formula.
+///
+/// A difference below about 1 is invisible to most people, around 2 is
+/// noticeable side by side, and above 10 the colors read as different.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::delta_e;
+///
+/// assert_eq!(delta_e((10, 20, 30), (10, 20, 30)), 0.0);
+/// assert!(delta_e((255, 0, 0), (254, 0, 0)) < 1.0);
+/// assert!(delta_e((255, 0, 0), (0, 0, 255)) > 10.0);
+/// ```
+pub fn delta_e(a: (u8, u8, u8), b: (u8, u8, u8)) -> f64 {
+    ciede2000(rgb_to_lab(a.0, a.1, a.2), rgb_to_lab(b.0, b.1, b.2))
+}
+
+/// Returns the CIEDE2000 difference between two CIELAB colors.
+pub(crate) fn ciede2000(lab1: (f64, f64, f64), lab2: (f64, f64, f64)) -> f64 {
+    let (l1, a1, b1) = lab1;
+    let (l2, a2, b2) = lab2;
+    let pow25_7: f64 = 25f64.powi(7);
+
+    let c_bar: f64 = (a1.hypot(b1) + a2.hypot(b2)) / 2.0;
+    let g: f64 = 0.5 * (1.0 - (c_bar.powi(7) / (c_bar.powi(7) + pow25_7)).sqrt());
+    let (a1, a2) = ((1.0 + g) * a1, (1.0 + g) * a2);
+    let (c1, c2) = (a1.hypot(b1), a2.hypot(b2));
+    let hue = |b: f64, a: f64| {
+        if a == 0.0 && b == 0.0 {
+            0.0
+        } else {
+            b.atan2(a).to_degrees().rem_euclid(360.0)
+        }
+    };
+    let (h1, h2) = (hue(b1, a1), hue(b2, a2));
+
+    let delta_l: f64 = l2 - l1;
+    let delta_c: f64 = c2 - c1;
+    let delta_h: f64 = match h2 - h1 {
+        _ if c1 * c2 == 0.0 => 0.0,
+        d if d > 180.0 => d - 360.0,
+        d if d < -180.0 => d + 360.0,
+        d => d,
+    };
+    let delta_h: f64 = 2.0 * (c1 * c2).sqrt() * (delta_h / 2.0).to_radians().sin();
+
+    let l_bar: f64 = (l1 + l2) / 2.0;
+    let c_bar: f64 = (c1 + c2) / 2.0;
+    let h_bar: f64 = if c1 * c2 == 0.0 {
+        h1 + h2
+    } else if (h1 - h2).abs() <= 180.0 {
+        (h1 + h2) / 2.0
+    } else if h1 + h2 < 360.0 {
+        (h1 + h2 + 360.0) / 2.0
+    } else {
+        (h1 + h2 - 360.0) / 2.0
+    };
+
+    let cos = |degrees: f64| degrees.to_radians().cos();
+    let t: f64 =
+        1.0 - 0.17 * cos(h_bar - 30.0) + 0.24 * cos(2.0 * h_bar) + 0.32 * cos(3.0 * h_bar + 6.0)
+            - 0.20 * cos(4.0 * h_bar - 63.0);
+    let delta_theta: f64 = 30.0 * (-((h_bar - 275.0) / 25.0).powi(2)).exp();
+    let r_c: f64 = 2.0 * (c_bar.powi(7) / (c_bar.powi(7) + pow25_7)).sqrt();
+    let s_l: f64 = 1.0 + 0.015 * (l_bar - 50.0).powi(2) / (20.0 + (l_bar - 50.0).powi(2)).sqrt();
+    let s_c: f64 = 1.0 + 0.045 * c_bar;
+    let s_h: f64 = 1.0 + 0.015 * c_bar * t;
+    let r_t: f64 = -(2.0 * delta_theta).to_radians().sin() * r_c;
+
+    let (l, c, h) = (delta_l / s_l, delta_c / s_c, delta_h / s_h);
+    (l * l + c * c + h * h + r_t * c * h).sqrt()
+}
+
+/// Returns the name of the CSS named color closest to `rgb`, as measured by
+/// [`delta_e`].
+///
+/// Where two names share a value, such as `gray` and `grey`, the one that
+/// sorts first is returned.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::nearest_named;
+///
+/// assert_eq!(nearest_named((70, 130, 180)), "steelblue");
+/// println!("closest to '{}'", nearest_named((250, 100, 70)));
+/// ```
+pub fn nearest_named(rgb: (u8, u8, u8)) -> &'static str {
+    CSS_COLORS
+        .iter()
+        .map(|&(name, color)| (name, delta_e(rgb, color)))
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map_or("black", |(name, _)| name)
+}
+
+/// Returns the WCAG relative luminance of a color, from 0 for black to 1 for white.
+pub(crate) fn relative_luminance(rgb: (u8, u8, u8)) -> f64 {
+    let channel = |c: u8| srgb_to_linear(c as f64 / 255.0);
+    0.2126 * channel(rgb.0) + 0.7152 * channel(rgb.1) + 0.0722 * channel(rgb.2)
+}
+
+/// Returns the WCAG 2 contrast ratio between a text color and its background,
+/// from 1 (no contrast) to 21 (black on white).
+///
+/// The ratio is symmetric, so the order of `fg` and `bg` does not matter.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::contrast_ratio;
+///
+/// assert_eq!(contrast_ratio((0, 0, 0), (255, 255, 255)), 21.0);
+/// println!("{:.2}:1", contrast_ratio((220, 50, 50), (30, 30, 30)));
+/// ```
+pub fn contrast_ratio(fg: (u8, u8, u8), bg: (u8, u8, u8)) -> f64 {
+    let (a, b) = (relative_luminance(fg), relative_luminance(bg));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+/// Returns whether `fg` on `bg` meets WCAG level AA for normal text, a
+/// contrast ratio of at least 4.5:1.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{meets_aa, meets_aaa};
+///
+/// let background = (255, 255, 255);
+/// assert!(meets_aa((110, 110, 110), background));
+/// assert!(!meets_aaa((110, 110, 110), background));
+/// ```
+pub fn meets_aa(fg: (u8, u8, u8), bg: (u8, u8, u8)) -> bool {
+    contrast_ratio(fg, bg) >= 4.5
+}
+
+/// Returns whether `fg` on `bg` meets WCAG level AAA for normal text, a
+/// contrast ratio of at least 7:1.
+pub fn meets_aaa(fg: (u8, u8, u8), bg: (u8, u8, u8)) -> bool {
+    contrast_ratio(fg, bg) >= 7.0
+}
+
+/// Returns black or white, whichever contrasts more with the background `bg`.
+///
+/// This is the text color [`auto_fg!`](crate::auto_fg) picks.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::readable_fg;
+///
+/// assert_eq!(readable_fg((255, 220, 0)), (0, 0, 0));
+/// assert_eq!(readable_fg((20, 40, 120)), (255, 255, 255));
+/// ```
+pub fn readable_fg(bg: (u8, u8, u8)) -> (u8, u8, u8) {
+    const BLACK: (u8, u8, u8) = (0, 0, 0);
+    const WHITE: (u8, u8, u8) = (255, 255, 255);
+    if contrast_ratio(BLACK, bg) >= contrast_ratio(WHITE, bg) {
+        BLACK
+    } else {
+        WHITE
+    }
+}
@@ -0,0 +1,374 @@
+//! The thread-local stack of styles applied by nested macro calls.
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+
+use crate::sgr::SgrState;
+use crate::{codes, ColorCode};
+
+static DEFAULT_COLOR: RwLock<Cow<'static, str>> = RwLock::new(Cow::Borrowed(codes::WHITE));
+
+static TRAILING_COLOR: AtomicBool = AtomicBool::new(true);
+
+thread_local! {
+    static COLOR_CONTEXT: RefCell<ContextStack> = RefCell::new(ContextStack::new());
+}
+
+#[cfg(feature = "tokio")]
+tokio::task_local! {
+    static TASK_CONTEXT: RefCell<ContextStack>;
+}
+
+/// Runs `f` on the context of the current task, if it was started with
+/// [`ColorContext::scope`], or else on the context of the current thread.
+#[cfg(feature = "tokio")]
+fn with_stack<R>(f: impl FnOnce(&RefCell<ContextStack>) -> R) -> R {
+    if TASK_CONTEXT.try_with(|_| ()).is_ok() {
+        TASK_CONTEXT.with(f)
+    } else {
+        COLOR_CONTEXT.with(f)
+    }
+}
+
+#[cfg(not(feature = "tokio"))]
+fn with_stack<R>(f: impl FnOnce(&RefCell<ContextStack>) -> R) -> R {
+    COLOR_CONTEXT.with(f)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ContextStack {
+    frames: Vec<SgrState>,
+}
+
+impl ContextStack {
+    fn new() -> Self {
+        ContextStack { frames: Vec::new() }
+    }
+}
+
+/// A saved copy of the color context, taken with [`ColorContext::snapshot`].
+///
+/// A snapshot records the pushed levels; the default color and the trailing
+/// color setting are process-wide and not part of it. It is `Send`, so it
+/// can carry the ambient style of one thread into another.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContextSnapshot {
+    stack: ContextStack,
+}
+
+impl ContextSnapshot {
+    /// Returns the number of nesting levels captured in the snapshot.
+    pub fn depth(&self) -> usize {
+        self.stack.frames.len()
+    }
+
+    /// Runs `f` with this snapshot installed as the current thread's context,
+    /// then puts the thread's previous context back.
+    ///
+    /// The previous context is restored even if `f` panics. Use this on worker
+    /// threads so their output nests inside the style of the spawning thread.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use term_ansi::{bold, ColorContext};
+    ///
+    /// ColorContext::with("\x1b[32m", || {
+    ///     let snapshot = ColorContext::snapshot();
+    ///     let line = std::thread::spawn(move || snapshot.enter(|| bold!("done")))
+    ///         .join()
+    ///         .unwrap();
+    ///     assert_eq!(line, "\x1b[1mdone\x1b[0m\x1b[32m");
+    /// });
+    /// ```
+    pub fn enter<R>(&self, f: impl FnOnce() -> R) -> R {
+        let _guard = RestoreGuard(Some(ColorContext::snapshot()));
+        ColorContext::restore(self.clone());
+        f()
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl ContextSnapshot {
+    /// Runs `future` with its own task-local context, starting from this snapshot.
+    ///
+    /// Pushes and pops made while the future is polled stay with the task, so
+    /// nesting survives `.await` points even when the executor moves the task
+    /// between threads.
+    pub fn scope<F: std::future::Future>(
+        self,
+        future: F,
+    ) -> impl std::future::Future<Output = F::Output> {
+        TASK_CONTEXT.scope(RefCell::new(self.stack), future)
+    }
+}
+
+/// Reinstates a saved context when dropped.
+struct RestoreGuard(Option<ContextSnapshot>);
+
+impl Drop for RestoreGuard {
+    fn drop(&mut self) {
+        if let Some(snapshot) = self.0.take() {
+            ColorContext::restore(snapshot);
+        }
+    }
+}
+
+/// Truncates the stack back to a recorded depth when dropped.
+struct DepthGuard(usize);
+
+impl Drop for DepthGuard {
+    fn drop(&mut self) {
+        with_stack(|ctx| ctx.borrow_mut().frames.truncate(self.0));
+    }
+}
+
+pub struct ColorContext;
+
+impl ColorContext {
+    /// Enters a new nesting level, layering `color` over the current style.
+    ///
+    /// Foreground, background, underline color and attributes set by
+    /// enclosing levels stay in effect unless `color` overrides them.
+    pub fn push(color: &str) {
+        with_stack(|ctx| {
+            let mut ctx = ctx.borrow_mut();
+            let mut style = ctx.frames.last().cloned().unwrap_or_default();
+            style.apply_code(color);
+            ctx.frames.push(style);
+        });
+    }
+
+    /// Leaves the innermost nesting level.
+    pub fn pop() {
+        with_stack(|ctx| {
+            ctx.borrow_mut().frames.pop();
+        });
+    }
+
+    /// Returns the code that re-establishes the complete style of the
+    /// innermost level, or the default color at the top level.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use term_ansi::ColorContext;
+    ///
+    /// ColorContext::push("\x1b[43m");
+    /// ColorContext::push("\x1b[1;31m");
+    /// assert_eq!(ColorContext::current_color(), "\x1b[1;31;43m");
+    /// ColorContext::pop();
+    /// assert_eq!(ColorContext::current_color(), "\x1b[43m");
+    /// ColorContext::pop();
+    /// ```
+    pub fn current_color() -> String {
+        with_stack(|ctx| {
+            let ctx = ctx.borrow();
+            match ctx.frames.last() {
+                Some(style) => style.to_code(),
+                None => Self::default_color(),
+            }
+        })
+    }
+
+    /// Runs `f` with `color` pushed as the ambient style, popping it afterwards.
+    ///
+    /// Macro output inside the closure restores `color` after each reset. The
+    /// stack is returned to its previous depth even if `f` panics or leaves
+    /// its own pushes behind.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use term_ansi::{bold, ColorContext};
+    ///
+    /// let line = ColorContext::with("\x1b[34m", || bold!("Note"));
+    /// assert_eq!(line, "\x1b[1mNote\x1b[0m\x1b[34m");
+    /// assert_eq!(ColorContext::depth(), 0);
+    /// ```
+    pub fn with<R>(color: impl ColorCode, f: impl FnOnce() -> R) -> R {
+        let _guard = DepthGuard(Self::depth());
+        Self::push(&color.color_code());
+        f()
+    }
+
+    /// Runs `future` with a task-local context initialized from the current one.
+    ///
+    /// Available with the `tokio` feature. Inside the future, every macro and
+    /// `ColorContext` call uses the task's context instead of the thread's.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// use term_ansi::{red, ColorContext};
+    ///
+    /// let line = ColorContext::scope(async {
+    ///     ColorContext::push("\x1b[44m");
+    ///     tokio::task::yield_now().await;
+    ///     red!("still on blue")
+    /// })
+    /// .await;
+    /// assert_eq!(line, "\x1b[31mstill on blue\x1b[0m\x1b[44m");
+    /// assert_eq!(ColorContext::depth(), 0);
+    /// # });
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub fn scope<F: std::future::Future>(
+        future: F,
+    ) -> impl std::future::Future<Output = F::Output> {
+        Self::snapshot().scope(future)
+    }
+
+    /// Returns the number of nesting levels currently pushed.
+    ///
+    /// This is `0` outside of any macro call; a non-zero value at the top of a
+    /// render loop points to a `push` without a matching `pop`.
+    pub fn depth() -> usize {
+        with_stack(|ctx| ctx.borrow().frames.len())
+    }
+
+    /// Discards every pushed level, returning to the default color.
+    ///
+    /// The default color and other settings are kept.
+    pub fn clear() {
+        with_stack(|ctx| ctx.borrow_mut().frames.clear());
+    }
+
+    /// Captures the current stack so it can be reinstated with [`ColorContext::restore`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use term_ansi::ColorContext;
+    ///
+    /// let snapshot = ColorContext::snapshot();
+    /// ColorContext::push("\x1b[31m");
+    /// assert_eq!(ColorContext::depth(), snapshot.depth() + 1);
+    /// ColorContext::restore(snapshot);
+    /// assert_eq!(ColorContext::depth(), 0);
+    /// ```
+    pub fn snapshot() -> ContextSnapshot {
+        with_stack(|ctx| ContextSnapshot {
+            stack: ctx.borrow().clone(),
+        })
+    }
+
+    /// Replaces the current context with one captured by [`ColorContext::snapshot`].
+    pub fn restore(snapshot: ContextSnapshot) {
+        with_stack(|ctx| *ctx.borrow_mut() = snapshot.stack);
+    }
+
+    /// Sets the color re-emitted after top-level macro output, replacing the
+    /// built-in white (`\x1b[37m`).
+    ///
+    /// Pass [`codes::DEFAULT_FG`] to hand control back to the terminal's own
+    /// foreground, which suits light and custom color schemes. The setting
+    /// applies to the whole process, including threads and tasks that are
+    /// already running.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use term_ansi::{bold, codes, green, red, ColorContext, Colours};
+    ///
+    /// assert_eq!(ColorContext::default_color(), "\x1b[37m");
+    /// ColorContext::set_default(codes::DEFAULT_FG);
+    /// assert_eq!(red!("Error"), "\x1b[31mError\x1b[0m\x1b[39m");
+    /// let worker = std::thread::spawn(|| red!("Error")).join().unwrap();
+    /// assert_eq!(worker, "\x1b[31mError\x1b[0m\x1b[39m");
+    ///
+    /// ColorContext::set_default(Colours::Black);
+    /// assert_eq!(
+    ///     green!("{}", bold!("Hello")),
+    ///     "\x1b[32m\x1b[1mHello\x1b[0m\x1b[30m"
+    /// );
+    /// ColorContext::set_default(codes::WHITE);
+    /// ```
+    pub fn set_default(color: impl ColorCode) {
+        let code = color.color_code().into_owned();
+        *DEFAULT_COLOR.write().unwrap_or_else(|e| e.into_inner()) = Cow::Owned(code);
+    }
+
+    /// Returns the color re-emitted after top-level macro output.
+    pub fn default_color() -> String {
+        DEFAULT_COLOR
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .to_string()
+    }
+
+    /// Controls whether the outermost macro call re-emits the default context
+    /// color after its reset.
+    ///
+    /// Enabled by default. When disabled, top-level output ends with a plain
+    /// `\x1b[0m`; nested calls still restore their parent's color. Like
+    /// [`ColorContext::set_default`], the setting applies to the whole process.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use term_ansi::{bold, red, ColorContext};
+    ///
+    /// assert!(ColorContext::trailing_color());
+    /// ColorContext::set_trailing_color(false);
+    /// assert_eq!(red!("Error"), "\x1b[31mError\x1b[0m");
+    /// assert_eq!(
+    ///     red!("a {} b", bold!("c")),
+    ///     "\x1b[31ma \x1b[1mc\x1b[0m\x1b[31m b\x1b[0m"
+    /// );
+    /// let worker = std::thread::spawn(|| red!("Error")).join().unwrap();
+    /// assert_eq!(worker, "\x1b[31mError\x1b[0m");
+    /// ColorContext::set_trailing_color(true);
+    /// ```
+    pub fn set_trailing_color(enabled: bool) {
+        TRAILING_COLOR.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Returns whether the outermost macro call re-emits the default context color.
+    pub fn trailing_color() -> bool {
+        TRAILING_COLOR.load(Ordering::Relaxed)
+    }
+
+    /// Returns `content` between `color_code` and a reset, followed by the
+    /// code that restores the enclosing color, and leaves the innermost
+    /// nesting level, which `color_code` was pushed as.
+    #[doc(hidden)]
+    pub fn close(color_code: &str, content: &str) -> String {
+        let mut output = String::with_capacity(color_code.len() + content.len() + 16);
+        output.push_str(color_code);
+        output.push_str(content);
+        Self::close_into(&mut output);
+        output
+    }
+
+    /// Appends a reset to `output`, leaves the innermost nesting level and
+    /// appends the code that restores the enclosing color.
+    ///
+    /// Nested output at the very end of `output` has just reset and restored
+    /// this level's style, only for the reset appended here to clear it
+    /// again; that restore is dropped instead of adding a second reset, so
+    /// runs like `\x1b[0m\x1b[32m\x1b[0m\x1b[37m` do not pile up.
+    pub(crate) fn close_into(output: &mut String) {
+        let nested_end = format!("{}{}", codes::RESET, Self::current_color());
+        if output.ends_with(&nested_end) {
+            output.truncate(output.len() - nested_end.len() + codes::RESET.len());
+        } else {
+            output.push_str(codes::RESET);
+        }
+        Self::pop();
+        output.push_str(&Self::restore_code());
+    }
+
+    /// Returns the code written after a reset to restore the enclosing color.
+    #[doc(hidden)]
+    pub fn restore_code() -> String {
+        match with_stack(|ctx| ctx.borrow().frames.last().map(SgrState::to_code)) {
+            Some(code) => code,
+            None if Self::trailing_color() => Self::default_color(),
+            None => String::new(),
+        }
+    }
+}
@@ -0,0 +1,256 @@
+//! Conversions between RGB and additional color models.
+
+/// Converts a CMYK color into RGB components.
+///
+/// All components are in the range 0-1 and are clamped to it.
+pub fn cmyk_to_rgb(c: f64, m: f64, y: f64, k: f64) -> (u8, u8, u8) {
+    let c: f64 = c.clamp(0.0, 1.0);
+    let m: f64 = m.clamp(0.0, 1.0);
+    let y: f64 = y.clamp(0.0, 1.0);
+    let k: f64 = k.clamp(0.0, 1.0);
+
+    let r: u8 = (255.0 * (1.0 - c) * (1.0 - k)).round() as u8;
+    let g: u8 = (255.0 * (1.0 - m) * (1.0 - k)).round() as u8;
+    let b: u8 = (255.0 * (1.0 - y) * (1.0 - k)).round() as u8;
+
+    (r, g, b)
+}
+
+/// Reference white (D65) used by the CIE conversions.
+const D65_WHITE: (f64, f64, f64) = (0.95047, 1.0, 1.08883);
+
+/// Converts an sRGB channel value (0-1) into linear light by removing the gamma curve.
+///
+/// Blending, mixing and luminance calculations should be done on linear values.
+pub fn srgb_to_linear(c: f64) -> f64 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Converts a linear-light channel value (0-1) back into gamma-encoded sRGB.
+pub fn linear_to_srgb(c: f64) -> f64 {
+    if c <= 0.0031308 {
+        12.92 * c
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Converts RGB components into CIE XYZ (D65), with `y` in the range 0-1.
+pub fn rgb_to_xyz(r: u8, g: u8, b: u8) -> (f64, f64, f64) {
+    let r: f64 = srgb_to_linear(r as f64 / 255.0);
+    let g: f64 = srgb_to_linear(g as f64 / 255.0);
+    let b: f64 = srgb_to_linear(b as f64 / 255.0);
+
+    let x: f64 = 0.4124564 * r + 0.3575761 * g + 0.1804375 * b;
+    let y: f64 = 0.2126729 * r + 0.7151522 * g + 0.0721750 * b;
+    let z: f64 = 0.0193339 * r + 0.1191920 * g + 0.9503041 * b;
+
+    (x, y, z)
+}
+
+/// Converts CIE XYZ (D65) into RGB components. Out-of-gamut colors are clamped.
+pub fn xyz_to_rgb(x: f64, y: f64, z: f64) -> (u8, u8, u8) {
+    let r: f64 = 3.2404542 * x - 1.5371385 * y - 0.4985314 * z;
+    let g: f64 = -0.9692660 * x + 1.8760108 * y + 0.0415560 * z;
+    let b: f64 = 0.0556434 * x - 0.2040259 * y + 1.0572252 * z;
+
+    linear_to_srgb8(r, g, b)
+}
+
+/// Converts linear-light RGB (0-1) into clamped 8-bit sRGB.
+fn linear_to_srgb8(r: f64, g: f64, b: f64) -> (u8, u8, u8) {
+    let to_u8 = |c: f64| (linear_to_srgb(c).clamp(0.0, 1.0) * 255.0).round() as u8;
+    (to_u8(r), to_u8(g), to_u8(b))
+}
+
+/// Converts a CIELAB (D65) color into RGB components.
+///
+/// `l` is the lightness (0-100), `a` and `b` are the green-red and blue-yellow
+/// axes (roughly -128 to 127). Out-of-gamut colors are clamped.
+pub fn lab_to_rgb(l: f64, a: f64, b: f64) -> (u8, u8, u8) {
+    const DELTA: f64 = 6.0 / 29.0;
+    let f_inv = |t: f64| {
+        if t > DELTA {
+            t * t * t
+        } else {
+            3.0 * DELTA * DELTA * (t - 4.0 / 29.0)
+        }
+    };
+
+    let fy: f64 = (l + 16.0) / 116.0;
+    let fx: f64 = fy + a / 500.0;
+    let fz: f64 = fy - b / 200.0;
+
+    let (xn, yn, zn) = D65_WHITE;
+    xyz_to_rgb(xn * f_inv(fx), yn * f_inv(fy), zn * f_inv(fz))
+}
+
+/// Converts RGB components into CIELAB (D65), the inverse of [`lab_to_rgb`].
+pub fn rgb_to_lab(r: u8, g: u8, b: u8) -> (f64, f64, f64) {
+    const DELTA: f64 = 6.0 / 29.0;
+    let f = |t: f64| {
+        if t > DELTA * DELTA * DELTA {
+            t.cbrt()
+        } else {
+            t / (3.0 * DELTA * DELTA) + 4.0 / 29.0
+        }
+    };
+
+    let (x, y, z) = rgb_to_xyz(r, g, b);
+    let (xn, yn, zn) = D65_WHITE;
+    let (fx, fy, fz) = (f(x / xn), f(y / yn), f(z / zn));
+    (116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz))
+}
+
+/// Converts an OKLCH color into RGB components.
+///
+/// `l` is the perceptual lightness (0-1), `c` the chroma (0 to roughly 0.4) and
+/// `h` the hue angle in degrees. Out-of-gamut colors are clamped.
+pub fn oklch_to_rgb(l: f64, c: f64, h: f64) -> (u8, u8, u8) {
+    let h: f64 = h.to_radians();
+    oklab_to_rgb(l, c * h.cos(), c * h.sin())
+}
+
+/// Converts an OKLab color into RGB components. Out-of-gamut colors are clamped.
+pub(crate) fn oklab_to_rgb(l: f64, a: f64, b: f64) -> (u8, u8, u8) {
+    let l_: f64 = l + 0.3963377774 * a + 0.2158037573 * b;
+    let m_: f64 = l - 0.1055613458 * a - 0.0638541728 * b;
+    let s_: f64 = l - 0.0894841775 * a - 1.2914855480 * b;
+
+    let l3: f64 = l_ * l_ * l_;
+    let m3: f64 = m_ * m_ * m_;
+    let s3: f64 = s_ * s_ * s_;
+
+    linear_to_srgb8(
+        4.0767416621 * l3 - 3.3077115913 * m3 + 0.2309699292 * s3,
+        -1.2684380046 * l3 + 2.6097574011 * m3 - 0.3413193965 * s3,
+        -0.0041960863 * l3 - 0.7034186147 * m3 + 1.7076147010 * s3,
+    )
+}
+
+/// Converts RGB components into OKLab, as `(l, a, b)` with `l` in the range 0-1.
+pub(crate) fn rgb_to_oklab(r: u8, g: u8, b: u8) -> (f64, f64, f64) {
+    let r: f64 = srgb_to_linear(r as f64 / 255.0);
+    let g: f64 = srgb_to_linear(g as f64 / 255.0);
+    let b: f64 = srgb_to_linear(b as f64 / 255.0);
+
+    let l_: f64 = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+    let m_: f64 = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+    let s_: f64 = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+
+    (
+        0.2104542553 * l_ + 0.7936177850 * m_ - 0.0040720468 * s_,
+        1.9779984951 * l_ - 2.4285922050 * m_ + 0.4505937099 * s_,
+        0.0259040371 * l_ + 0.7827717662 * m_ - 0.8086757660 * s_,
+    )
+}
+
+/// Returns the fully saturated color for a hue angle, as RGB channels in 0-1.
+fn pure_hue(h: f64) -> (f64, f64, f64) {
+    let h: f64 = h.rem_euclid(360.0) / 60.0;
+    let x: f64 = 1.0 - (h % 2.0 - 1.0).abs();
+
+    match h as u8 {
+        0 => (1.0, x, 0.0),
+        1 => (x, 1.0, 0.0),
+        2 => (0.0, 1.0, x),
+        3 => (0.0, x, 1.0),
+        4 => (x, 0.0, 1.0),
+        _ => (1.0, 0.0, x),
+    }
+}
+
+/// Converts an HWB (hue, whiteness, blackness) color into RGB components.
+///
+/// `h` is the hue in degrees, `w` and `b` are in the range 0-1. Following CSS Color 4,
+/// whiteness and blackness are normalized when their sum exceeds 1, yielding a gray.
+pub fn hwb_to_rgb(h: f64, w: f64, b: f64) -> (u8, u8, u8) {
+    let w: f64 = w.clamp(0.0, 1.0);
+    let b: f64 = b.clamp(0.0, 1.0);
+    if w + b >= 1.0 {
+        let gray: u8 = (w / (w + b) * 255.0).round() as u8;
+        return (gray, gray, gray);
+    }
+
+    let (r, g, bl) = pure_hue(h);
+    let scale: f64 = 1.0 - w - b;
+    let to_u8 = |c: f64| ((c * scale + w) * 255.0).round() as u8;
+    (to_u8(r), to_u8(g), to_u8(bl))
+}
+
+/// sRGB matrix used by HSLuv to compute gamut boundaries.
+const HSLUV_M: [[f64; 3]; 3] = [
+    [3.240969941904521, -1.537383177570093, -0.498610760293],
+    [-0.96924363628087, 1.87596750150772, 0.041555057407175],
+    [0.055630079696993, -0.20397695888897, 1.056971514242878],
+];
+const HSLUV_REF_U: f64 = 0.19783000664283;
+const HSLUV_REF_V: f64 = 0.46831999493879;
+const HSLUV_KAPPA: f64 = 903.2962962;
+const HSLUV_EPSILON: f64 = 0.0088564516;
+
+/// Returns the largest in-gamut CIELUV chroma for a lightness and hue (in degrees).
+fn hsluv_max_chroma(l: f64, h: f64) -> f64 {
+    let sub1: f64 = (l + 16.0).powi(3) / 1_560_896.0;
+    let sub2: f64 = if sub1 > HSLUV_EPSILON {
+        sub1
+    } else {
+        l / HSLUV_KAPPA
+    };
+    let (sin_h, cos_h) = h.to_radians().sin_cos();
+
+    let mut max: f64 = f64::MAX;
+    for [m1, m2, m3] in HSLUV_M {
+        for t in [0.0, 1.0] {
+            let top1: f64 = (284_517.0 * m1 - 94_839.0 * m3) * sub2;
+            let top2: f64 =
+                (838_422.0 * m3 + 769_860.0 * m2 + 731_718.0 * m1) * l * sub2 - 769_860.0 * t * l;
+            let bottom: f64 = (632_260.0 * m3 - 126_452.0 * m2) * sub2 + 126_452.0 * t;
+
+            let slope: f64 = top1 / bottom;
+            let intercept: f64 = top2 / bottom;
+            let length: f64 = intercept / (sin_h - slope * cos_h);
+            if length >= 0.0 {
+                max = max.min(length);
+            }
+        }
+    }
+    max
+}
+
+/// Converts an HSLuv color into RGB components.
+///
+/// `h` is the hue in degrees, `s` and `l` are the saturation and lightness (0-100).
+/// Unlike HSL, equal lightness values look equally bright across all hues.
+pub fn hsluv_to_rgb(h: f64, s: f64, l: f64) -> (u8, u8, u8) {
+    let s: f64 = s.clamp(0.0, 100.0);
+    let l: f64 = l.clamp(0.0, 100.0);
+    if l > 99.9999999 {
+        return (255, 255, 255);
+    }
+    if l < 0.00000001 {
+        return (0, 0, 0);
+    }
+
+    let chroma: f64 = hsluv_max_chroma(l, h) / 100.0 * s;
+    let (sin_h, cos_h) = h.to_radians().sin_cos();
+    let u: f64 = chroma * cos_h;
+    let v: f64 = chroma * sin_h;
+
+    let var_u: f64 = u / (13.0 * l) + HSLUV_REF_U;
+    let var_v: f64 = v / (13.0 * l) + HSLUV_REF_V;
+    let y: f64 = if l <= 8.0 {
+        l / HSLUV_KAPPA
+    } else {
+        ((l + 16.0) / 116.0).powi(3)
+    };
+    let x: f64 = -(9.0 * y * var_u) / ((var_u - 4.0) * var_v - var_u * var_v);
+    let z: f64 = (9.0 * y - 15.0 * var_v * y - var_v * x) / (3.0 * var_v);
+
+    let [r, g, b] = HSLUV_M.map(|[m1, m2, m3]| m1 * x + m2 * y + m3 * z);
+    linear_to_srgb8(r, g, b)
+}
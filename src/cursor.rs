@@ -0,0 +1,95 @@
+//! Escape sequences that move, hide and show the cursor, for redrawing
+//! output in place.
+//!
+//! Each function returns the sequence to print. Relative moves by 0 return
+//! an empty string, since terminals would read a count of 0 as 1. Unlike the
+//! color macros these do not check [`colors_enabled`](crate::colors_enabled),
+//! since they are not styling; check [`Stream::is_terminal`](crate::Stream::is_terminal)
+//! before redrawing, as output sent to a pipe or file cannot be moved around in.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::io::Write;
+//! use term_ansi::cursor;
+//!
+//! print!("{}", cursor::hide());
+//! for done in 0..=100 {
+//!     print!("{}{:>3}%", cursor::move_to_column(1), done);
+//!     std::io::stdout().flush().unwrap();
+//! }
+//! println!("{}", cursor::show());
+//! ```
+
+/// Moves the cursor up `n` rows, stopping at the top of the screen.
+pub fn move_up(n: u16) -> String {
+    csi(n, 'A')
+}
+
+/// Moves the cursor down `n` rows, stopping at the bottom of the screen.
+pub fn move_down(n: u16) -> String {
+    csi(n, 'B')
+}
+
+/// Moves the cursor right `n` columns, stopping at the right edge.
+pub fn move_right(n: u16) -> String {
+    csi(n, 'C')
+}
+
+/// Moves the cursor left `n` columns, stopping at the left edge.
+pub fn move_left(n: u16) -> String {
+    csi(n, 'D')
+}
+
+/// Moves the cursor to the start of the line `n` rows down.
+pub fn next_line(n: u16) -> String {
+    csi(n, 'E')
+}
+
+/// Moves the cursor to the start of the line `n` rows up, such as the first
+/// line of a block of output about to be redrawn.
+pub fn prev_line(n: u16) -> String {
+    csi(n, 'F')
+}
+
+/// Moves the cursor to `col` in the current row, counting from 1.
+pub fn move_to_column(col: u16) -> String {
+    format!("\x1b[{}G", col.max(1))
+}
+
+/// Moves the cursor to `row` and `col`, counting from 1 at the top left
+/// corner like the terminal itself.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::cursor;
+///
+/// assert_eq!(cursor::move_to(1, 1), "\x1b[1;1H");
+/// ```
+pub fn move_to(row: u16, col: u16) -> String {
+    format!("\x1b[{};{}H", row.max(1), col.max(1))
+}
+
+/// Hides the cursor, so it does not flicker across a line being redrawn.
+///
+/// Print [`show`] before the program exits, or the cursor stays hidden in
+/// the shell.
+pub fn hide() -> &'static str {
+    "\x1b[?25l"
+}
+
+/// Shows the cursor again after [`hide`].
+pub fn show() -> &'static str {
+    "\x1b[?25h"
+}
+
+/// Returns the CSI sequence with count `n` and final byte `op`, or nothing
+/// when `n` is 0.
+fn csi(n: u16, op: char) -> String {
+    if n == 0 {
+        String::new()
+    } else {
+        format!("\x1b[{}{}", n, op)
+    }
+}
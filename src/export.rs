@@ -0,0 +1,362 @@
+//! Conversion of styled text into other markup formats.
+
+use std::fmt::Write;
+
+use crate::quantize::{color_to_rgb, downgrade};
+use crate::text::char_width;
+use crate::{basic_palette, AnsiParser, Attribute, Color, ColorSupport, Colours, Style};
+
+/// Returns `color` as a CSS hex color.
+fn css_color(color: Color) -> String {
+    let (r, g, b) = color_to_rgb(color);
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// Returns the CSS `text-decoration` lines drawn by `style`.
+fn decoration_lines(style: Style) -> Vec<&'static str> {
+    let underline = style.has(Attribute::Underline) || style.has(Attribute::DoubleUnderline);
+    [
+        (underline, "underline"),
+        (style.has(Attribute::Overline), "overline"),
+        (style.has(Attribute::Strikethrough), "line-through"),
+    ]
+    .into_iter()
+    .filter(|&(set, _)| set)
+    .map(|(_, line)| line)
+    .collect()
+}
+
+/// Returns the inline CSS declarations for `style`, or an empty string for a plain style.
+fn css(style: Style) -> String {
+    let (mut fg, mut bg) = (style.foreground(), style.background());
+    if style.has(Attribute::Reverse) {
+        (fg, bg) = (bg, fg);
+    }
+
+    let mut declarations: Vec<String> = Vec::new();
+    if let Some(fg) = fg {
+        declarations.push(format!("color:{}", css_color(fg)));
+    }
+    if let Some(bg) = bg {
+        declarations.push(format!("background-color:{}", css_color(bg)));
+    }
+    if style.has(Attribute::Bold) {
+        declarations.push(String::from("font-weight:bold"));
+    }
+    if style.has(Attribute::Dim) {
+        declarations.push(String::from("opacity:0.6"));
+    }
+    if style.has(Attribute::Italic) {
+        declarations.push(String::from("font-style:italic"));
+    }
+    let lines = decoration_lines(style);
+    if !lines.is_empty() {
+        declarations.push(format!("text-decoration:{}", lines.join(" ")));
+    }
+    if style.has(Attribute::DoubleUnderline) {
+        declarations.push(String::from("text-decoration-style:double"));
+    }
+    if style.has(Attribute::Conceal) {
+        declarations.push(String::from("visibility:hidden"));
+    }
+    declarations.join(";")
+}
+
+/// Appends `text` to `output` with the characters special to HTML and XML escaped.
+fn escape_xml(text: &str, output: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' => output.push_str("&quot;"),
+            '\'' => output.push_str("&#39;"),
+            c => output.push(c),
+        }
+    }
+}
+
+/// Converts text containing escape codes into HTML, with each styled run in a
+/// `<span>` carrying inline CSS.
+///
+/// Basic, 256-color and RGB colors all become hex colors; the 16 basic colors
+/// follow [`basic_palette`](crate::basic_palette). Bold, dim, italic, the
+/// underline variants, strikethrough, overline and concealed text map to
+/// their CSS counterparts, and reverse video swaps the colors that are set.
+/// Text is HTML-escaped and line breaks are kept, so wrap the result in a
+/// `<pre>` element. Escape sequences other than colors and styles are dropped.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{ansi_to_html, bold, red};
+///
+/// let html = ansi_to_html(&format!("{} <b>", red!("{} failed", bold!("1"))));
+/// assert_eq!(
+///     html,
+///     "<span style=\"color:#cd0000;font-weight:bold\">1</span>\
+///      <span style=\"color:#cd0000\"> failed</span>\
+///      <span style=\"color:#e5e5e5\"> &lt;b&gt;</span>"
+/// );
+/// ```
+pub fn ansi_to_html(text: &str) -> String {
+    let mut html = String::with_capacity(text.len() * 2);
+    for (style, span) in AnsiParser::new(text) {
+        let css = css(style);
+        if css.is_empty() {
+            escape_xml(span, &mut html);
+        } else {
+            let _ = write!(html, "<span style=\"{}\">", css);
+            escape_xml(span, &mut html);
+            html.push_str("</span>");
+        }
+    }
+    html
+}
+
+/// Returns the mIRC color number closest to `color`.
+fn irc_color(color: Color) -> u8 {
+    // mIRC numbers for black, red, green, yellow, blue, magenta, cyan and
+    // white, then for their bright variants.
+    const BASIC: [u8; 8] = [1, 5, 3, 7, 2, 6, 10, 15];
+    const BRIGHT: [u8; 8] = [14, 4, 9, 8, 12, 13, 11, 0];
+    match downgrade(color, ColorSupport::Basic16) {
+        Color::BrightBasic(colour) => BRIGHT[colour as usize],
+        Color::Basic(colour) => BASIC[colour as usize],
+        _ => 99,
+    }
+}
+
+/// Converts text containing escape codes into mIRC formatting codes, for
+/// relaying terminal output to IRC.
+///
+/// Colors are mapped to the 16 standard mIRC colors; bold, italic,
+/// underline, strikethrough and reverse video keep their meaning. Styles
+/// without an IRC equivalent, such as dim, are dropped, as are escape
+/// sequences other than colors and styles.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{ansi_to_irc, bold, red};
+///
+/// assert_eq!(
+///     ansi_to_irc(&format!("{} ok", red!("{} failed", bold!("1")))),
+///     "\x0305\x021\x0f\x0305 failed\x0f\x0315 ok\x0f"
+/// );
+/// ```
+pub fn ansi_to_irc(text: &str) -> String {
+    let mut irc = String::with_capacity(text.len());
+    let mut styled = false;
+    for (style, span) in AnsiParser::new(text) {
+        if styled {
+            irc.push('\x0f');
+        }
+        match (style.foreground(), style.background()) {
+            (Some(fg), Some(bg)) => {
+                let _ = write!(irc, "\x03{:02},{:02}", irc_color(fg), irc_color(bg));
+            }
+            (Some(fg), None) => {
+                let _ = write!(irc, "\x03{:02}", irc_color(fg));
+                // A following `,5` would be read as a background color.
+                if span.starts_with(',') {
+                    irc.push_str("\x02\x02");
+                }
+            }
+            (None, Some(bg)) => {
+                let _ = write!(irc, "\x0399,{:02}", irc_color(bg));
+            }
+            (None, None) => {}
+        }
+        let underline = style.has(Attribute::Underline) || style.has(Attribute::DoubleUnderline);
+        for (set, code) in [
+            (style.has(Attribute::Bold), '\x02'),
+            (style.has(Attribute::Italic), '\x1d'),
+            (underline, '\x1f'),
+            (style.has(Attribute::Strikethrough), '\x1e'),
+            (style.has(Attribute::Reverse), '\x16'),
+        ] {
+            if set {
+                irc.push(code);
+            }
+        }
+        irc.push_str(span);
+        styled = !style.is_plain();
+    }
+    if styled {
+        irc.push('\x0f');
+    }
+    irc
+}
+
+/// Converts text containing escape codes into BBCode, for relaying terminal
+/// output to forums.
+///
+/// Foreground colors become `[color=#rrggbb]` tags, following
+/// [`basic_palette`](crate::basic_palette) for the 16 basic colors, and bold,
+/// italic, underline and strikethrough become `[b]`, `[i]`, `[u]` and `[s]`.
+/// BBCode has no common tag for backgrounds, so they are dropped along with
+/// other styles and escape sequences.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{ansi_to_bbcode, bold, red};
+///
+/// assert_eq!(
+///     ansi_to_bbcode(&format!("{} failed", red!("{}", bold!("1")))),
+///     "[color=#cd0000][b]1[/b][/color][color=#e5e5e5] failed[/color]"
+/// );
+/// ```
+pub fn ansi_to_bbcode(text: &str) -> String {
+    let mut bbcode = String::with_capacity(text.len());
+    for (style, span) in AnsiParser::new(text) {
+        let underline = style.has(Attribute::Underline) || style.has(Attribute::DoubleUnderline);
+        let tags: Vec<&str> = [
+            (style.has(Attribute::Bold), "b"),
+            (style.has(Attribute::Italic), "i"),
+            (underline, "u"),
+            (style.has(Attribute::Strikethrough), "s"),
+        ]
+        .into_iter()
+        .filter(|&(set, _)| set)
+        .map(|(_, tag)| tag)
+        .collect();
+
+        if let Some(fg) = style.foreground() {
+            let _ = write!(bbcode, "[color={}]", css_color(fg));
+        }
+        for tag in &tags {
+            let _ = write!(bbcode, "[{}]", tag);
+        }
+        bbcode.push_str(span);
+        for tag in tags.iter().rev() {
+            let _ = write!(bbcode, "[/{}]", tag);
+        }
+        if style.foreground().is_some() {
+            bbcode.push_str("[/color]");
+        }
+    }
+    bbcode
+}
+
+/// The width of one terminal cell in an SVG rendering, in pixels.
+const SVG_CELL_WIDTH: usize = 9;
+/// The height of one line in an SVG rendering, in pixels.
+const SVG_LINE_HEIGHT: usize = 20;
+/// The font size of an SVG rendering, in pixels, for a cell width of about 0.6em.
+const SVG_FONT_SIZE: usize = 15;
+
+/// Renders text containing escape codes as an SVG image of a terminal.
+///
+/// Every character sits on a monospace grid of 9×20 pixel cells, with wide
+/// characters taking two when the `unicode-width` feature is enabled.
+/// Backgrounds are drawn as rectangles behind the text, and the terminal's
+/// own colors are black and white from [`basic_palette`](crate::basic_palette).
+/// Bold, dim, italic, underline, strikethrough, overline, reverse video and
+/// concealed text are rendered; other escape sequences are dropped. Expand
+/// tabs beforehand, as each counts as one cell.
+///
+/// Useful for generating README screenshots of colored output in tests.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{ansi_to_svg, bg_blue, red};
+///
+/// let svg = ansi_to_svg(&format!("{}\n{}", red!("error"), bg_blue!(" ok ")));
+/// assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"45\" height=\"40\""));
+/// assert!(svg.contains("<rect x=\"0\" y=\"20\" width=\"36\" height=\"20\" fill=\"#0000ee\"/>"));
+/// assert!(svg.contains("<text x=\"0\" y=\"15\" fill=\"#cd0000\">error</text>"));
+/// // std::fs::write("screenshot.svg", svg).unwrap();
+/// ```
+pub fn ansi_to_svg(text: &str) -> String {
+    let palette = basic_palette();
+    let default_fg = Color::Basic(Colours::White);
+    let default_bg = Color::Basic(Colours::Black);
+
+    let mut shapes = String::new();
+    let mut texts = String::new();
+    let (mut row, mut column, mut columns) = (0, 0, 0);
+    for (style, span) in AnsiParser::new(text) {
+        for (i, line) in span.split('\n').enumerate() {
+            if i > 0 {
+                row += 1;
+                column = 0;
+            }
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            let width: usize = line.chars().map(char_width).sum();
+            if width == 0 {
+                continue;
+            }
+
+            let x = column * SVG_CELL_WIDTH;
+            let y = row * SVG_LINE_HEIGHT;
+            let (mut fg, mut bg) = (style.foreground(), style.background());
+            if style.has(Attribute::Reverse) {
+                (fg, bg) = (
+                    Some(bg.unwrap_or(default_bg)),
+                    Some(fg.unwrap_or(default_fg)),
+                );
+            }
+            if let Some(bg) = bg {
+                let _ = writeln!(
+                    shapes,
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                    x,
+                    y,
+                    width * SVG_CELL_WIDTH,
+                    SVG_LINE_HEIGHT,
+                    css_color(bg)
+                );
+            }
+            if !style.has(Attribute::Conceal) && !line.trim().is_empty() {
+                let _ = write!(
+                    texts,
+                    "<text x=\"{}\" y=\"{}\" fill=\"{}\"{}>",
+                    x,
+                    y + SVG_FONT_SIZE,
+                    css_color(fg.unwrap_or(default_fg)),
+                    svg_attributes(style)
+                );
+                escape_xml(line, &mut texts);
+                texts.push_str("</text>\n");
+            }
+            column += width;
+            columns = columns.max(column);
+        }
+    }
+    let rows = if column == 0 { row } else { row + 1 };
+
+    let (width, height) = (columns * SVG_CELL_WIDTH, rows * SVG_LINE_HEIGHT);
+    let (r, g, b) = palette[0];
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" \
+         viewBox=\"0 0 {w} {h}\" font-family=\"monospace\" font-size=\"{size}\" \
+         xml:space=\"preserve\">\n\
+         <rect width=\"100%\" height=\"100%\" fill=\"#{r:02x}{g:02x}{b:02x}\"/>\n\
+         {shapes}{texts}</svg>\n",
+        w = width,
+        h = height,
+        size = SVG_FONT_SIZE,
+    )
+}
+
+/// Returns the SVG presentation attributes for the text styles of `style`.
+fn svg_attributes(style: Style) -> String {
+    let mut attributes = String::new();
+    if style.has(Attribute::Bold) {
+        attributes.push_str(" font-weight=\"bold\"");
+    }
+    if style.has(Attribute::Italic) {
+        attributes.push_str(" font-style=\"italic\"");
+    }
+    if style.has(Attribute::Dim) {
+        attributes.push_str(" opacity=\"0.6\"");
+    }
+    let lines = decoration_lines(style);
+    if !lines.is_empty() {
+        let _ = write!(attributes, " text-decoration=\"{}\"", lines.join(" "));
+    }
+    attributes
+}
@@ -0,0 +1,75 @@
+//! Picking colors for values that have none of their own, by hash or at random.
+
+use crate::hsluv_to_rgb;
+
+/// Returns a stable color for `key`, for telling apart usernames, module
+/// names or hosts in log output.
+///
+/// The key is hashed with FNV-1a, which does not change between runs,
+/// platforms or crate versions, and the hash picks a hue. Saturation and
+/// lightness are fixed in HSLuv, so every key gets an equally readable color.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{color_for, rgb};
+///
+/// for module in ["http", "db", "cache", "http"] {
+///     let (r, g, b) = color_for(module);
+///     println!("{} request handled", rgb!(r, g, b, "[{}]", module));
+/// }
+/// assert_eq!(color_for("db"), color_for("db"));
+/// ```
+pub fn color_for(key: &str) -> (u8, u8, u8) {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in key.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hsluv_to_rgb((hash % 360) as f64, 80.0, 65.0)
+}
+
+/// Returns a uniformly random color.
+///
+/// Available with the `rand` feature. Any RGB value can come out, including
+/// near-black and near-white; see [`random_pleasant_color`] for colors that
+/// are always readable.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{random_color, rgb};
+///
+/// let (r, g, b) = random_color();
+/// println!("{}", rgb!(r, g, b, "surprise"));
+/// ```
+#[cfg(feature = "rand")]
+pub fn random_color() -> (u8, u8, u8) {
+    rand::random()
+}
+
+/// Returns a random color with a moderate saturation and lightness.
+///
+/// Available with the `rand` feature. The hue is picked at random while
+/// saturation and lightness stay in a range that reads well on dark and
+/// light backgrounds, which suits per-run accent colors and demos.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{random_pleasant_color, rgb};
+///
+/// let (r, g, b) = random_pleasant_color();
+/// println!("{}", rgb!(r, g, b, "Welcome back!"));
+/// ```
+#[cfg(feature = "rand")]
+pub fn random_pleasant_color() -> (u8, u8, u8) {
+    use rand::Rng;
+
+    let mut rng = rand::thread_rng();
+    hsluv_to_rgb(
+        rng.gen_range(0.0..360.0),
+        rng.gen_range(60.0..=90.0),
+        rng.gen_range(55.0..=70.0),
+    )
+}
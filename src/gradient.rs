@@ -0,0 +1,134 @@
+//! Colors that change from one character to the next.
+
+use crate::text::escape_len;
+use crate::{bg_rgb_code, colors_enabled, lerp_color, rgb_code, strip_ansi_cow};
+
+/// Colors every visible character of `text` along a straight line from
+/// `start` to `end`.
+///
+/// The first character gets `start` and the last gets `end`. Escape sequences
+/// already in `text`, such as nested macro output, are kept, and the gradient
+/// resumes on the next character. Whitespace advances the gradient without
+/// a code of its own. No reset is appended; see [`gradient!`](crate::gradient)
+/// for a version that closes and nests like the other macros.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{gradient, set_color_support, ColorSupport};
+///
+/// set_color_support(Some(ColorSupport::TrueColor));
+/// assert_eq!(
+///     gradient("ab", (255, 0, 0), (0, 0, 255)),
+///     "\x1b[38;2;255;0;0ma\x1b[38;2;0;0;255mb"
+/// );
+/// ```
+pub fn gradient(text: &str, start: (u8, u8, u8), end: (u8, u8, u8)) -> String {
+    paint_gradient(text, start, end, false, false)
+}
+
+/// Colors the background of every visible character of `text` along a
+/// straight line from `start` to `end`.
+///
+/// The counterpart of [`gradient`]: the foreground is left alone, so text
+/// stays in whatever color encloses it. Spaces get a background too, which
+/// makes a run of them a solid bar. See [`bg_gradient!`](crate::bg_gradient)
+/// for the macro form.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{bg_gradient, set_color_support, ColorSupport};
+///
+/// set_color_support(Some(ColorSupport::TrueColor));
+/// assert_eq!(
+///     bg_gradient(" b", (255, 0, 0), (0, 0, 255)),
+///     "\x1b[48;2;255;0;0m \x1b[48;2;0;0;255mb"
+/// );
+/// ```
+pub fn bg_gradient(text: &str, start: (u8, u8, u8), end: (u8, u8, u8)) -> String {
+    paint_gradient(text, start, end, true, false)
+}
+
+/// Colors every line of `text` along a straight line from `start` on the
+/// first line to `end` on the last.
+///
+/// Each line starts with its own color code, so the result can be printed or
+/// paged one line at a time. Like [`gradient`], escape sequences in `text`
+/// are kept and whitespace gets no code. See [`vgradient!`](crate::vgradient)
+/// for the macro form.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{set_color_support, vgradient, ColorSupport};
+///
+/// set_color_support(Some(ColorSupport::TrueColor));
+/// assert_eq!(
+///     vgradient("##\n##", (255, 0, 0), (0, 0, 255)),
+///     "\x1b[38;2;255;0;0m##\n\x1b[38;2;0;0;255m##"
+/// );
+/// ```
+pub fn vgradient(text: &str, start: (u8, u8, u8), end: (u8, u8, u8)) -> String {
+    paint_gradient(text, start, end, false, true)
+}
+
+fn paint_gradient(
+    text: &str,
+    start: (u8, u8, u8),
+    end: (u8, u8, u8),
+    background: bool,
+    vertical: bool,
+) -> String {
+    if !colors_enabled() {
+        return text.to_string();
+    }
+
+    let plain = strip_ansi_cow(text);
+    let count = if vertical {
+        plain.lines().count()
+    } else {
+        plain.chars().count()
+    };
+    let steps = count.saturating_sub(1).max(1);
+    let mut output = String::with_capacity(text.len() * 8);
+    let mut last_code = String::new();
+    let mut index = 0;
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if c == '\x1b' {
+            let (sequence, tail) = rest.split_at(escape_len(rest));
+            output.push_str(sequence);
+            last_code.clear();
+            rest = tail;
+            continue;
+        }
+
+        let blank = if background {
+            c.is_control()
+        } else {
+            c.is_whitespace()
+        };
+        if !blank {
+            let (r, g, b) = lerp_color(start, end, index as f64 / steps as f64);
+            let code = if background {
+                bg_rgb_code(r, g, b)
+            } else {
+                rgb_code(r, g, b)
+            };
+            if code != last_code {
+                output.push_str(&code);
+                last_code = code;
+            }
+        }
+        output.push(c);
+        if !vertical {
+            index += 1;
+        } else if c == '\n' {
+            index += 1;
+            last_code.clear();
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    output
+}
@@ -0,0 +1,136 @@
+//! Clickable links in terminal output, written as OSC 8 sequences.
+
+use std::borrow::Cow;
+use std::ffi::OsString;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+use crate::colors_enabled;
+
+const SUPPORT_AUTO: u8 = 0;
+const SUPPORT_ON: u8 = 1;
+const SUPPORT_OFF: u8 = 2;
+
+static HYPERLINKS: AtomicU8 = AtomicU8::new(SUPPORT_AUTO);
+
+/// Returns `text` as a link to `url`, or `text (url)` where links cannot be shown.
+///
+/// The link is an OSC 8 sequence ended with the string terminator `ESC \`,
+/// which terminals that support it render as clickable text. The fallback is
+/// used while [`colors_enabled`] is false or [`hyperlinks_supported`] reports
+/// no support; it is just `url` when `text` is empty or the URL itself.
+/// Characters a URL may not contain in the sequence, such as spaces and
+/// non-ASCII letters, are percent-encoded. See [`hyperlink!`](crate::hyperlink!)
+/// for the macro form.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{hyperlink, set_hyperlinks_supported};
+///
+/// set_hyperlinks_supported(Some(true));
+/// assert_eq!(
+///     hyperlink("https://example.com", "docs"),
+///     "\x1b]8;;https://example.com\x1b\\docs\x1b]8;;\x1b\\"
+/// );
+/// set_hyperlinks_supported(Some(false));
+/// assert_eq!(hyperlink("https://example.com", "docs"), "docs (https://example.com)");
+/// ```
+pub fn hyperlink(url: &str, text: &str) -> String {
+    if colors_enabled() && hyperlinks_supported() {
+        format!(
+            "\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\",
+            encode_url(url),
+            if text.is_empty() { url } else { text }
+        )
+    } else if text.is_empty() || text == url {
+        url.to_string()
+    } else {
+        format!("{} ({})", text, url)
+    }
+}
+
+/// Percent-encodes the bytes of `url` outside printable ASCII, which OSC 8
+/// does not allow.
+pub(crate) fn encode_url(url: &str) -> Cow<'_, str> {
+    if url.bytes().all(|b| (0x21..0x7f).contains(&b)) {
+        return Cow::Borrowed(url);
+    }
+    let mut encoded = String::with_capacity(url.len() * 3);
+    for b in url.bytes() {
+        if (0x21..0x7f).contains(&b) {
+            encoded.push(char::from(b));
+        } else {
+            encoded.push_str(&format!("%{:02X}", b));
+        }
+    }
+    Cow::Owned(encoded)
+}
+
+/// Overrides whether terminal output supports hyperlinks.
+///
+/// `None` restores detection with [`detect_hyperlink_support`].
+pub fn set_hyperlinks_supported(supported: Option<bool>) {
+    let value = match supported {
+        None => SUPPORT_AUTO,
+        Some(true) => SUPPORT_ON,
+        Some(false) => SUPPORT_OFF,
+    };
+    HYPERLINKS.store(value, Ordering::Relaxed);
+}
+
+/// Returns whether [`hyperlink`] writes links rather than the fallback.
+///
+/// This is the value set with [`set_hyperlinks_supported`], or else the result
+/// of [`detect_hyperlink_support`], which is computed once and cached.
+pub fn hyperlinks_supported() -> bool {
+    match HYPERLINKS.load(Ordering::Relaxed) {
+        SUPPORT_ON => true,
+        SUPPORT_OFF => false,
+        _ => {
+            static DETECTED: OnceLock<bool> = OnceLock::new();
+            *DETECTED.get_or_init(detect_hyperlink_support)
+        }
+    }
+}
+
+/// Detects whether the terminal renders OSC 8 hyperlinks from environment variables.
+///
+/// `FORCE_HYPERLINK` wins when set: `0` disables links and any other value
+/// enables them. Otherwise links are assumed for terminals known to support
+/// them: iTerm2, WezTerm, VS Code, Ghostty, Hyper, Windows Terminal, Konsole,
+/// DomTerm, kitty, Alacritty, foot and VTE-based terminals from version 0.50.
+/// Anything else, including `TERM=dumb`, gets the plain fallback.
+pub fn detect_hyperlink_support() -> bool {
+    env_hyperlink_support(|name| std::env::var_os(name))
+}
+
+/// Resolves hyperlink support from environment variables looked up with `var`.
+pub(crate) fn env_hyperlink_support(var: impl Fn(&str) -> Option<OsString>) -> bool {
+    let get = |name: &str| {
+        var(name)
+            .map(|value| value.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    };
+
+    if let Some(force) = var("FORCE_HYPERLINK") {
+        return force != "0";
+    }
+
+    let term = get("TERM");
+    if term == "dumb" {
+        return false;
+    }
+    let term_program = get("TERM_PROGRAM");
+    let vte_version: u32 = get("VTE_VERSION").parse().unwrap_or(0);
+    matches!(
+        term_program.as_str(),
+        "iTerm.app" | "WezTerm" | "vscode" | "ghostty" | "Hyper"
+    ) || ["kitty", "alacritty", "foot", "ghostty", "wezterm"]
+        .iter()
+        .any(|name| term.contains(name))
+        || vte_version >= 5000
+        || ["WT_SESSION", "KONSOLE_VERSION", "DOMTERM"]
+            .iter()
+            .any(|name| var(name).is_some())
+}
@@ -7,11 +7,71 @@
 //! ## Features
 //!
 //! - Apply predefined colors: Red, Green, Blue, White, Black, Yellow, Magenta, Cyan
+//! - Apply high-intensity (bright) variants of the predefined colors
 //! - Apply background colors
-//! - Apply text styles: Bold, Italic, Underline
+//! - Apply text styles: Bold, Dim, Italic, Underline (double, curly, dotted, dashed), Strikethrough, Blink, Reverse, Conceal, Overline
 //! - Custom RGB color support for text and background
-//! - HSL and HSV color support for text and background
+//! - 256-color palette support for text and background
+//! - HSL, HSV, HWB, HSLuv, CMYK, CIELAB and OKLCH color support for text and background
+//! - Hex color string and CSS color name support for text and background
+//! - Color math helpers: RGB to HSL/HSV, RGB to the 256-color and 16-color palettes, CIE XYZ, CIELAB and linear-light sRGB conversions
+//! - Per-character and per-line gradients (`gradient!`, `bg_gradient!`, `vgradient!`) and color interpolation (`lerp_color`, `lerp_color_oklab`)
+//! - Color adjustments: `mix`, `lighten`, `darken`, `saturate`, `desaturate`, `invert` and `complement`
+//! - Shade and tint ladders from a single base color with `shades` and `tints`
+//! - Palette harmonies from a seed color: `analogous`, `triadic`, `tetradic` and `split_complement`
+//! - Stable per-key colors with `color_for`, e.g. one color per username in logs
+//! - Random colors with the `rand` feature: `random_color` and `random_pleasant_color`
+//! - Perceptual color difference (`delta_e`, CIEDE2000) and the nearest CSS color name (`nearest_named`)
+//! - WCAG contrast checks: `contrast_ratio`, `meets_aa`, `meets_aaa` and `readable_fg`
+//! - Color-blindness previews with `simulate` (protanopia, deuteranopia, tritanopia)
+//! - Color-blind-safe palettes: `OKABE_ITO`, `TOL_BRIGHT`, `TOL_VIBRANT` and `TOL_MUTED`
+//! - `Color` and `Colours` types for choosing colors at runtime
+//! - `color!(Colours::Red, "text {}", x)` and `bg!(Colours::Blue, ...)` for foreground and background colors chosen at runtime, e.g. from config or a log level
+//! - `Style` builder for combining colors and attributes at runtime
+//! - `style!(fg: red, bg: black, bold, underline; "text {}", x)` for combined styles in a single escape sequence
+//! - `sgr!([1, 4, 38, 5, 208], "text")` escape hatch for raw SGR parameters, with the same nesting as the other macros
+//! - `cprint!`/`cprintln!` and `ceprint!`/`ceprintln!` for printing styled text straight to the locked stdout or stderr, without intermediate `String`s
+//! - `cwrite!`/`cwriteln!` for styled output to any `fmt::Write` or `io::Write` destination: string buffers, files and sockets
+//! - `Theme` mapping semantic roles (error, warning, accent, muted, ...) to styles, with a global registry (`set_global_theme`, `theme`)
+//! - `LevelStyles` mapping log levels to styles for any logging frontend, with defaults or taken from a theme
+//! - Theme overrides from the environment (`TERM_ANSI_THEME`, `MYAPP_COLOR_ERROR=#ff5555`) and `Style::parse` for specs such as `"bold red on #202020"`
+//! - Light/dark background detection via an OSC 11 query (`terminal_is_dark`), with matching `Theme::dark`/`Theme::light` presets
+//! - Terminal palette query via OSC 4 (`terminal_palette`), usable for downgrading with `set_basic_palette`
+//! - Terminal palette redefinition via OSC 4 (`set_palette_entry`, `set_palette`, `reset_palette`)
+//! - Semantic message macros (`success!`, `failure!`, `warn_msg!`, `info_msg!`) styled by a global `Theme`
+//! - Allocation-free `Styled` wrapper for any `Display` value
+//! - `paint` and `paint_bg` functions for non-macro contexts
+//! - `Colorize` extension trait for method-call syntax: `"error".red().bold()`
+//! - `strip_ansi` for removing escape sequences from text
+//! - `slice_ansi` for cutting colored text by column
+//! - `AnsiWriter` for adapting escape sequences to a color depth as bytes are written: downgraded for 256-color or 16-color terminals, or stripped for pipes and log files
+//! - `TeeWriter` for writing styled output to the console and a plain copy to a log file in one pass
+//! - `ansi_to_html` for showing colored output in CI systems and web dashboards
+//! - `ansi_to_irc` and `ansi_to_bbcode` for relaying colored output to chat systems and forums
+//! - `ansi_to_svg` for rendering colored output as an SVG image, e.g. README screenshots generated in tests
+//! - `AnsiParser` for turning colored text back into styled spans
+//! - `AnsiString` rich text that renders escape codes only when displayed
+//! - `optimize_ansi` for compacting redundant escape sequences
+//! - `linewise!` for re-applying styles on every line, for pagers and CI logs
+//! - `markup!` and `colorize_markup` for HTML-like inline styling: `"<red><b>error</b></red>"`
+//! - `colorize_template` for brace-tag templates from localization catalogs: `"{bold}{green}OK{/}"`
+//! - Compile-time checked markup with the `macros` feature: `styled!("<red>{count}</red> errors")` expands to `format_args!`
+//! - `visible_width` for aligning colored text, Unicode-aware with the `unicode-width` feature
 //! - Nested color support
+//! - Global on/off switch that honors `NO_COLOR`, `CLICOLOR`, `CLICOLOR_FORCE` and `FORCE_COLOR`
+//! - Per-stream color decisions with terminal detection (`tty` feature, on by default)
+//! - Terminal color depth detection: truecolor, 256 or 16 colors
+//! - RGB output automatically downgraded to the 256-color or 16-color palette when needed
+//! - Task-local color context for async code with the `tokio` feature
+//! - A `cursor` module with `move_up`, `move_to`, `hide`, `show` and other cursor sequences for in-place updates
+//! - Alternate screen and saved cursor position, with `AltScreen` and `SavedCursor` guards that undo them on drop or panic
+//! - Terminal window and tab titles with `set_title!`, restored afterwards by the `SavedTitle` guard
+//! - Clickable OSC 8 hyperlinks with a plain `text (url)` fallback for terminals without them
+//! - `ProgressBar` rendering bars to a string with custom glyphs and gradient or threshold coloring
+//! - `Spinner` frames (dots, line, arc or custom) with per-frame color cycling, driven by the caller's timer
+//! - `Table` builder that aligns styled cells by visible width, with colorable single, rounded, double, heavy or ASCII borders
+//! - `boxed!` frames around multi-line styled text, sized by visible width
+//! - A `tracing-subscriber` event formatter colored by the theme, `ThemedFormat`, with the `tracing` feature
 //!
 //! ## Usage
 //!
@@ -51,56 +111,156 @@
 //!
 //! ### Text Colors
 //! - `red!`, `green!`, `blue!`, `white!`, `black!`, `yellow!`, `magenta!`, `cyan!`
+//! - `bright_red!`, `bright_green!`, `bright_blue!`, `bright_white!`, `bright_black!`, `bright_yellow!`, `bright_magenta!`, `bright_cyan!`
+//! - `color!`: Apply a `Colours` value chosen at runtime, e.g. `color!(Colours::Red, "text")`.
 //!
 //! ### Background Colors
 //! - `bg_red!`, `bg_green!`, `bg_blue!`, `bg_white!`, `bg_black!`, `bg_yellow!`, `bg_magenta!`, `bg_cyan!`
+//! - `bg_bright_red!`, `bg_bright_green!`, `bg_bright_blue!`, `bg_bright_white!`, `bg_bright_black!`, `bg_bright_yellow!`, `bg_bright_magenta!`, `bg_bright_cyan!`
+//! - `bg!`: Apply a `Colours` value chosen at runtime as the background, e.g. `bg!(Colours::Blue, "text")`.
 //!
 //! ### Styles
-//! - `bold!`, `italic!`, `underline!`
+//! - `bold!`, `dim!`, `italic!`, `underline!`, `strikethrough!`, `blink!`, `rapid_blink!`, `reverse!`, `conceal!`, `double_underline!`, `overline!`, `extended_double_underline!`, `curly_underline!`, `dotted_underline!`, `dashed_underline!`
+//!
+//! - `sgr!`: Apply raw SGR parameters as one sequence, e.g. `sgr!([1, 4, 38, 5, 208], "text")`, for features without a macro of their own.
+//! - `style!`: Combine a foreground, background and attributes into one escape sequence, e.g. `style!(fg: red, bg: black, bold; "text")`.
+//!
+//! ### Messages
+//! - `success!`, `failure!`, `warn_msg!`, `info_msg!`: Apply the success, error, warning or info style of the global `Theme`.
+//! - `themed!`: Apply the style of any named role of the global `Theme`, such as `"accent"` or `"muted"`.
+//!
+//! ### Printing
+//! - `cprint!`, `cprintln!`: Print in a color or style straight to stdout, e.g. `cprintln!(red, "error: {}", e)`.
+//! - `ceprint!`, `ceprintln!`: The same for stderr, with its own color decision.
+//! - `cwrite!`, `cwriteln!`: Write in a color or style to any `fmt::Write` or `io::Write` destination, like `write!`.
+//!
+//! ### Markup
+//! - `markup!`: Format the arguments and replace HTML-like tags such as `<red>` and `<b>` with escape codes.
+//!
+//! ### Terminal Control
+//! - `hyperlink!`: Make the formatted text a clickable OSC 8 link, e.g. `hyperlink!("https://example.com", "docs")`, falling back to `text (url)`.
+//! - `clear_line!`, `clear_screen!`: Erase the current line or the screen, whole or on one side of the cursor, for redrawing status lines.
+//! - `set_title!`: Set the terminal window and tab title, e.g. `set_title!("myapp – {}", phase)`.
+//!
+//! ### Layout
+//! - `boxed!`: Draw a frame around multi-line, styled text, e.g. `boxed!(Border::Rounded, Colours::Cyan; "Deployed {}", version)`.
 //!
 //! ### Custom Colors
 //! - `rgb!`, `bg_rgb!`: Apply custom RGB colors for text and background.
+//! - `ansi256!`, `bg_ansi256!`: Apply 8-bit indexed (256-color palette) colors for text and background.
+//! - `grayscale!`, `bg_grayscale!`: Apply a shade from the 24-step grayscale ramp for text and background.
 //! - `hsl!`, `hsv!`, `bg_hsl!`, `bg_hsv!`: Apply colors using HSL or HSV color models for text and background.
+//! - `hex!`, `bg_hex!`: Apply colors given as `#RRGGBB` or `#RGB` hex strings for text and background.
+//! - `named!`, `bg_named!`: Apply CSS named colors (e.g. `"tomato"`) for text and background.
+//! - `cmyk!`, `bg_cmyk!`: Apply colors using the CMYK color model for text and background.
+//! - `lab!`, `bg_lab!`: Apply colors using the perceptually uniform CIELAB color space for text and background.
+//! - `oklch!`, `bg_oklch!`: Apply colors using the OKLCH color space for text and background.
+//! - `hwb!`, `bg_hwb!`: Apply colors using the CSS HWB (hue, whiteness, blackness) model for text and background.
+//! - `hsluv!`, `bg_hsluv!`: Apply colors using the perceptually even HSLuv color space for text and background.
+//! - `underline_rgb!`, `underline_ansi256!`: Underline text with a custom underline color.
+//! - `auto_fg!`: Apply an RGB background with black or white text, whichever is more readable.
+//! - `gradient!`, `bg_gradient!`: Color each character along a gradient between two RGB colors, for text and background.
+//! - `vgradient!`: Color each line along a gradient between two RGB colors.
 //!
 //! ## License
 //!
 //! This crate is licensed under the MIT License. See the [LICENSE](LICENSE) file for details.
 
-use std::cell::RefCell;
+use std::ffi::OsString;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
 
+mod adjust;
+mod ansi_string;
+pub mod codes;
+mod colorize;
+mod compare;
+mod context;
+mod convert;
+pub mod cursor;
+mod export;
+mod generate;
+mod gradient;
+mod hyperlink;
+mod markup;
+mod named;
+mod parse;
+mod print;
+mod progress;
+mod quantize;
+mod screen;
+mod sgr;
+mod style;
+mod support;
+mod table;
+mod terminal;
 #[cfg(test)]
 mod test;
+mod text;
+mod theme;
+mod title;
+#[cfg(feature = "tracing")]
+mod tracing_fmt;
+mod types;
+mod vision;
+mod writer;
 
-thread_local! {
-    static COLOR_CONTEXT: RefCell<Vec<String>> = RefCell::new(vec![String::from("\x1b[37m")]);
-}
-
-#[allow(dead_code)]
-pub struct ColorContext;
-
-#[allow(dead_code)]
-impl ColorContext {
-    pub fn push(color: &str) {
-        COLOR_CONTEXT.with(|ctx| {
-            ctx.borrow_mut().push(color.to_string());
-        });
-    }
-
-    pub fn pop() {
-        COLOR_CONTEXT.with(|ctx| {
-            ctx.borrow_mut().pop();
-        });
-    }
-
-    pub fn current_color() -> String {
-        COLOR_CONTEXT.with(|ctx| {
-            ctx.borrow()
-                .last()
-                .cloned()
-                .unwrap_or_else(|| String::from("\x1b[37m"))
-        })
-    }
-}
+pub use adjust::{
+    analogous, complement, darken, desaturate, invert, lerp_color, lerp_color_oklab, lighten, mix,
+    saturate, shades, split_complement, tetradic, tints, triadic,
+};
+pub use ansi_string::AnsiString;
+pub use colorize::Colorize;
+pub use compare::{contrast_ratio, delta_e, meets_aa, meets_aaa, nearest_named, readable_fg};
+pub use context::{ColorContext, ContextSnapshot};
+pub use convert::{
+    cmyk_to_rgb, hsluv_to_rgb, hwb_to_rgb, lab_to_rgb, linear_to_srgb, oklch_to_rgb, rgb_to_lab,
+    rgb_to_xyz, srgb_to_linear, xyz_to_rgb,
+};
+pub use export::{ansi_to_bbcode, ansi_to_html, ansi_to_irc, ansi_to_svg};
+pub use generate::color_for;
+#[cfg(feature = "rand")]
+pub use generate::{random_color, random_pleasant_color};
+pub use gradient::{bg_gradient, gradient, vgradient};
+pub use hyperlink::{
+    detect_hyperlink_support, hyperlink, hyperlinks_supported, set_hyperlinks_supported,
+};
+pub use markup::{colorize_markup, colorize_template};
+#[cfg(feature = "macros")]
+#[doc(hidden)]
+pub use markup::MarkupCode;
+pub use named::{named_to_rgb, CSS_COLORS};
+pub use parse::AnsiParser;
+#[doc(hidden)]
+pub use print::{PrintScope, Wrapped};
+pub use progress::{ProgressBar, Spinner};
+pub use quantize::{
+    basic_palette, bg_rgb_code, rgb_code, rgb_to_ansi256, rgb_to_basic16, set_basic_palette,
+};
+pub use screen::{
+    enter_alt_screen, leave_alt_screen, restore_cursor, save_cursor, AltScreen, SavedCursor,
+};
+pub use style::{styled, Attribute, Style, Styled};
+pub use support::{
+    color_choice, color_support, colors_enabled, detect_color_support, set_color_support,
+    set_colors_enabled, should_colorize, ColorChoice, ColorSupport, Stream,
+};
+#[cfg(feature = "macros")]
+#[doc(hidden)]
+pub use term_ansi_macros::styled as __styled;
+pub use table::{boxed, Align, Border, Table};
+pub use terminal::{
+    reset_palette, set_palette, set_palette_entry, terminal_background, terminal_is_dark,
+    terminal_palette,
+};
+pub use text::{linewise, optimize_ansi, slice_ansi, strip_ansi, strip_ansi_cow, visible_width};
+pub use theme::{set_global_theme, theme, LevelStyles, Theme};
+pub use title::{pop_title, push_title, set_title, SavedTitle};
+#[cfg(feature = "tracing")]
+pub use tracing_fmt::ThemedFormat;
+pub use types::{Color, ColorCode, Colours};
+pub use vision::{simulate, Deficiency, OKABE_ITO, TOL_BRIGHT, TOL_MUTED, TOL_VIBRANT};
+pub use writer::{AnsiWriter, TeeWriter};
 
 pub fn hsl_to_rgb(h: f64, s: f64, l: f64) -> (u8, u8, u8) {
     let c: f64 = (1.0 - (2.0 * l - 1.0).abs()) * s;
@@ -108,12 +268,12 @@ pub fn hsl_to_rgb(h: f64, s: f64, l: f64) -> (u8, u8, u8) {
     let m: f64 = l - c / 2.0;
 
     let (r, g, b): (f64, f64, f64) = match h {
-        h if h >= 0.0 && h < 60.0 => (c, x, 0.0),
-        h if h >= 60.0 && h < 120.0 => (x, c, 0.0),
-        h if h >= 120.0 && h < 180.0 => (0.0, c, x),
-        h if h >= 180.0 && h < 240.0 => (0.0, x, c),
-        h if h >= 240.0 && h < 300.0 => (x, 0.0, c),
-        h if h >= 300.0 && h <= 360.0 => (c, 0.0, x),
+        h if (0.0..60.0).contains(&h) => (c, x, 0.0),
+        h if (60.0..120.0).contains(&h) => (x, c, 0.0),
+        h if (120.0..180.0).contains(&h) => (0.0, c, x),
+        h if (180.0..240.0).contains(&h) => (0.0, x, c),
+        h if (240.0..300.0).contains(&h) => (x, 0.0, c),
+        h if (300.0..=360.0).contains(&h) => (c, 0.0, x),
         _ => (0.0, 0.0, 0.0),
     };
 
@@ -130,12 +290,12 @@ pub fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
     let m: f64 = v - c;
 
     let (r, g, b): (f64, f64, f64) = match h {
-        h if h >= 0.0 && h < 60.0 => (c, x, 0.0),
-        h if h >= 60.0 && h < 120.0 => (x, c, 0.0),
-        h if h >= 120.0 && h < 180.0 => (0.0, c, x),
-        h if h >= 180.0 && h < 240.0 => (0.0, x, c),
-        h if h >= 240.0 && h < 300.0 => (x, 0.0, c),
-        h if h >= 300.0 && h <= 360.0 => (c, 0.0, x),
+        h if (0.0..60.0).contains(&h) => (c, x, 0.0),
+        h if (60.0..120.0).contains(&h) => (x, c, 0.0),
+        h if (120.0..180.0).contains(&h) => (0.0, c, x),
+        h if (180.0..240.0).contains(&h) => (0.0, x, c),
+        h if (240.0..300.0).contains(&h) => (x, 0.0, c),
+        h if (300.0..=360.0).contains(&h) => (c, 0.0, x),
         _ => (0.0, 0.0, 0.0),
     };
 
@@ -146,8 +306,176 @@ pub fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
     (r, g, b)
 }
 
+/// Returns the hue (0-360), chroma, max and min of an RGB color, with channels scaled to 0-1.
+fn rgb_hue_chroma(r: u8, g: u8, b: u8) -> (f64, f64, f64, f64) {
+    let r: f64 = r as f64 / 255.0;
+    let g: f64 = g as f64 / 255.0;
+    let b: f64 = b as f64 / 255.0;
+
+    let max: f64 = r.max(g).max(b);
+    let min: f64 = r.min(g).min(b);
+    let c: f64 = max - min;
+
+    let h: f64 = if c == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / c).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / c + 2.0)
+    } else {
+        60.0 * ((r - g) / c + 4.0)
+    };
+
+    (h, c, max, min)
+}
+
+/// Converts RGB components into HSL, returning the hue (0-360), saturation (0-1) and lightness (0-1).
+pub fn rgb_to_hsl(r: u8, g: u8, b: u8) -> (f64, f64, f64) {
+    let (h, c, max, min) = rgb_hue_chroma(r, g, b);
+    let l: f64 = (max + min) / 2.0;
+    let s: f64 = if c == 0.0 {
+        0.0
+    } else {
+        c / (1.0 - (2.0 * l - 1.0).abs())
+    };
+
+    (h, s, l)
+}
+
+/// Converts RGB components into HSV, returning the hue (0-360), saturation (0-1) and value (0-1).
+pub fn rgb_to_hsv(r: u8, g: u8, b: u8) -> (f64, f64, f64) {
+    let (h, c, max, _) = rgb_hue_chroma(r, g, b);
+    let s: f64 = if max == 0.0 { 0.0 } else { c / max };
+
+    (h, s, max)
+}
+
+/// Parses a `#RRGGBB` or `#RGB` hex color string into RGB components.
+///
+/// The leading `#` is optional. Returns `None` if the string is not a valid hex color.
+pub fn hex_to_rgb(hex: &str) -> Option<(u8, u8, u8)> {
+    let digits: &str = hex.strip_prefix('#').unwrap_or(hex);
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    match digits.len() {
+        6 => {
+            let r: u8 = u8::from_str_radix(&digits[0..2], 16).ok()?;
+            let g: u8 = u8::from_str_radix(&digits[2..4], 16).ok()?;
+            let b: u8 = u8::from_str_radix(&digits[4..6], 16).ok()?;
+            Some((r, g, b))
+        }
+        3 => {
+            let r: u8 = u8::from_str_radix(&digits[0..1], 16).ok()?;
+            let g: u8 = u8::from_str_radix(&digits[1..2], 16).ok()?;
+            let b: u8 = u8::from_str_radix(&digits[2..3], 16).ok()?;
+            Some((r * 17, g * 17, b * 17))
+        }
+        _ => None,
+    }
+}
+
+/// Maps a grayscale level (0-23) onto the 232-255 grayscale ramp of the 256-color palette.
+///
+/// Levels above 23 are clamped to the brightest gray.
+pub fn grayscale_index(level: u8) -> u8 {
+    232 + level.min(23)
+}
+
 pub fn reset_all() -> &'static str {
-    "\x1b[0m"
+    codes::RESET
+}
+
+/// Colors `text` with `color` as the foreground, like the color macros do.
+///
+/// Useful where a macro can't be used, e.g. in closures passed to other APIs.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// let lines: Vec<String> = ["a", "b"].iter().map(|s| paint(Colours::Green, s)).collect();
+/// ```
+pub fn paint(color: impl Into<Color>, text: &str) -> String {
+    apply_color!(color.into(), "{}", text)
+}
+
+/// Colors the background of `text` with `color`, like the background macros do.
+pub fn paint_bg(color: impl Into<Color>, text: &str) -> String {
+    apply_color!(color.into().bg_code(), "{}", text)
+}
+
+const EXTENDED_UNDERLINE_AUTO: u8 = 0;
+const EXTENDED_UNDERLINE_ON: u8 = 1;
+const EXTENDED_UNDERLINE_OFF: u8 = 2;
+
+static EXTENDED_UNDERLINE: AtomicU8 = AtomicU8::new(EXTENDED_UNDERLINE_AUTO);
+
+/// Overrides whether extended underline styles (`4:2` to `4:5`) are emitted.
+///
+/// `Some(true)` forces the colon sub-parameter form, `Some(false)` forces the plain
+/// SGR 4 fallback and `None` restores detection from the environment.
+pub fn set_extended_underline(enabled: Option<bool>) {
+    let value = match enabled {
+        None => EXTENDED_UNDERLINE_AUTO,
+        Some(true) => EXTENDED_UNDERLINE_ON,
+        Some(false) => EXTENDED_UNDERLINE_OFF,
+    };
+    EXTENDED_UNDERLINE.store(value, Ordering::Relaxed);
+}
+
+/// Returns whether the terminal is expected to understand extended underline styles.
+///
+/// Unless overridden with [`set_extended_underline`], this looks at `TERM`,
+/// `TERM_PROGRAM` and `VTE_VERSION` for terminals known to support colon
+/// sub-parameters. The environment is read once and the result cached.
+pub fn extended_underline_supported() -> bool {
+    match EXTENDED_UNDERLINE.load(Ordering::Relaxed) {
+        EXTENDED_UNDERLINE_ON => true,
+        EXTENDED_UNDERLINE_OFF => false,
+        _ => {
+            static DETECTED: OnceLock<bool> = OnceLock::new();
+            *DETECTED.get_or_init(|| env_extended_underline(|name| std::env::var_os(name)))
+        }
+    }
+}
+
+/// Resolves extended underline support from environment variables looked up with `var`.
+pub(crate) fn env_extended_underline(var: impl Fn(&str) -> Option<OsString>) -> bool {
+    let get = |name: &str| {
+        var(name)
+            .map(|value| value.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    };
+    let term = get("TERM");
+    let term_program = get("TERM_PROGRAM");
+    let vte_version = get("VTE_VERSION").parse::<u32>().unwrap_or(0);
+
+    term.contains("kitty")
+        || term.contains("ghostty")
+        || term.starts_with("foot")
+        || term.starts_with("wezterm")
+        || matches!(term_program.as_str(), "WezTerm" | "iTerm.app" | "ghostty")
+        || vte_version >= 5102
+}
+
+/// Returns the escape code for an extended underline style.
+///
+/// `style` is the SGR 4 sub-parameter: 2 (double), 3 (curly), 4 (dotted) or 5 (dashed).
+/// Falls back to a plain underline when extended styles are not supported.
+pub fn underline_style_code(style: u8) -> String {
+    extended_underline_code(style, extended_underline_supported())
+}
+
+/// Returns the code for underline `style`, or plain SGR 4 when `supported` is false.
+pub(crate) fn extended_underline_code(style: u8, supported: bool) -> String {
+    if supported {
+        format!("\x1b[4:{}m", style)
+    } else {
+        String::from(codes::UNDERLINE)
+    }
 }
 
 #[macro_export]
@@ -155,7 +483,7 @@ pub fn reset_all() -> &'static str {
 ///
 /// # Arguments
 ///
-/// * `color_code` - The ANSI escape code for the desired color.
+/// * `color_code` - The ANSI escape code for the desired color, or a [`Color`] to use as the foreground.
 /// * `args` - The format string and its arguments.
 ///
 /// # Example
@@ -165,17 +493,29 @@ pub fn reset_all() -> &'static str {
 ///
 /// let colored_text = apply_color!("\x1b[31m", "This is {}", "red");
 /// println!("{}", colored_text);
+///
+/// let color = Color::Rgb(255, 128, 0);
+/// println!("{}", apply_color!(color, "This is {}", "orange"));
 /// ```
 ///
 /// # Notes
 ///
 /// The color context is managed using `ColorContext` to ensure colors are correctly nested.
+/// Nesting re-emits the enclosing color after every reset, except where nested output ends
+/// the text and the closing reset would clear it straight away. Pass the final output
+/// through [`optimize_ansi`] to merge the sequences that remain.
+/// When colors are disabled (see [`set_colors_enabled`]), only the formatted text is returned.
 macro_rules! apply_color {
     ($color_code:expr, $($arg:tt)*) => {{
-        $crate::ColorContext::push($color_code);
-        let result = format!("{}{}{}", $color_code, format!($($arg)*), $crate::reset_all());
-        $crate::ColorContext::pop();
-        format!("{}{}", result, $crate::ColorContext::current_color())
+        if $crate::colors_enabled() {
+            let color_code = &$color_code;
+            let color_code = $crate::ColorCode::color_code(color_code);
+            $crate::ColorContext::push(&color_code);
+            let content = format!($($arg)*);
+            $crate::ColorContext::close(&color_code, &content)
+        } else {
+            format!($($arg)*)
+        }
     }};
 }
 
@@ -195,7 +535,7 @@ macro_rules! apply_color {
 /// ```
 macro_rules! red {
     ($($arg:tt)*) => {{
-        $crate::apply_color!("\x1b[31m", $($arg)*)
+        $crate::apply_color!($crate::codes::RED, $($arg)*)
     }};
 }
 
@@ -215,7 +555,7 @@ macro_rules! red {
 /// ```
 macro_rules! green {
     ($($arg:tt)*) => {{
-        $crate::apply_color!("\x1b[32m", $($arg)*)
+        $crate::apply_color!($crate::codes::GREEN, $($arg)*)
     }};
 }
 
@@ -235,7 +575,7 @@ macro_rules! green {
 /// ```
 macro_rules! blue {
     ($($arg:tt)*) => {{
-        $crate::apply_color!("\x1b[34m", $($arg)*)
+        $crate::apply_color!($crate::codes::BLUE, $($arg)*)
     }};
 }
 
@@ -255,7 +595,7 @@ macro_rules! blue {
 /// ```
 macro_rules! white {
     ($($arg:tt)*) => {{
-        $crate::apply_color!("\x1b[37m", $($arg)*)
+        $crate::apply_color!($crate::codes::WHITE, $($arg)*)
     }};
 }
 
@@ -275,7 +615,7 @@ macro_rules! white {
 /// ```
 macro_rules! black {
     ($($arg:tt)*) => {{
-        $crate::apply_color!("\x1b[30m", $($arg)*)
+        $crate::apply_color!($crate::codes::BLACK, $($arg)*)
     }};
 }
 
@@ -295,7 +635,7 @@ macro_rules! black {
 /// ```
 macro_rules! yellow {
     ($($arg:tt)*) => {{
-        $crate::apply_color!("\x1b[33m", $($arg)*)
+        $crate::apply_color!($crate::codes::YELLOW, $($arg)*)
     }};
 }
 
@@ -315,7 +655,7 @@ macro_rules! yellow {
 /// ```
 macro_rules! magenta {
     ($($arg:tt)*) => {{
-        $crate::apply_color!("\x1b[35m", $($arg)*)
+        $crate::apply_color!($crate::codes::MAGENTA, $($arg)*)
     }};
 }
 
@@ -335,18 +675,15 @@ macro_rules! magenta {
 /// ```
 macro_rules! cyan {
     ($($arg:tt)*) => {{
-        $crate::apply_color!("\x1b[36m", $($arg)*)
+        $crate::apply_color!($crate::codes::CYAN, $($arg)*)
     }};
 }
 
 #[macro_export]
-/// Applies a custom RGB color to the provided format string.
+/// Applies bright black (gray) color to the provided format string.
 ///
 /// # Arguments
 ///
-/// * `r` - The red component (0-255).
-/// * `g` - The green component (0-255).
-/// * `b` - The blue component (0-255).
 /// * `args` - The format string and its arguments.
 ///
 /// # Example
@@ -354,23 +691,19 @@ macro_rules! cyan {
 /// ```
 /// use term_ansi::*;
 ///
-/// println!("{}", rgb!(255, 0, 0, "This is {} text", "bright red"));
+/// println!("{}", bright_black!("This is {} text", "bright black (gray)"));
 /// ```
-macro_rules! rgb {
-    ($r:expr, $g:expr, $b:expr, $($arg:tt)*) => {{
-        let color_code = format!("\x1b[38;2;{};{};{}m", $r, $g, $b);
-        $crate::apply_color!(&color_code, $($arg)*)
+macro_rules! bright_black {
+    ($($arg:tt)*) => {{
+        $crate::apply_color!($crate::codes::BRIGHT_BLACK, $($arg)*)
     }};
 }
 
 #[macro_export]
-/// Applies HSL color to the provided format string.
+/// Applies bright red color to the provided format string.
 ///
 /// # Arguments
 ///
-/// * `h` - The hue (0-360).
-/// * `s` - The saturation (0-1).
-/// * `l` - The lightness (0-1).
 /// * `args` - The format string and its arguments.
 ///
 /// # Example
@@ -378,23 +711,19 @@ macro_rules! rgb {
 /// ```
 /// use term_ansi::*;
 ///
-/// println!("{}", hsl!(120.0, 1.0, 0.5, "This is green text"));
+/// println!("{}", bright_red!("This is {} text", "bright red"));
 /// ```
-macro_rules! hsl {
-    ($h:expr, $s:expr, $l:expr, $($arg:tt)*) => {{
-        let (r, g, b) = $crate::hsl_to_rgb($h, $s, $l);
-        $crate::rgb!(r, g, b, $($arg)*)
+macro_rules! bright_red {
+    ($($arg:tt)*) => {{
+        $crate::apply_color!($crate::codes::BRIGHT_RED, $($arg)*)
     }};
 }
 
 #[macro_export]
-/// Applies HSV color to the provided format string.
+/// Applies bright green color to the provided format string.
 ///
 /// # Arguments
 ///
-/// * `h` - The hue (0-360).
-/// * `s` - The saturation (0-1).
-/// * `v` - The value (0-1).
 /// * `args` - The format string and its arguments.
 ///
 /// # Example
@@ -402,302 +731,2316 @@ macro_rules! hsl {
 /// ```
 /// use term_ansi::*;
 ///
-/// println!("{}", hsv!(240.0, 1.0, 1.0, "This is blue text"));
+/// println!("{}", bright_green!("This is {} text", "bright green"));
 /// ```
-macro_rules! hsv {
-    ($h:expr, $s:expr, $v:expr, $($arg:tt)*) => {{
-        let (r, g, b) = $crate::hsv_to_rgb($h, $s, $v);
-        $crate::rgb!(r, g, b, $($arg)*)
+macro_rules! bright_green {
+    ($($arg:tt)*) => {{
+        $crate::apply_color!($crate::codes::BRIGHT_GREEN, $($arg)*)
     }};
 }
-/// Applies a red background color to the provided format string.
+
+#[macro_export]
+/// Applies bright yellow color to the provided format string.
 ///
 /// # Arguments
 ///
-/// * `$arg` - The format string and its arguments.
+/// * `args` - The format string and its arguments.
 ///
 /// # Example
 ///
 /// ```
 /// use term_ansi::*;
 ///
-/// println!("{}", bg_red!("This has a red background"));
+/// println!("{}", bright_yellow!("This is {} text", "bright yellow"));
 /// ```
-#[macro_export]
-macro_rules! bg_red {
+macro_rules! bright_yellow {
     ($($arg:tt)*) => {{
-        $crate::apply_color!("\x1b[41m", $($arg)*)
+        $crate::apply_color!($crate::codes::BRIGHT_YELLOW, $($arg)*)
     }};
 }
 
-/// Applies a green background color to the provided format string.
+#[macro_export]
+/// Applies bright blue color to the provided format string.
 ///
 /// # Arguments
 ///
-/// * `$arg` - The format string and its arguments.
+/// * `args` - The format string and its arguments.
 ///
 /// # Example
 ///
 /// ```
 /// use term_ansi::*;
 ///
-/// println!("{}", bg_green!("This has a green background"));
+/// println!("{}", bright_blue!("This is {} text", "bright blue"));
 /// ```
-#[macro_export]
-macro_rules! bg_green {
+macro_rules! bright_blue {
     ($($arg:tt)*) => {{
-        $crate::apply_color!("\x1b[42m", $($arg)*)
+        $crate::apply_color!($crate::codes::BRIGHT_BLUE, $($arg)*)
     }};
 }
 
-/// Applies a blue background color to the provided format string.
+#[macro_export]
+/// Applies bright magenta color to the provided format string.
 ///
 /// # Arguments
 ///
-/// * `$arg` - The format string and its arguments.
+/// * `args` - The format string and its arguments.
 ///
 /// # Example
 ///
 /// ```
 /// use term_ansi::*;
 ///
-/// println!("{}", bg_blue!("This has a blue background"));
+/// println!("{}", bright_magenta!("This is {} text", "bright magenta"));
 /// ```
-#[macro_export]
-macro_rules! bg_blue {
+macro_rules! bright_magenta {
     ($($arg:tt)*) => {{
-        $crate::apply_color!("\x1b[44m", $($arg)*)
+        $crate::apply_color!($crate::codes::BRIGHT_MAGENTA, $($arg)*)
     }};
 }
 
-/// Applies a white background color to the provided format string.
+#[macro_export]
+/// Applies bright cyan color to the provided format string.
 ///
 /// # Arguments
 ///
-/// * `$arg` - The format string and its arguments.
+/// * `args` - The format string and its arguments.
 ///
 /// # Example
 ///
 /// ```
 /// use term_ansi::*;
 ///
-/// println!("{}", bg_white!("This has a white background"));
+/// println!("{}", bright_cyan!("This is {} text", "bright cyan"));
 /// ```
-#[macro_export]
-macro_rules! bg_white {
+macro_rules! bright_cyan {
     ($($arg:tt)*) => {{
-        $crate::apply_color!("\x1b[47m", $($arg)*)
+        $crate::apply_color!($crate::codes::BRIGHT_CYAN, $($arg)*)
     }};
 }
 
-/// Applies a black background color to the provided format string.
+#[macro_export]
+/// Applies bright white color to the provided format string.
 ///
 /// # Arguments
 ///
-/// * `$arg` - The format string and its arguments.
+/// * `args` - The format string and its arguments.
 ///
 /// # Example
 ///
 /// ```
 /// use term_ansi::*;
 ///
-/// println!("{}", bg_black!("This has a black background"));
+/// println!("{}", bright_white!("This is {} text", "bright white"));
 /// ```
-#[macro_export]
-macro_rules! bg_black {
+macro_rules! bright_white {
     ($($arg:tt)*) => {{
-        $crate::apply_color!("\x1b[40m", $($arg)*)
+        $crate::apply_color!($crate::codes::BRIGHT_WHITE, $($arg)*)
     }};
 }
 
-/// Applies a yellow background color to the provided format string.
+#[macro_export]
+/// Applies a custom RGB color to the provided format string.
 ///
 /// # Arguments
 ///
-/// * `$arg` - The format string and its arguments.
+/// * `r` - The red component (0-255).
+/// * `g` - The green component (0-255).
+/// * `b` - The blue component (0-255).
+/// * `args` - The format string and its arguments.
 ///
 /// # Example
 ///
 /// ```
 /// use term_ansi::*;
 ///
-/// println!("{}", bg_yellow!("This has a yellow background"));
+/// println!("{}", rgb!(255, 0, 0, "This is {} text", "bright red"));
 /// ```
-#[macro_export]
-macro_rules! bg_yellow {
-    ($($arg:tt)*) => {{
-        $crate::apply_color!("\x1b[43m", $($arg)*)
+macro_rules! rgb {
+    ($r:expr, $g:expr, $b:expr, $($arg:tt)*) => {{
+        let color_code = $crate::rgb_code($r, $g, $b);
+        $crate::apply_color!(&color_code, $($arg)*)
     }};
 }
 
-/// Applies a magenta background color to the provided format string.
+#[macro_export]
+/// Applies an 8-bit indexed color to the provided format string.
+///
+/// Useful for terminals that support the 256-color palette but not truecolor.
 ///
 /// # Arguments
 ///
-/// * `$arg` - The format string and its arguments.
+/// * `idx` - The 256-color palette index (0-255).
+/// * `args` - The format string and its arguments.
 ///
 /// # Example
 ///
 /// ```
 /// use term_ansi::*;
 ///
-/// println!("{}", bg_magenta!("This has a magenta background"));
+/// println!("{}", ansi256!(208, "This is {} text", "orange"));
 /// ```
-#[macro_export]
-macro_rules! bg_magenta {
-    ($($arg:tt)*) => {{
-        $crate::apply_color!("\x1b[45m", $($arg)*)
+macro_rules! ansi256 {
+    ($idx:expr, $($arg:tt)*) => {{
+        let color_code = format!("\x1b[38;5;{}m", $idx);
+        $crate::apply_color!(&color_code, $($arg)*)
     }};
 }
 
-/// Applies a cyan background color to the provided format string.
+#[macro_export]
+/// Applies a shade of gray from the 256-color grayscale ramp to the provided format string.
 ///
 /// # Arguments
 ///
-/// * `$arg` - The format string and its arguments.
+/// * `level` - The grayscale level (0-23), from darkest to brightest.
+/// * `args` - The format string and its arguments.
 ///
 /// # Example
 ///
 /// ```
 /// use term_ansi::*;
 ///
-/// println!("{}", bg_cyan!("This has a cyan background"));
+/// println!("{}", grayscale!(12, "This is {} text", "gray"));
 /// ```
-#[macro_export]
-macro_rules! bg_cyan {
-    ($($arg:tt)*) => {{
-        $crate::apply_color!("\x1b[46m", $($arg)*)
+macro_rules! grayscale {
+    ($level:expr, $($arg:tt)*) => {{
+        $crate::ansi256!($crate::grayscale_index($level), $($arg)*)
     }};
 }
 
-/// Applies bold formatting to the provided format string.
+#[macro_export]
+/// Applies HSL color to the provided format string.
 ///
 /// # Arguments
 ///
-/// * `$arg` - The format string and its arguments.
+/// * `h` - The hue (0-360).
+/// * `s` - The saturation (0-1).
+/// * `l` - The lightness (0-1).
+/// * `args` - The format string and its arguments.
 ///
 /// # Example
 ///
 /// ```
 /// use term_ansi::*;
 ///
-/// println!("{}", bold!("This text is bold"));
+/// println!("{}", hsl!(120.0, 1.0, 0.5, "This is green text"));
 /// ```
-#[macro_export]
-macro_rules! bold {
-    ($($arg:tt)*) => {{
-        $crate::apply_color!("\x1b[1m", $($arg)*)
+macro_rules! hsl {
+    ($h:expr, $s:expr, $l:expr, $($arg:tt)*) => {{
+        let (r, g, b) = $crate::hsl_to_rgb($h, $s, $l);
+        $crate::rgb!(r, g, b, $($arg)*)
     }};
 }
 
-/// Applies italic formatting to the provided format string.
+#[macro_export]
+/// Applies HSV color to the provided format string.
 ///
 /// # Arguments
 ///
-/// * `$arg` - The format string and its arguments.
+/// * `h` - The hue (0-360).
+/// * `s` - The saturation (0-1).
+/// * `v` - The value (0-1).
+/// * `args` - The format string and its arguments.
 ///
 /// # Example
 ///
 /// ```
 /// use term_ansi::*;
 ///
-/// println!("{}", italic!("This text is italic"));
+/// println!("{}", hsv!(240.0, 1.0, 1.0, "This is blue text"));
 /// ```
-#[macro_export]
-macro_rules! italic {
-    ($($arg:tt)*) => {{
-        $crate::apply_color!("\x1b[3m", $($arg)*)
+macro_rules! hsv {
+    ($h:expr, $s:expr, $v:expr, $($arg:tt)*) => {{
+        let (r, g, b) = $crate::hsv_to_rgb($h, $s, $v);
+        $crate::rgb!(r, g, b, $($arg)*)
     }};
 }
 
-/// Applies underline formatting to the provided format string.
+#[macro_export]
+/// Applies a color given as a hex string to the provided format string.
 ///
 /// # Arguments
 ///
-/// * `$arg` - The format string and its arguments.
+/// * `hex` - The color as `"#RRGGBB"` or `"#RGB"` (the `#` is optional).
+/// * `args` - The format string and its arguments.
+///
+/// # Panics
+///
+/// Panics if `hex` is not a valid hex color. Use [`hex_to_rgb`] to validate untrusted input.
 ///
 /// # Example
 ///
 /// ```
 /// use term_ansi::*;
 ///
-/// println!("{}", underline!("This text is underlined"));
+/// println!("{}", hex!("#ff8800", "This is {} text", "orange"));
 /// ```
-#[macro_export]
-macro_rules! underline {
-    ($($arg:tt)*) => {{
-        $crate::apply_color!("\x1b[4m", $($arg)*)
+macro_rules! hex {
+    ($hex:expr, $($arg:tt)*) => {{
+        let hex: &str = $hex;
+        let (r, g, b) = $crate::hex_to_rgb(hex)
+            .unwrap_or_else(|| panic!("invalid hex color: {:?}", hex));
+        $crate::rgb!(r, g, b, $($arg)*)
     }};
 }
 
-/// Applies a custom RGB background color to the provided format string.
+#[macro_export]
+/// Applies a CSS named color to the provided format string.
 ///
 /// # Arguments
 ///
-/// * `$r` - The red component (0-255).
-/// * `$g` - The green component (0-255).
-/// * `$b` - The blue component (0-255).
-/// * `$arg` - The format string and its arguments.
+/// * `name` - A CSS/X11 color name such as `"tomato"` (case-insensitive).
+/// * `args` - The format string and its arguments.
+///
+/// # Panics
+///
+/// Panics if `name` is not a known color name. Use [`named_to_rgb`] to validate untrusted input.
 ///
 /// # Example
 ///
 /// ```
 /// use term_ansi::*;
 ///
-/// println!("{}", bg_rgb!(100, 150, 200, "This has a custom RGB background"));
+/// println!("{}", named!("tomato", "This is {} text", "tomato"));
 /// ```
-#[macro_export]
-macro_rules! bg_rgb {
-    ($r:expr, $g:expr, $b:expr, $($arg:tt)*) => {{
-        let color_code = format!("\x1b[48;2;{};{};{}m", $r, $g, $b);
-        $crate::apply_color!(&color_code, $($arg)*)
+macro_rules! named {
+    ($name:expr, $($arg:tt)*) => {{
+        let name: &str = $name;
+        let (r, g, b) = $crate::named_to_rgb(name)
+            .unwrap_or_else(|| panic!("unknown color name: {:?}", name));
+        $crate::rgb!(r, g, b, $($arg)*)
     }};
 }
 
-/// Applies a background color specified in HSL color space to the provided format string.
+#[macro_export]
+/// Applies a CMYK color to the provided format string.
 ///
 /// # Arguments
 ///
-/// * `$h` - The hue component (0-360).
-/// * `$s` - The saturation component (0-1).
-/// * `$l` - The lightness component (0-1).
-/// * `$arg` - The format string and its arguments.
+/// * `c` - The cyan component (0-1).
+/// * `m` - The magenta component (0-1).
+/// * `y` - The yellow component (0-1).
+/// * `k` - The key (black) component (0-1).
+/// * `args` - The format string and its arguments.
 ///
 /// # Example
 ///
 /// ```
 /// use term_ansi::*;
 ///
-/// println!("{}", bg_hsl!(180.0, 0.5, 0.5, "This has an HSL-specified background"));
+/// println!("{}", cmyk!(0.0, 0.47, 1.0, 0.0, "This is orange text"));
 /// ```
-#[macro_export]
-macro_rules! bg_hsl {
-    ($h:expr, $s:expr, $l:expr, $($arg:tt)*) => {{
-        let (r, g, b) = $crate::hsl_to_rgb($h, $s, $l);
-        $crate::bg_rgb!(r, g, b, $($arg)*)
+macro_rules! cmyk {
+    ($c:expr, $m:expr, $y:expr, $k:expr, $($arg:tt)*) => {{
+        let (r, g, b) = $crate::cmyk_to_rgb($c, $m, $y, $k);
+        $crate::rgb!(r, g, b, $($arg)*)
     }};
 }
 
-/// Applies a background color specified in HSV color space to the provided format string.
+#[macro_export]
+/// Applies a CIELAB color to the provided format string.
 ///
 /// # Arguments
 ///
-/// * `$h` - The hue component (0-360).
-/// * `$s` - The saturation component (0-1).
-/// * `$v` - The value component (0-1).
-/// * `$arg` - The format string and its arguments.
+/// * `l` - The lightness (0-100).
+/// * `a` - The green-red axis (roughly -128 to 127).
+/// * `b` - The blue-yellow axis (roughly -128 to 127).
+/// * `args` - The format string and its arguments.
 ///
 /// # Example
 ///
 /// ```
 /// use term_ansi::*;
 ///
-/// println!("{}", bg_hsv!(270.0, 0.7, 0.9, "This has an HSV-specified background"));
+/// println!("{}", lab!(53.24, 80.09, 67.2, "This is red text"));
 /// ```
-#[macro_export]
-macro_rules! bg_hsv {
-    ($h:expr, $s:expr, $v:expr, $($arg:tt)*) => {{
-        let (r, g, b) = $crate::hsv_to_rgb($h, $s, $v);
-        $crate::bg_rgb!(r, g, b, $($arg)*)
+macro_rules! lab {
+    ($l:expr, $a:expr, $b:expr, $($arg:tt)*) => {{
+        let (r, g, b) = $crate::lab_to_rgb($l, $a, $b);
+        $crate::rgb!(r, g, b, $($arg)*)
     }};
 }
+
+#[macro_export]
+/// Applies an OKLCH color to the provided format string.
+///
+/// # Arguments
+///
+/// * `l` - The perceptual lightness (0-1).
+/// * `c` - The chroma (0 to roughly 0.4).
+/// * `h` - The hue angle in degrees (0-360).
+/// * `args` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", oklch!(0.7, 0.15, 250.0, "This is blue text"));
+/// ```
+macro_rules! oklch {
+    ($l:expr, $c:expr, $h:expr, $($arg:tt)*) => {{
+        let (r, g, b) = $crate::oklch_to_rgb($l, $c, $h);
+        $crate::rgb!(r, g, b, $($arg)*)
+    }};
+}
+
+#[macro_export]
+/// Applies an HWB (hue, whiteness, blackness) color to the provided format string.
+///
+/// # Arguments
+///
+/// * `h` - The hue (0-360).
+/// * `w` - The whiteness (0-1).
+/// * `b` - The blackness (0-1).
+/// * `args` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", hwb!(200.0, 0.2, 0.1, "This is sky blue text"));
+/// ```
+macro_rules! hwb {
+    ($h:expr, $w:expr, $b:expr, $($arg:tt)*) => {{
+        let (r, g, b) = $crate::hwb_to_rgb($h, $w, $b);
+        $crate::rgb!(r, g, b, $($arg)*)
+    }};
+}
+
+#[macro_export]
+/// Applies an HSLuv color to the provided format string.
+///
+/// HSLuv keeps perceived brightness constant across hues, which makes it a good fit
+/// for sweeping the hue to generate category colors.
+///
+/// # Arguments
+///
+/// * `h` - The hue (0-360).
+/// * `s` - The saturation (0-100).
+/// * `l` - The lightness (0-100).
+/// * `args` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", hsluv!(60.0, 100.0, 65.0, "This is {} text", "orange"));
+/// ```
+macro_rules! hsluv {
+    ($h:expr, $s:expr, $l:expr, $($arg:tt)*) => {{
+        let (r, g, b) = $crate::hsluv_to_rgb($h, $s, $l);
+        $crate::rgb!(r, g, b, $($arg)*)
+    }};
+}
+/// Applies a red background color to the provided format string.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", bg_red!("This has a red background"));
+/// ```
+#[macro_export]
+macro_rules! bg_red {
+    ($($arg:tt)*) => {{
+        $crate::apply_color!($crate::codes::BG_RED, $($arg)*)
+    }};
+}
+
+/// Applies a green background color to the provided format string.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", bg_green!("This has a green background"));
+/// ```
+#[macro_export]
+macro_rules! bg_green {
+    ($($arg:tt)*) => {{
+        $crate::apply_color!($crate::codes::BG_GREEN, $($arg)*)
+    }};
+}
+
+/// Applies a blue background color to the provided format string.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", bg_blue!("This has a blue background"));
+/// ```
+#[macro_export]
+macro_rules! bg_blue {
+    ($($arg:tt)*) => {{
+        $crate::apply_color!($crate::codes::BG_BLUE, $($arg)*)
+    }};
+}
+
+/// Applies a white background color to the provided format string.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", bg_white!("This has a white background"));
+/// ```
+#[macro_export]
+macro_rules! bg_white {
+    ($($arg:tt)*) => {{
+        $crate::apply_color!($crate::codes::BG_WHITE, $($arg)*)
+    }};
+}
+
+/// Applies a black background color to the provided format string.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", bg_black!("This has a black background"));
+/// ```
+#[macro_export]
+macro_rules! bg_black {
+    ($($arg:tt)*) => {{
+        $crate::apply_color!($crate::codes::BG_BLACK, $($arg)*)
+    }};
+}
+
+/// Applies a yellow background color to the provided format string.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", bg_yellow!("This has a yellow background"));
+/// ```
+#[macro_export]
+macro_rules! bg_yellow {
+    ($($arg:tt)*) => {{
+        $crate::apply_color!($crate::codes::BG_YELLOW, $($arg)*)
+    }};
+}
+
+/// Applies a magenta background color to the provided format string.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", bg_magenta!("This has a magenta background"));
+/// ```
+#[macro_export]
+macro_rules! bg_magenta {
+    ($($arg:tt)*) => {{
+        $crate::apply_color!($crate::codes::BG_MAGENTA, $($arg)*)
+    }};
+}
+
+/// Applies a cyan background color to the provided format string.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", bg_cyan!("This has a cyan background"));
+/// ```
+#[macro_export]
+macro_rules! bg_cyan {
+    ($($arg:tt)*) => {{
+        $crate::apply_color!($crate::codes::BG_CYAN, $($arg)*)
+    }};
+}
+
+/// Applies a bright black (gray) background color to the provided format string.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", bg_bright_black!("This has a bright black (gray) background"));
+/// ```
+#[macro_export]
+macro_rules! bg_bright_black {
+    ($($arg:tt)*) => {{
+        $crate::apply_color!($crate::codes::BG_BRIGHT_BLACK, $($arg)*)
+    }};
+}
+
+/// Applies a bright red background color to the provided format string.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", bg_bright_red!("This has a bright red background"));
+/// ```
+#[macro_export]
+macro_rules! bg_bright_red {
+    ($($arg:tt)*) => {{
+        $crate::apply_color!($crate::codes::BG_BRIGHT_RED, $($arg)*)
+    }};
+}
+
+/// Applies a bright green background color to the provided format string.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", bg_bright_green!("This has a bright green background"));
+/// ```
+#[macro_export]
+macro_rules! bg_bright_green {
+    ($($arg:tt)*) => {{
+        $crate::apply_color!($crate::codes::BG_BRIGHT_GREEN, $($arg)*)
+    }};
+}
+
+/// Applies a bright yellow background color to the provided format string.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", bg_bright_yellow!("This has a bright yellow background"));
+/// ```
+#[macro_export]
+macro_rules! bg_bright_yellow {
+    ($($arg:tt)*) => {{
+        $crate::apply_color!($crate::codes::BG_BRIGHT_YELLOW, $($arg)*)
+    }};
+}
+
+/// Applies a bright blue background color to the provided format string.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", bg_bright_blue!("This has a bright blue background"));
+/// ```
+#[macro_export]
+macro_rules! bg_bright_blue {
+    ($($arg:tt)*) => {{
+        $crate::apply_color!($crate::codes::BG_BRIGHT_BLUE, $($arg)*)
+    }};
+}
+
+/// Applies a bright magenta background color to the provided format string.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", bg_bright_magenta!("This has a bright magenta background"));
+/// ```
+#[macro_export]
+macro_rules! bg_bright_magenta {
+    ($($arg:tt)*) => {{
+        $crate::apply_color!($crate::codes::BG_BRIGHT_MAGENTA, $($arg)*)
+    }};
+}
+
+/// Applies a bright cyan background color to the provided format string.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", bg_bright_cyan!("This has a bright cyan background"));
+/// ```
+#[macro_export]
+macro_rules! bg_bright_cyan {
+    ($($arg:tt)*) => {{
+        $crate::apply_color!($crate::codes::BG_BRIGHT_CYAN, $($arg)*)
+    }};
+}
+
+/// Applies a bright white background color to the provided format string.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", bg_bright_white!("This has a bright white background"));
+/// ```
+#[macro_export]
+macro_rules! bg_bright_white {
+    ($($arg:tt)*) => {{
+        $crate::apply_color!($crate::codes::BG_BRIGHT_WHITE, $($arg)*)
+    }};
+}
+
+/// Applies bold formatting to the provided format string.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", bold!("This text is bold"));
+/// ```
+#[macro_export]
+macro_rules! bold {
+    ($($arg:tt)*) => {{
+        $crate::apply_color!($crate::codes::BOLD, $($arg)*)
+    }};
+}
+
+/// Applies italic formatting to the provided format string.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", italic!("This text is italic"));
+/// ```
+#[macro_export]
+macro_rules! italic {
+    ($($arg:tt)*) => {{
+        $crate::apply_color!($crate::codes::ITALIC, $($arg)*)
+    }};
+}
+
+/// Applies underline formatting to the provided format string.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", underline!("This text is underlined"));
+/// ```
+#[macro_export]
+macro_rules! underline {
+    ($($arg:tt)*) => {{
+        $crate::apply_color!($crate::codes::UNDERLINE, $($arg)*)
+    }};
+}
+
+/// Applies double underline formatting to the provided format string.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", double_underline!("This text is double underlined"));
+/// ```
+#[macro_export]
+macro_rules! double_underline {
+    ($($arg:tt)*) => {{
+        $crate::apply_color!($crate::codes::DOUBLE_UNDERLINE, $($arg)*)
+    }};
+}
+
+/// Applies a double underline in the extended underline form to the provided format string.
+///
+/// Emits SGR `4:2`, or a plain underline on terminals without extended underline support.
+/// Unlike [`double_underline!`], which emits SGR 21, this cannot be mistaken for
+/// "bold off" by terminals that read 21 that way.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", extended_double_underline!("This text has a double underline"));
+/// ```
+#[macro_export]
+macro_rules! extended_double_underline {
+    ($($arg:tt)*) => {{
+        let color_code = $crate::underline_style_code(2);
+        $crate::apply_color!(&color_code, $($arg)*)
+    }};
+}
+
+/// Applies a curly underline to the provided format string.
+///
+/// Emits SGR `4:3`, or a plain underline on terminals without extended underline support.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", curly_underline!("This text has a curly underline"));
+/// ```
+#[macro_export]
+macro_rules! curly_underline {
+    ($($arg:tt)*) => {{
+        let color_code = $crate::underline_style_code(3);
+        $crate::apply_color!(&color_code, $($arg)*)
+    }};
+}
+
+/// Applies a dotted underline to the provided format string.
+///
+/// Emits SGR `4:4`, or a plain underline on terminals without extended underline support.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", dotted_underline!("This text has a dotted underline"));
+/// ```
+#[macro_export]
+macro_rules! dotted_underline {
+    ($($arg:tt)*) => {{
+        let color_code = $crate::underline_style_code(4);
+        $crate::apply_color!(&color_code, $($arg)*)
+    }};
+}
+
+/// Applies a dashed underline to the provided format string.
+///
+/// Emits SGR `4:5`, or a plain underline on terminals without extended underline support.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", dashed_underline!("This text has a dashed underline"));
+/// ```
+#[macro_export]
+macro_rules! dashed_underline {
+    ($($arg:tt)*) => {{
+        let color_code = $crate::underline_style_code(5);
+        $crate::apply_color!(&color_code, $($arg)*)
+    }};
+}
+
+/// Applies overline formatting to the provided format string.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", overline!("This text is overlined"));
+/// ```
+#[macro_export]
+macro_rules! overline {
+    ($($arg:tt)*) => {{
+        $crate::apply_color!($crate::codes::OVERLINE, $($arg)*)
+    }};
+}
+
+/// Applies strikethrough formatting to the provided format string.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", strikethrough!("This text is struck through"));
+/// ```
+#[macro_export]
+macro_rules! strikethrough {
+    ($($arg:tt)*) => {{
+        $crate::apply_color!($crate::codes::STRIKETHROUGH, $($arg)*)
+    }};
+}
+
+/// Applies dim (faint) formatting to the provided format string.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", dim!("This text is dimmed"));
+/// ```
+#[macro_export]
+macro_rules! dim {
+    ($($arg:tt)*) => {{
+        $crate::apply_color!($crate::codes::DIM, $($arg)*)
+    }};
+}
+
+/// Applies blinking formatting to the provided format string.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", blink!("This text blinks"));
+/// ```
+#[macro_export]
+macro_rules! blink {
+    ($($arg:tt)*) => {{
+        $crate::apply_color!($crate::codes::BLINK, $($arg)*)
+    }};
+}
+
+/// Applies rapid blinking formatting (not supported by all terminals) to the provided format string.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", rapid_blink!("This text blinks rapidly"));
+/// ```
+#[macro_export]
+macro_rules! rapid_blink {
+    ($($arg:tt)*) => {{
+        $crate::apply_color!($crate::codes::RAPID_BLINK, $($arg)*)
+    }};
+}
+
+/// Applies reverse video formatting (swapped foreground and background) to the provided format string.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", reverse!("This text is reversed"));
+/// ```
+#[macro_export]
+macro_rules! reverse {
+    ($($arg:tt)*) => {{
+        $crate::apply_color!($crate::codes::REVERSE, $($arg)*)
+    }};
+}
+
+/// Applies concealed (hidden) formatting to the provided format string.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", conceal!("This text is hidden"));
+/// ```
+#[macro_export]
+macro_rules! conceal {
+    ($($arg:tt)*) => {{
+        $crate::apply_color!($crate::codes::CONCEAL, $($arg)*)
+    }};
+}
+
+/// Applies a custom RGB background color to the provided format string.
+///
+/// # Arguments
+///
+/// * `$r` - The red component (0-255).
+/// * `$g` - The green component (0-255).
+/// * `$b` - The blue component (0-255).
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", bg_rgb!(100, 150, 200, "This has a custom RGB background"));
+/// ```
+#[macro_export]
+macro_rules! bg_rgb {
+    ($r:expr, $g:expr, $b:expr, $($arg:tt)*) => {{
+        let color_code = $crate::bg_rgb_code($r, $g, $b);
+        $crate::apply_color!(&color_code, $($arg)*)
+    }};
+}
+
+/// Applies an 8-bit indexed background color to the provided format string.
+///
+/// # Arguments
+///
+/// * `$idx` - The 256-color palette index (0-255).
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", bg_ansi256!(24, "This has a 256-color background"));
+/// ```
+#[macro_export]
+macro_rules! bg_ansi256 {
+    ($idx:expr, $($arg:tt)*) => {{
+        let color_code = format!("\x1b[48;5;{}m", $idx);
+        $crate::apply_color!(&color_code, $($arg)*)
+    }};
+}
+
+/// Applies a gray background from the 256-color grayscale ramp to the provided format string.
+///
+/// # Arguments
+///
+/// * `$level` - The grayscale level (0-23), from darkest to brightest.
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", bg_grayscale!(4, "This has a dark gray background"));
+/// ```
+#[macro_export]
+macro_rules! bg_grayscale {
+    ($level:expr, $($arg:tt)*) => {{
+        $crate::bg_ansi256!($crate::grayscale_index($level), $($arg)*)
+    }};
+}
+
+/// Applies a background color specified in HSL color space to the provided format string.
+///
+/// # Arguments
+///
+/// * `$h` - The hue component (0-360).
+/// * `$s` - The saturation component (0-1).
+/// * `$l` - The lightness component (0-1).
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", bg_hsl!(180.0, 0.5, 0.5, "This has an HSL-specified background"));
+/// ```
+#[macro_export]
+macro_rules! bg_hsl {
+    ($h:expr, $s:expr, $l:expr, $($arg:tt)*) => {{
+        let (r, g, b) = $crate::hsl_to_rgb($h, $s, $l);
+        $crate::bg_rgb!(r, g, b, $($arg)*)
+    }};
+}
+
+/// Applies a background color specified in HSV color space to the provided format string.
+///
+/// # Arguments
+///
+/// * `$h` - The hue component (0-360).
+/// * `$s` - The saturation component (0-1).
+/// * `$v` - The value component (0-1).
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", bg_hsv!(270.0, 0.7, 0.9, "This has an HSV-specified background"));
+/// ```
+#[macro_export]
+macro_rules! bg_hsv {
+    ($h:expr, $s:expr, $v:expr, $($arg:tt)*) => {{
+        let (r, g, b) = $crate::hsv_to_rgb($h, $s, $v);
+        $crate::bg_rgb!(r, g, b, $($arg)*)
+    }};
+}
+
+/// Applies a background color given as a hex string to the provided format string.
+///
+/// # Arguments
+///
+/// * `$hex` - The color as `"#RRGGBB"` or `"#RGB"` (the `#` is optional).
+/// * `$arg` - The format string and its arguments.
+///
+/// # Panics
+///
+/// Panics if `$hex` is not a valid hex color. Use [`hex_to_rgb`] to validate untrusted input.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", bg_hex!("#1e1e2e", "This has a hex-specified background"));
+/// ```
+#[macro_export]
+macro_rules! bg_hex {
+    ($hex:expr, $($arg:tt)*) => {{
+        let hex: &str = $hex;
+        let (r, g, b) = $crate::hex_to_rgb(hex)
+            .unwrap_or_else(|| panic!("invalid hex color: {:?}", hex));
+        $crate::bg_rgb!(r, g, b, $($arg)*)
+    }};
+}
+
+/// Applies a CSS named background color to the provided format string.
+///
+/// # Arguments
+///
+/// * `$name` - A CSS/X11 color name such as `"midnightblue"` (case-insensitive).
+/// * `$arg` - The format string and its arguments.
+///
+/// # Panics
+///
+/// Panics if `$name` is not a known color name. Use [`named_to_rgb`] to validate untrusted input.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", bg_named!("midnightblue", "This has a named background"));
+/// ```
+#[macro_export]
+macro_rules! bg_named {
+    ($name:expr, $($arg:tt)*) => {{
+        let name: &str = $name;
+        let (r, g, b) = $crate::named_to_rgb(name)
+            .unwrap_or_else(|| panic!("unknown color name: {:?}", name));
+        $crate::bg_rgb!(r, g, b, $($arg)*)
+    }};
+}
+
+/// Applies a background color specified in CMYK to the provided format string.
+///
+/// # Arguments
+///
+/// * `$c` - The cyan component (0-1).
+/// * `$m` - The magenta component (0-1).
+/// * `$y` - The yellow component (0-1).
+/// * `$k` - The key (black) component (0-1).
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", bg_cmyk!(1.0, 0.5, 0.0, 0.2, "This has a CMYK-specified background"));
+/// ```
+#[macro_export]
+macro_rules! bg_cmyk {
+    ($c:expr, $m:expr, $y:expr, $k:expr, $($arg:tt)*) => {{
+        let (r, g, b) = $crate::cmyk_to_rgb($c, $m, $y, $k);
+        $crate::bg_rgb!(r, g, b, $($arg)*)
+    }};
+}
+
+/// Applies a background color specified in CIELAB color space to the provided format string.
+///
+/// # Arguments
+///
+/// * `$l` - The lightness (0-100).
+/// * `$a` - The green-red axis (roughly -128 to 127).
+/// * `$b` - The blue-yellow axis (roughly -128 to 127).
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", bg_lab!(30.0, 20.0, -40.0, "This has a Lab-specified background"));
+/// ```
+#[macro_export]
+macro_rules! bg_lab {
+    ($l:expr, $a:expr, $b:expr, $($arg:tt)*) => {{
+        let (r, g, b) = $crate::lab_to_rgb($l, $a, $b);
+        $crate::bg_rgb!(r, g, b, $($arg)*)
+    }};
+}
+
+/// Applies a background color specified in OKLCH color space to the provided format string.
+///
+/// # Arguments
+///
+/// * `$l` - The perceptual lightness (0-1).
+/// * `$c` - The chroma (0 to roughly 0.4).
+/// * `$h` - The hue angle in degrees (0-360).
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", bg_oklch!(0.3, 0.05, 280.0, "This has an OKLCH-specified background"));
+/// ```
+#[macro_export]
+macro_rules! bg_oklch {
+    ($l:expr, $c:expr, $h:expr, $($arg:tt)*) => {{
+        let (r, g, b) = $crate::oklch_to_rgb($l, $c, $h);
+        $crate::bg_rgb!(r, g, b, $($arg)*)
+    }};
+}
+
+/// Applies a background color specified in HWB color space to the provided format string.
+///
+/// # Arguments
+///
+/// * `$h` - The hue component (0-360).
+/// * `$w` - The whiteness component (0-1).
+/// * `$b` - The blackness component (0-1).
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", bg_hwb!(120.0, 0.0, 0.5, "This has an HWB-specified background"));
+/// ```
+#[macro_export]
+macro_rules! bg_hwb {
+    ($h:expr, $w:expr, $b:expr, $($arg:tt)*) => {{
+        let (r, g, b) = $crate::hwb_to_rgb($h, $w, $b);
+        $crate::bg_rgb!(r, g, b, $($arg)*)
+    }};
+}
+
+/// Applies a background color specified in HSLuv color space to the provided format string.
+///
+/// # Arguments
+///
+/// * `$h` - The hue component (0-360).
+/// * `$s` - The saturation component (0-100).
+/// * `$l` - The lightness component (0-100).
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", bg_hsluv!(250.0, 80.0, 30.0, "This has an HSLuv-specified background"));
+/// ```
+#[macro_export]
+macro_rules! bg_hsluv {
+    ($h:expr, $s:expr, $l:expr, $($arg:tt)*) => {{
+        let (r, g, b) = $crate::hsluv_to_rgb($h, $s, $l);
+        $crate::bg_rgb!(r, g, b, $($arg)*)
+    }};
+}
+
+/// Applies an underline with a custom RGB underline color to the provided format string.
+///
+/// The underline color (SGR 58) is independent of the text color, so this can be
+/// nested inside any foreground color macro.
+///
+/// # Arguments
+///
+/// * `$r` - The red component (0-255).
+/// * `$g` - The green component (0-255).
+/// * `$b` - The blue component (0-255).
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", underline_rgb!(255, 0, 0, "This has a red underline"));
+/// ```
+#[macro_export]
+macro_rules! underline_rgb {
+    ($r:expr, $g:expr, $b:expr, $($arg:tt)*) => {{
+        let color_code = format!("\x1b[4;58;2;{};{};{}m", $r, $g, $b);
+        $crate::apply_color!(&color_code, $($arg)*)
+    }};
+}
+
+/// Applies an underline with an 8-bit indexed underline color to the provided format string.
+///
+/// # Arguments
+///
+/// * `$idx` - The 256-color palette index (0-255).
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", underline_ansi256!(196, "This has a red underline"));
+/// ```
+#[macro_export]
+macro_rules! underline_ansi256 {
+    ($idx:expr, $($arg:tt)*) => {{
+        let color_code = format!("\x1b[4;58;5;{}m", $idx);
+        $crate::apply_color!(&color_code, $($arg)*)
+    }};
+}
+
+/// Formats the arguments and re-applies the active styles on every line.
+///
+/// Equivalent to [`linewise`] applied to the formatted string: styles are
+/// closed before each newline and re-opened after it, so multi-line colored
+/// output survives `less -R` and line-oriented log viewers.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// let report = linewise!("{}", red!("error:\n  disk full"));
+/// println!("{}", report);
+/// ```
+#[macro_export]
+macro_rules! linewise {
+    ($($arg:tt)*) => {{
+        $crate::linewise(&format!($($arg)*))
+    }};
+}
+
+/// Colors each character of the formatted text along a gradient between two RGB colors.
+///
+/// The colors are interpolated linearly from `$start` on the first visible
+/// character to `$end` on the last. Like the other color macros, the output
+/// ends with a reset and nests inside enclosing colors and styles.
+///
+/// # Arguments
+///
+/// * `$start` - The `(r, g, b)` color of the first character.
+/// * `$end` - The `(r, g, b)` color of the last character.
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", gradient!((255, 0, 128), (0, 128, 255), "Welcome to {}", "term_ansi"));
+/// ```
+#[macro_export]
+macro_rules! gradient {
+    ($start:expr, $end:expr, $($arg:tt)*) => {{
+        $crate::apply_color!("", "{}", $crate::gradient(&format!($($arg)*), $start, $end))
+    }};
+}
+
+/// Colors the background of each character of the formatted text along a
+/// gradient between two RGB colors.
+///
+/// The foreground is not touched, so wrap the call in a color macro such as
+/// `black!` or `white!` to keep the text readable across the whole gradient.
+///
+/// # Arguments
+///
+/// * `$start` - The `(r, g, b)` background of the first character.
+/// * `$end` - The `(r, g, b)` background of the last character.
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", black!("{}", bg_gradient!((0, 200, 80), (0, 80, 200), " Build {:<20}", "passed")));
+/// ```
+#[macro_export]
+macro_rules! bg_gradient {
+    ($start:expr, $end:expr, $($arg:tt)*) => {{
+        $crate::apply_color!("", "{}", $crate::bg_gradient(&format!($($arg)*), $start, $end))
+    }};
+}
+
+/// Colors each line of the formatted text along a gradient between two RGB colors.
+///
+/// Every line starts with its own color code, which suits block banners and
+/// ASCII-art logos.
+///
+/// # Arguments
+///
+/// * `$start` - The `(r, g, b)` color of the first line.
+/// * `$end` - The `(r, g, b)` color of the last line.
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// let logo = " _   _\n| |_| |\n|  _  |\n|_| |_|";
+/// println!("{}", vgradient!((255, 200, 0), (255, 0, 100), "{}", logo));
+/// ```
+#[macro_export]
+macro_rules! vgradient {
+    ($start:expr, $end:expr, $($arg:tt)*) => {{
+        $crate::apply_color!("", "{}", $crate::vgradient(&format!($($arg)*), $start, $end))
+    }};
+}
+
+/// Applies a custom RGB background with a black or white foreground,
+/// whichever contrasts more with it.
+///
+/// Badges and labels built this way stay readable whatever background color
+/// is computed at runtime. See [`readable_fg`] for the choice itself.
+///
+/// # Arguments
+///
+/// * `$r` - The red component of the background (0-255).
+/// * `$g` - The green component of the background (0-255).
+/// * `$b` - The blue component of the background (0-255).
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// for (r, g, b) in [(255, 220, 0), (20, 40, 120)] {
+///     print!("{} ", auto_fg!(r, g, b, " v{} ", "1.2.0"));
+/// }
+/// println!();
+/// ```
+#[macro_export]
+macro_rules! auto_fg {
+    ($r:expr, $g:expr, $b:expr, $($arg:tt)*) => {{
+        let background: (u8, u8, u8) = ($r, $g, $b);
+        let (r, g, b) = $crate::readable_fg(background);
+        let color_code = format!(
+            "{}{}",
+            $crate::rgb_code(r, g, b),
+            $crate::bg_rgb_code(background.0, background.1, background.2)
+        );
+        $crate::apply_color!(&color_code, $($arg)*)
+    }};
+}
+
+/// Styles the formatted text as a success message, using the `success` style of the global [`Theme`].
+///
+/// The style is looked up on every call, so changes made with
+/// [`set_global_theme`] apply immediately.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", success!("Built {} crates in {}s", 12, 3));
+/// ```
+#[macro_export]
+macro_rules! success {
+    ($($arg:tt)*) => {{
+        $crate::themed!("success", $($arg)*)
+    }};
+}
+
+/// Styles the formatted text as an error message, using the `error` style of the global [`Theme`].
+///
+/// The style is looked up on every call, so changes made with
+/// [`set_global_theme`] apply immediately.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", failure!("Failed to open {}", "config.toml"));
+/// ```
+#[macro_export]
+macro_rules! failure {
+    ($($arg:tt)*) => {{
+        $crate::themed!("error", $($arg)*)
+    }};
+}
+
+/// Styles the formatted text as a warning, using the `warning` style of the global [`Theme`].
+///
+/// The style is looked up on every call, so changes made with
+/// [`set_global_theme`] apply immediately.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", warn_msg!("{} is deprecated", "--fast"));
+/// ```
+#[macro_export]
+macro_rules! warn_msg {
+    ($($arg:tt)*) => {{
+        $crate::themed!("warning", $($arg)*)
+    }};
+}
+
+/// Styles the formatted text as an informational message, using the `info` style of the global [`Theme`].
+///
+/// The style is looked up on every call, so changes made with
+/// [`set_global_theme`] apply immediately.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", info_msg!("Listening on port {}", 8080));
+/// ```
+#[macro_export]
+macro_rules! info_msg {
+    ($($arg:tt)*) => {{
+        $crate::themed!("info", $($arg)*)
+    }};
+}
+
+/// Styles the formatted text with the style of a named role in the global [`Theme`].
+///
+/// The role is looked up at runtime, so a library can mark up its output by
+/// meaning and leave the colors to the application's theme. An unknown role
+/// leaves the text unstyled. See [`Theme::ROLES`] for the names.
+///
+/// # Arguments
+///
+/// * `$role` - The role name, such as `"error"` or `"muted"`.
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// let path = "config.toml";
+/// println!("{} {}", themed!("error", "failed to open {}", path), themed!("muted", "(retrying)"));
+/// ```
+#[macro_export]
+macro_rules! themed {
+    ($role:expr, $($arg:tt)*) => {{
+        let style = $crate::theme().get($role).unwrap_or_default();
+        if style.is_plain() {
+            format!($($arg)*)
+        } else {
+            $crate::apply_color!(style.prefix(), $($arg)*)
+        }
+    }};
+}
+
+/// Formats the arguments and replaces HTML-like style tags with escape codes.
+///
+/// Equivalent to [`colorize_markup`] applied to the formatted string, so tags
+/// may come from the format string or from its arguments, e.g. a translated
+/// message. Values that may contain `<` should be formatted separately.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", markup!("<green>✓</green> {} tests <b>passed</b>", 42));
+/// ```
+#[macro_export]
+macro_rules! markup {
+    ($($arg:tt)*) => {{
+        $crate::colorize_markup(&format!($($arg)*))
+    }};
+}
+
+/// Formats text styled with HTML-like tags that are checked and converted
+/// to escape codes at compile time.
+///
+/// Tags are those of [`markup!`], limited to what is known without
+/// running the program: the standard and bright color names, 256-color
+/// indices, hex colors, attributes with their shorthands (`<b>`, `<i>`, `<u>`,
+/// `<s>`) and `on` for backgrounds. Theme roles and CSS color names need
+/// [`markup!`]. The rest of the string is a format string, with the same
+/// arguments as `format!`.
+///
+/// The expansion is a single `format_args!` call with the escape codes
+/// spliced in, so nothing is parsed or allocated at runtime. The codes are
+/// left out while colors are disabled. Each closing tag resets and restores
+/// the tags still open around it; the outermost one ends with a plain reset
+/// rather than restoring the color of an enclosing macro.
+///
+/// An unknown color or attribute, a closing tag that does not match, or a tag
+/// that is never closed is a compile error. Write `<<` for a literal `<`.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::styled;
+///
+/// let count = 3;
+/// assert_eq!(
+///     styled!("<bold red>{count}</bold red> errors in <u>{}</u>", "main.rs").to_string(),
+///     "\x1b[1;31m3\x1b[0m errors in \x1b[4mmain.rs\x1b[0m"
+/// );
+/// assert_eq!(
+///     styled!("<on blue>a <b>b</b> c</>").to_string(),
+///     "\x1b[44ma \x1b[1mb\x1b[0m\x1b[44m c\x1b[0m"
+/// );
+/// ```
+///
+/// A mismatched tag does not compile:
+///
+/// ```compile_fail
+/// let _ = term_ansi::styled!("<red>oops</blue>");
+/// ```
+#[cfg(feature = "macros")]
+#[macro_export]
+macro_rules! styled {
+    ($($input:tt)*) => {
+        $crate::__styled!($crate; $($input)*)
+    };
+}
+
+/// Formats the arguments with a combined foreground, background and set of
+/// attributes, written as a single escape sequence.
+///
+/// Properties come before a `;` and are separated by commas:
+///
+/// * `fg: $color`, `bg: $color` - A color name such as `red` or
+///   `bright_cyan`, or any expression convertible into a [`Color`], such as
+///   `Colours::Blue`, `(255, 136, 0)` or `Color::Ansi256(208)`.
+/// * `bold`, `dim`, `italic`, `underline`, `blink`, `reverse`, `conceal`,
+///   `strikethrough`, `double_underline`, `overline` - The [`Style`] attributes.
+///
+/// The rest is the format string and its arguments. Unlike nesting `red!`,
+/// `bg_black!` and `bold!`, the text is opened with one sequence and closed
+/// with one reset.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// let x = 3;
+/// assert_eq!(
+///     style!(fg: red, bg: black, bold, underline; "text {}", x),
+///     "\x1b[1;4;31;40mtext 3\x1b[0m\x1b[37m"
+/// );
+/// println!("{}", style!(fg: (255, 136, 0), italic; "orange"));
+/// ```
+#[macro_export]
+macro_rules! style {
+    ($($arg:tt)*) => {{
+        $crate::__style!($crate::Style::new(); $($arg)*)
+    }};
+}
+
+/// Applies the properties of [`style!`] one at a time.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __style {
+    ($style:expr; ; $($arg:tt)*) => {{
+        let style: $crate::Style = $style;
+        if style.is_plain() {
+            format!($($arg)*)
+        } else {
+            $crate::apply_color!(style.prefix(), $($arg)*)
+        }
+    }};
+    ($style:expr; , $($rest:tt)*) => {
+        $crate::__style!($style; $($rest)*)
+    };
+    ($style:expr; fg: $color:ident, $($rest:tt)*) => {
+        $crate::__style!($style.fg($crate::__color_name!($color)); $($rest)*)
+    };
+    ($style:expr; fg: $color:ident; $($rest:tt)*) => {
+        $crate::__style!($style.fg($crate::__color_name!($color)); ; $($rest)*)
+    };
+    ($style:expr; bg: $color:ident, $($rest:tt)*) => {
+        $crate::__style!($style.bg($crate::__color_name!($color)); $($rest)*)
+    };
+    ($style:expr; bg: $color:ident; $($rest:tt)*) => {
+        $crate::__style!($style.bg($crate::__color_name!($color)); ; $($rest)*)
+    };
+    ($style:expr; fg: $color:expr, $($rest:tt)*) => {
+        $crate::__style!($style.fg($color); $($rest)*)
+    };
+    ($style:expr; fg: $color:expr; $($rest:tt)*) => {
+        $crate::__style!($style.fg($color); ; $($rest)*)
+    };
+    ($style:expr; bg: $color:expr, $($rest:tt)*) => {
+        $crate::__style!($style.bg($color); $($rest)*)
+    };
+    ($style:expr; bg: $color:expr; $($rest:tt)*) => {
+        $crate::__style!($style.bg($color); ; $($rest)*)
+    };
+    ($style:expr; $attribute:ident $($rest:tt)*) => {
+        $crate::__style!($style.$attribute(); $($rest)*)
+    };
+}
+
+/// Maps a color name written in a macro, such as `red` or `bright_red`, to its
+/// [`Color`]. Any other identifier is passed through as a variable.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __color_name {
+    (black) => { $crate::Color::Basic($crate::Colours::Black) };
+    (red) => { $crate::Color::Basic($crate::Colours::Red) };
+    (green) => { $crate::Color::Basic($crate::Colours::Green) };
+    (yellow) => { $crate::Color::Basic($crate::Colours::Yellow) };
+    (blue) => { $crate::Color::Basic($crate::Colours::Blue) };
+    (magenta) => { $crate::Color::Basic($crate::Colours::Magenta) };
+    (cyan) => { $crate::Color::Basic($crate::Colours::Cyan) };
+    (white) => { $crate::Color::Basic($crate::Colours::White) };
+    (bright_black) => { $crate::Color::BrightBasic($crate::Colours::Black) };
+    (bright_red) => { $crate::Color::BrightBasic($crate::Colours::Red) };
+    (bright_green) => { $crate::Color::BrightBasic($crate::Colours::Green) };
+    (bright_yellow) => { $crate::Color::BrightBasic($crate::Colours::Yellow) };
+    (bright_blue) => { $crate::Color::BrightBasic($crate::Colours::Blue) };
+    (bright_magenta) => { $crate::Color::BrightBasic($crate::Colours::Magenta) };
+    (bright_cyan) => { $crate::Color::BrightBasic($crate::Colours::Cyan) };
+    (bright_white) => { $crate::Color::BrightBasic($crate::Colours::White) };
+    ($other:ident) => { $other };
+}
+
+/// Applies a basic color chosen at runtime to the provided format string.
+///
+/// The data-driven counterpart of [`red!`], [`green!`] and the other color
+/// macros, for when the color comes from configuration or, say, a log level.
+///
+/// # Arguments
+///
+/// * `$color` - The [`Colours`] value to apply.
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// let level = "warn";
+/// let colour = match level {
+///     "error" => Colours::Red,
+///     "warn" => Colours::Yellow,
+///     _ => Colours::White,
+/// };
+/// assert_eq!(color!(colour, "[{}]", level), yellow!("[warn]"));
+/// ```
+#[macro_export]
+macro_rules! color {
+    ($color:expr, $($arg:tt)*) => {{
+        let colour: $crate::Colours = $color;
+        $crate::apply_color!(colour.fg_code(), $($arg)*)
+    }};
+}
+
+/// Applies a basic background color chosen at runtime to the provided format string.
+///
+/// The data-driven counterpart of [`bg_red!`], [`bg_blue!`] and the other
+/// background macros. Combine it with [`color!`] to pick both colors from data.
+///
+/// # Arguments
+///
+/// * `$color` - The [`Colours`] value to apply as the background.
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// let (fg, bg) = (Colours::White, Colours::Blue);
+/// println!("{}", bg!(bg, "{}", color!(fg, " INFO ")));
+/// assert_eq!(bg!(Colours::Blue, "selected"), bg_blue!("selected"));
+/// ```
+#[macro_export]
+macro_rules! bg {
+    ($color:expr, $($arg:tt)*) => {{
+        let colour: $crate::Colours = $color;
+        $crate::apply_color!(colour.bg_code(), $($arg)*)
+    }};
+}
+
+/// Applies a sequence built from raw SGR parameters to the provided format string.
+///
+/// An escape hatch for terminal features without a macro of their own. The
+/// parameters are joined into a single `ESC [ ... m` sequence, which nests
+/// and resets like every other macro. An empty list leaves the text unstyled.
+///
+/// # Arguments
+///
+/// * `$param` - The SGR parameters, in brackets, e.g. `[1, 4, 38, 5, 208]`.
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// assert_eq!(sgr!([1, 4, 38, 5, 208], "text"), "\x1b[1;4;38;5;208mtext\x1b[0m\x1b[37m");
+/// // SGR 73: superscript, on the few terminals that support it.
+/// println!("x{}", sgr!([73], "2"));
+/// ```
+#[macro_export]
+macro_rules! sgr {
+    ([$($param:expr),* $(,)?], $($arg:tt)*) => {{
+        let params: &[u8] = &[$($param),*];
+        if params.is_empty() {
+            format!($($arg)*)
+        } else {
+            let params: Vec<String> = params.iter().map(|param| param.to_string()).collect();
+            let color_code = format!("\x1b[{}m", params.join(";"));
+            $crate::apply_color!(&color_code, $($arg)*)
+        }
+    }};
+}
+
+/// Prints the formatted text to standard output in the given color or style.
+///
+/// Like `print!("{}", red!(...))`, but the color code, the text and the reset
+/// are written straight to the locked stdout instead of being collected into
+/// `String`s first. Colors are written only if [`should_colorize`] allows them
+/// for [`Stream::Stdout`].
+///
+/// # Arguments
+///
+/// * `$color` - A color name such as `red` or `bright_cyan`, or any value
+///   convertible into a [`Style`], such as `Colours::Red` or `Style::new().bold()`.
+/// * `$arg` - The format string and its arguments.
+///
+/// # Panics
+///
+/// Panics if writing to stdout fails, like [`print!`].
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// cprint!(green, "{} ", "ok");
+/// cprint!(Style::new().fg(Colours::Red).bold(), "error");
+/// println!();
+/// ```
+#[macro_export]
+macro_rules! cprint {
+    ($color:ident, $($arg:tt)*) => {
+        $crate::cprint!($crate::__color_name!($color), $($arg)*)
+    };
+    ($color:expr, $($arg:tt)*) => {{
+        let scope = $crate::PrintScope::new(
+            $color,
+            $crate::should_colorize($crate::Stream::Stdout),
+        );
+        ::std::io::Write::write_fmt(
+            &mut ::std::io::stdout().lock(),
+            format_args!("{}", scope.wrap(format_args!($($arg)*), "")),
+        )
+        .unwrap_or_else(|error| panic!("failed printing to stdout: {}", error));
+    }};
+}
+
+/// Prints the formatted text and a newline to standard output in the given
+/// color or style.
+///
+/// The line-ending counterpart of [`cprint!`]. The newline comes after the
+/// reset, so a pager never sees a style spanning lines.
+///
+/// # Arguments
+///
+/// * `$color` - A color name such as `red` or `bright_cyan`, or any value
+///   convertible into a [`Style`].
+/// * `$arg` - The format string and its arguments.
+///
+/// # Panics
+///
+/// Panics if writing to stdout fails, like [`println!`].
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// let e = "permission denied";
+/// cprintln!(red, "error: {}", e);
+/// let warning = Colours::Yellow;
+/// cprintln!(warning, "warning: {}", bold!("low disk space"));
+/// ```
+#[macro_export]
+macro_rules! cprintln {
+    ($color:ident, $($arg:tt)*) => {
+        $crate::cprintln!($crate::__color_name!($color), $($arg)*)
+    };
+    ($color:expr, $($arg:tt)*) => {{
+        let scope = $crate::PrintScope::new(
+            $color,
+            $crate::should_colorize($crate::Stream::Stdout),
+        );
+        ::std::io::Write::write_fmt(
+            &mut ::std::io::stdout().lock(),
+            format_args!("{}", scope.wrap(format_args!($($arg)*), "\n")),
+        )
+        .unwrap_or_else(|error| panic!("failed printing to stdout: {}", error));
+    }};
+}
+
+/// Prints the formatted text to standard error in the given color or style.
+///
+/// The stderr counterpart of [`cprint!`]. Colors are decided for
+/// [`Stream::Stderr`] on its own, so errors stay colored when only stdout is
+/// piped to a file or another program.
+///
+/// # Arguments
+///
+/// * `$color` - A color name such as `red` or `bright_cyan`, or any value
+///   convertible into a [`Style`].
+/// * `$arg` - The format string and its arguments.
+///
+/// # Panics
+///
+/// Panics if writing to stderr fails, like [`eprint!`].
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// ceprint!(bright_red, "error: ");
+/// eprintln!("file not found");
+/// ```
+#[macro_export]
+macro_rules! ceprint {
+    ($color:ident, $($arg:tt)*) => {
+        $crate::ceprint!($crate::__color_name!($color), $($arg)*)
+    };
+    ($color:expr, $($arg:tt)*) => {{
+        let scope = $crate::PrintScope::new(
+            $color,
+            $crate::should_colorize($crate::Stream::Stderr),
+        );
+        ::std::io::Write::write_fmt(
+            &mut ::std::io::stderr().lock(),
+            format_args!("{}", scope.wrap(format_args!($($arg)*), "")),
+        )
+        .unwrap_or_else(|error| panic!("failed printing to stderr: {}", error));
+    }};
+}
+
+/// Prints the formatted text and a newline to standard error in the given
+/// color or style.
+///
+/// The stderr counterpart of [`cprintln!`], with the color decision made for
+/// [`Stream::Stderr`].
+///
+/// # Arguments
+///
+/// * `$color` - A color name such as `red` or `bright_cyan`, or any value
+///   convertible into a [`Style`].
+/// * `$arg` - The format string and its arguments.
+///
+/// # Panics
+///
+/// Panics if writing to stderr fails, like [`eprintln!`].
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// let path = "config.toml";
+/// ceprintln!(red, "error: cannot read {}", path);
+/// println!("this line may be piped while the error above stays colored");
+/// ```
+#[macro_export]
+macro_rules! ceprintln {
+    ($color:ident, $($arg:tt)*) => {
+        $crate::ceprintln!($crate::__color_name!($color), $($arg)*)
+    };
+    ($color:expr, $($arg:tt)*) => {{
+        let scope = $crate::PrintScope::new(
+            $color,
+            $crate::should_colorize($crate::Stream::Stderr),
+        );
+        ::std::io::Write::write_fmt(
+            &mut ::std::io::stderr().lock(),
+            format_args!("{}", scope.wrap(format_args!($($arg)*), "\n")),
+        )
+        .unwrap_or_else(|error| panic!("failed printing to stderr: {}", error));
+    }};
+}
+
+/// Writes the formatted text to `$dst` in the given color or style.
+///
+/// Works like [`write!`] with any destination that has a `write_fmt` method,
+/// both [`std::fmt::Write`] ones such as `String` and [`std::io::Write`] ones
+/// such as files, sockets and locked stdout, and returns its result. The
+/// destination cannot be checked for a terminal, so colors are written
+/// whenever [`colors_enabled`] is true.
+///
+/// # Arguments
+///
+/// * `$dst` - The destination to write to.
+/// * `$color` - A color name such as `red` or `bright_cyan`, or any value
+///   convertible into a [`Style`].
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use std::fmt::Write as _;
+/// use std::io::Write as _;
+/// use term_ansi::*;
+///
+/// let mut buffer = String::new();
+/// cwrite!(buffer, red, "{} failed", 2).unwrap();
+/// assert_eq!(buffer, red!("2 failed"));
+///
+/// let mut bytes: Vec<u8> = Vec::new();
+/// cwrite!(bytes, Colours::Green, "ok").unwrap();
+/// assert_eq!(bytes, green!("ok").into_bytes());
+/// ```
+#[macro_export]
+macro_rules! cwrite {
+    ($dst:expr, $color:ident, $($arg:tt)*) => {
+        $crate::cwrite!($dst, $crate::__color_name!($color), $($arg)*)
+    };
+    ($dst:expr, $color:expr, $($arg:tt)*) => {{
+        let scope = $crate::PrintScope::new($color, $crate::colors_enabled());
+        $dst.write_fmt(format_args!("{}", scope.wrap(format_args!($($arg)*), "")))
+    }};
+}
+
+/// Writes the formatted text and a newline to `$dst` in the given color or style.
+///
+/// The line-ending counterpart of [`cwrite!`], like [`writeln!`]. The
+/// newline comes after the reset.
+///
+/// # Arguments
+///
+/// * `$dst` - The destination to write to.
+/// * `$color` - A color name such as `red` or `bright_cyan`, or any value
+///   convertible into a [`Style`].
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use std::io::Write as _;
+/// use term_ansi::*;
+///
+/// # fn main() -> std::io::Result<()> {
+/// let mut log = std::io::BufWriter::new(std::io::stderr());
+/// cwriteln!(log, yellow, "warning: {}", "disk almost full")?;
+/// # Ok(())
+/// # }
+/// ```
+#[macro_export]
+macro_rules! cwriteln {
+    ($dst:expr, $color:ident, $($arg:tt)*) => {
+        $crate::cwriteln!($dst, $crate::__color_name!($color), $($arg)*)
+    };
+    ($dst:expr, $color:expr, $($arg:tt)*) => {{
+        let scope = $crate::PrintScope::new($color, $crate::colors_enabled());
+        $dst.write_fmt(format_args!("{}", scope.wrap(format_args!($($arg)*), "\n")))
+    }};
+}
+
+/// Formats the arguments as the text of a link to `$url`.
+///
+/// Equivalent to [`hyperlink()`] applied to the formatted string: terminals
+/// with OSC 8 support show clickable text, and elsewhere the result reads
+/// `text (url)`.
+///
+/// # Arguments
+///
+/// * `$url` - The link target, a `&str` or `String`.
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// let issue = 42;
+/// println!("see {}", hyperlink!(format!("https://example.com/issues/{}", issue), "#{}", issue));
+/// ```
+#[macro_export]
+macro_rules! hyperlink {
+    ($url:expr, $($arg:tt)*) => {
+        $crate::hyperlink(&$url, &format!($($arg)*))
+    };
+}
+
+/// Returns the escape sequence that erases the current line, or part of it.
+///
+/// The cursor does not move, so redraw a status line by returning to the
+/// first column with `\r` before printing the new text. Erasing does not
+/// depend on [`colors_enabled`]; check that the output is a terminal first.
+///
+/// # Arguments
+///
+/// * no argument - Erase the whole line ([`codes::CLEAR_LINE`]).
+/// * `right` - Erase from the cursor to the end of the line.
+/// * `left` - Erase from the start of the line to the cursor.
+///
+/// # Example
+///
+/// ```
+/// use std::io::Write;
+/// use term_ansi::*;
+///
+/// for file in ["a.rs", "main.rs"] {
+///     print!("\r{}compiling {}", clear_line!(), file);
+///     std::io::stdout().flush().unwrap();
+/// }
+/// println!("\r{}{}", clear_line!(), green!("done"));
+/// assert_eq!(clear_line!(right), "\x1b[0K");
+/// ```
+#[macro_export]
+macro_rules! clear_line {
+    () => {
+        $crate::codes::CLEAR_LINE
+    };
+    (right) => {
+        $crate::codes::CLEAR_LINE_RIGHT
+    };
+    (left) => {
+        $crate::codes::CLEAR_LINE_LEFT
+    };
+}
+
+/// Returns the escape sequence that erases the screen, or part of it.
+///
+/// Like [`clear_line!`], erasing does not depend on [`colors_enabled`].
+///
+/// # Arguments
+///
+/// * no argument - Erase the whole screen and move the cursor to the top
+///   left corner, like the `clear` command ([`codes::CLEAR_SCREEN_HOME`]);
+///   [`codes::CLEAR_SCREEN`] erases without moving the cursor.
+/// * `below` - Erase from the cursor to the end of the screen, such as the
+///   lines left over from a longer previous redraw.
+/// * `above` - Erase from the start of the screen to the cursor.
+/// * `scrollback` - Erase the scrollback buffer, where the terminal supports it.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// print!("{}", clear_screen!());
+/// assert_eq!(clear_screen!(below), "\x1b[0J");
+/// ```
+#[macro_export]
+macro_rules! clear_screen {
+    () => {
+        $crate::codes::CLEAR_SCREEN_HOME
+    };
+    (below) => {
+        $crate::codes::CLEAR_SCREEN_BELOW
+    };
+    (above) => {
+        $crate::codes::CLEAR_SCREEN_ABOVE
+    };
+    (scrollback) => {
+        $crate::codes::CLEAR_SCROLLBACK
+    };
+}
+
+/// Formats the arguments as the terminal window and tab title.
+///
+/// Returns the OSC 0 sequence from [`set_title()`], to be printed. Wrap a
+/// long-running program in a [`SavedTitle`] to put the user's title back
+/// when it finishes.
+///
+/// # Arguments
+///
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```no_run
+/// use term_ansi::*;
+///
+/// let target = "release";
+/// print!("{}", set_title!("myapp – building {}…", target));
+/// ```
+#[macro_export]
+macro_rules! set_title {
+    ($($arg:tt)*) => {
+        $crate::set_title(&format!($($arg)*))
+    };
+}
+
+/// Formats the arguments and draws a box around them.
+///
+/// Equivalent to [`boxed()`] applied to the formatted string. The content may
+/// span several lines and be styled; the frame is sized by visible width.
+///
+/// # Arguments
+///
+/// * `$border` - Optional: the [`Border`] to draw, [`Border::Single`] by default.
+/// * `$style` - Optional, together with `$border` and followed by `;`: the
+///   style of the frame, any value convertible into a [`Style`].
+/// * `$arg` - The format string and its arguments.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// println!("{}", boxed!("{}\nall {} checks passed", bold!("Done"), 12));
+/// println!("{}", boxed!(Border::Double, Colours::Yellow; "Update available: {}", "0.3.0"));
+/// ```
+#[macro_export]
+macro_rules! boxed {
+    ($border:expr, $style:expr; $($arg:tt)*) => {
+        $crate::boxed(&format!($($arg)*), $border, $style)
+    };
+    ($($arg:tt)*) => {
+        $crate::boxed(&format!($($arg)*), $crate::Border::Single, $crate::Style::new())
+    };
+}
@@ -0,0 +1,198 @@
+//! Inline styling with tags, parsed at runtime.
+
+#[cfg(feature = "macros")]
+use std::fmt;
+
+use crate::{colors_enabled, theme, ColorContext, Style};
+
+/// Returns the style named by the contents of a tag: a theme role such as
+/// `error`, or a style spec with optional shorthands such as `b red`.
+fn tag_style(tag: &str) -> Option<Style> {
+    if tag.trim().is_empty() {
+        return None;
+    }
+    if let Some(style) = theme().get(tag.trim()) {
+        return Some(style);
+    }
+    let spec: Vec<&str> = tag
+        .split_whitespace()
+        .map(|word| match word.to_ascii_lowercase().as_str() {
+            "b" | "strong" => "bold",
+            "i" | "em" => "italic",
+            "u" => "underline",
+            "s" | "del" => "strikethrough",
+            _ => word,
+        })
+        .collect();
+    Style::parse(&spec.join(" "))
+}
+
+/// The output and the stack of open tags shared by both tag syntaxes.
+struct Painter<'a> {
+    output: String,
+    open: Vec<&'a str>,
+    enabled: bool,
+}
+
+impl<'a> Painter<'a> {
+    fn new(capacity: usize) -> Self {
+        Painter {
+            output: String::with_capacity(capacity),
+            open: Vec::new(),
+            enabled: colors_enabled(),
+        }
+    }
+
+    /// Opens `tag`, returning `false` if it names no style.
+    fn open(&mut self, tag: &'a str) -> bool {
+        let Some(style) = tag_style(tag) else {
+            return false;
+        };
+        self.open.push(tag);
+        if self.enabled {
+            let code = style.prefix();
+            ColorContext::push(&code);
+            self.output.push_str(&code);
+        }
+        true
+    }
+
+    /// Closes the innermost tag if `name` is empty or matches it, returning
+    /// whether a tag was closed.
+    fn close(&mut self, name: &str) -> bool {
+        match self.open.last() {
+            Some(&tag) if name.is_empty() || tag == name => {
+                self.open.pop();
+                if self.enabled {
+                    ColorContext::close_into(&mut self.output);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Closes every open tag and returns the output.
+    fn finish(mut self) -> String {
+        while self.close("") {}
+        self.output
+    }
+}
+
+/// Replaces HTML-like style tags in `markup` with escape codes.
+///
+/// An opening tag holds a style spec as accepted by [`Style::parse`], such as
+/// `<red>`, `<bold yellow on blue>` or `<#ff8800>`, one of the shorthands
+/// `<b>`, `<i>`, `<u>` and `<s>`, or a role of the global [`Theme`], such as
+/// `<error>`. It is closed by `</>` or by the same tag
+/// with a slash, like `</red>`. Tags nest through the [`ColorContext`], so a
+/// closing tag restores the enclosing styles, and tags left open are closed at
+/// the end. Anything that is not a valid tag, such as `a < b`, is kept as
+/// text. When colors are disabled the tags are removed.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::colorize_markup;
+///
+/// let template = "<red><b>error</b>: file not found</red>";
+/// assert_eq!(
+///     colorize_markup(template),
+///     "\x1b[31m\x1b[1merror\x1b[0m\x1b[31m: file not found\x1b[0m\x1b[37m"
+/// );
+/// ```
+pub fn colorize_markup(markup: &str) -> String {
+    let mut painter = Painter::new(markup.len());
+    let mut rest = markup;
+    while let Some(start) = rest.find('<') {
+        painter.output.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[1..end];
+        let handled = match tag.strip_prefix('/') {
+            Some(name) => painter.close(name),
+            None => painter.open(tag),
+        };
+        if handled {
+            rest = &rest[end + 1..];
+        } else {
+            painter.output.push('<');
+            rest = &rest[1..];
+        }
+    }
+    painter.output.push_str(rest);
+    painter.finish()
+}
+
+/// Replaces brace style tags in `template` with escape codes.
+///
+/// Tags are the same as for [`colorize_markup`] but written in braces:
+/// `{green}`, `{bold yellow}`, `{warning}`. `{/name}` closes the innermost
+/// tag if it is `name`, while `{/}` closes every open tag, like a reset.
+/// Anything else in braces, such as a `{count}` placeholder, is kept, as is
+/// `{{`, so the result can still be passed to a formatter that expects them.
+/// This suits strings from localization catalogs, where inline tags keep the
+/// styling next to the words it applies to.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::colorize_template;
+///
+/// assert_eq!(
+///     colorize_template("{bold}{green}OK{/} {count} files"),
+///     "\x1b[1m\x1b[32mOK\x1b[0m\x1b[37m {count} files"
+/// );
+/// ```
+pub fn colorize_template(template: &str) -> String {
+    let mut painter = Painter::new(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        painter.output.push_str(&rest[..start]);
+        rest = &rest[start..];
+        if rest.starts_with("{{") {
+            painter.output.push_str("{{");
+            rest = &rest[2..];
+            continue;
+        }
+        let Some(end) = rest.find('}') else {
+            break;
+        };
+        let tag = &rest[1..end];
+        let handled = match tag.strip_prefix('/') {
+            Some("") => {
+                while painter.close("") {}
+                true
+            }
+            Some(name) => painter.close(name),
+            None => painter.open(tag),
+        };
+        if handled {
+            rest = &rest[end + 1..];
+        } else {
+            painter.output.push('{');
+            rest = &rest[1..];
+        }
+    }
+    painter.output.push_str(rest);
+    painter.finish()
+}
+
+/// An escape code spliced in by the `styled!` macro, written only while
+/// colors are enabled.
+#[cfg(feature = "macros")]
+#[doc(hidden)]
+pub struct MarkupCode(pub &'static str);
+
+#[cfg(feature = "macros")]
+impl fmt::Display for MarkupCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if colors_enabled() {
+            f.write_str(self.0)
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,67 @@
+//! Recovering styled spans from text that contains escape sequences.
+
+use crate::sgr::sgr_params;
+use crate::text::escape_len;
+use crate::Style;
+
+/// An iterator over the styled spans of a string containing escape codes.
+///
+/// Each item is the [`Style`] in effect and the text it applies to. Color and
+/// style sequences update the style as a terminal would; other escape
+/// sequences, such as hyperlinks, are skipped. Colors that [`Style`] cannot
+/// represent, like underline colors, are ignored.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{bold, red, AnsiParser, Colours, Style};
+///
+/// let text = format!("{} done", red!("{} failed", bold!("1")));
+/// let spans: Vec<(Style, &str)> = AnsiParser::new(&text).collect();
+///
+/// assert_eq!(spans[0], (Style::new().fg(Colours::Red).bold(), "1"));
+/// assert_eq!(spans[1], (Style::new().fg(Colours::Red), " failed"));
+/// assert_eq!(spans[2], (Style::new().fg(Colours::White), " done"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct AnsiParser<'a> {
+    rest: &'a str,
+    style: Style,
+}
+
+impl<'a> AnsiParser<'a> {
+    /// Creates a parser over `text`, starting from a plain style.
+    pub fn new(text: &'a str) -> Self {
+        AnsiParser {
+            rest: text,
+            style: Style::new(),
+        }
+    }
+
+    /// Returns the style in effect at the current position.
+    pub fn style(&self) -> Style {
+        self.style
+    }
+}
+
+impl<'a> Iterator for AnsiParser<'a> {
+    type Item = (Style, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.rest.starts_with('\x1b') {
+            let (sequence, rest) = self.rest.split_at(escape_len(self.rest));
+            for params in sgr_params(sequence) {
+                self.style.apply_sgr(params);
+            }
+            self.rest = rest;
+        }
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        let end = self.rest.find('\x1b').unwrap_or(self.rest.len());
+        let (span, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        Some((self.style, span))
+    }
+}
@@ -0,0 +1,114 @@
+//! Writing styled text straight to a stream or writer, without building a `String`.
+
+use std::fmt::{self, Write};
+
+use crate::{codes, reset_all, ColorContext, Style};
+
+/// The style of a direct-print macro, held on the color context while its
+/// arguments are formatted so that nested macro output restores it.
+#[doc(hidden)]
+pub struct PrintScope {
+    style: Style,
+    /// The code that restores the enclosing color after the reset.
+    restore: String,
+    /// The code nested output uses to restore this style.
+    own: String,
+    enabled: bool,
+}
+
+impl PrintScope {
+    /// Pushes `style` onto the color context if `enabled` and the style is not plain.
+    pub fn new(style: impl Into<Style>, enabled: bool) -> Self {
+        let style = style.into();
+        let enabled = enabled && !style.is_plain();
+        let mut restore = String::new();
+        let mut own = String::new();
+        if enabled {
+            restore = ColorContext::restore_code();
+            ColorContext::push(&style.prefix());
+            own = ColorContext::current_color();
+        }
+        PrintScope {
+            style,
+            restore,
+            own,
+            enabled,
+        }
+    }
+
+    /// Returns `args` between the style's code and a reset, followed by the
+    /// enclosing color and `end`, ready to pass to any `write_fmt`.
+    pub fn wrap<'a>(&'a self, args: fmt::Arguments<'a>, end: &'a str) -> Wrapped<'a> {
+        Wrapped {
+            scope: self,
+            args,
+            end,
+        }
+    }
+}
+
+impl Drop for PrintScope {
+    fn drop(&mut self) {
+        if self.enabled {
+            ColorContext::pop();
+        }
+    }
+}
+
+/// The text written by a direct-print macro, returned by [`PrintScope::wrap`].
+#[doc(hidden)]
+pub struct Wrapped<'a> {
+    scope: &'a PrintScope,
+    args: fmt::Arguments<'a>,
+    end: &'a str,
+}
+
+impl fmt::Display for Wrapped<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scope = self.scope;
+        if scope.enabled {
+            write!(f, "{}", scope.style)?;
+            let mut content = Content {
+                f: &mut *f,
+                own: &scope.own,
+                held: false,
+            };
+            content.write_fmt(self.args)?;
+            // Like `apply_color!`, leave out the restore of nested output
+            // that ends the text, along with the reset that would undo it.
+            if !content.held {
+                f.write_str(reset_all())?;
+            }
+            write!(f, "{}{}", scope.restore, self.end)
+        } else {
+            write!(f, "{}{}", self.args, self.end)
+        }
+    }
+}
+
+/// Forwards formatted arguments, holding back `own` when a chunk ends with a
+/// reset followed by it, until it turns out not to be the end of the text.
+struct Content<'a, 'b> {
+    f: &'a mut fmt::Formatter<'b>,
+    own: &'a str,
+    held: bool,
+}
+
+impl Write for Content<'_, '_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if s.is_empty() {
+            return Ok(());
+        }
+        if self.held {
+            self.f.write_str(self.own)?;
+            self.held = false;
+        }
+        match s.strip_suffix(self.own) {
+            Some(head) if !self.own.is_empty() && head.ends_with(codes::RESET) => {
+                self.held = true;
+                self.f.write_str(head)
+            }
+            _ => self.f.write_str(s),
+        }
+    }
+}
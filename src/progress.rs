@@ -0,0 +1,300 @@
+//! Progress bars and spinners rendered to strings, for callers that drive
+//! their own redraws.
+
+use crate::{codes, colors_enabled, lerp_color, rgb_code, theme, visible_width, Style};
+
+/// How the filled part of a [`ProgressBar`] is colored.
+#[derive(Debug, Clone, PartialEq)]
+enum Fill {
+    /// The `success` role of the global theme.
+    Theme,
+    Style(Style),
+    Gradient((u8, u8, u8), (u8, u8, u8)),
+    /// Styles from the fraction they apply at, sorted by fraction.
+    Thresholds(Vec<(f64, Style)>),
+}
+
+/// A progress bar formatter.
+///
+/// [`ProgressBar::render`] draws the bar for one fraction and width and
+/// returns it; printing, timing and redrawing are up to the caller, for
+/// example with `\r` and [`clear_line!`](crate::clear_line). Widths are
+/// counted in terminal cells with [`visible_width`], so wide or styled glyphs
+/// still add up to the requested width.
+///
+/// The filled part uses the `success` role of the global [`Theme`](crate::Theme)
+/// and the empty part the `muted` role, unless set with the builder methods.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{strip_ansi, Colours, ProgressBar, Style};
+///
+/// let bar = ProgressBar::new().glyphs("=", "-").thresholds([
+///     (0.0, Style::new().fg(Colours::Red)),
+///     (0.5, Style::new().fg(Colours::Yellow)),
+///     (0.9, Style::new().fg(Colours::Green)),
+/// ]);
+/// let line = format!("[{}] {:>3}%", bar.render(0.4, 10), 40);
+/// assert_eq!(strip_ansi(&line), "[====------]  40%");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgressBar {
+    filled: String,
+    empty: String,
+    fill: Fill,
+    empty_style: Option<Style>,
+}
+
+impl ProgressBar {
+    /// Creates a bar drawn with `█` for the filled part and `░` for the rest.
+    pub fn new() -> Self {
+        ProgressBar {
+            filled: String::from("█"),
+            empty: String::from("░"),
+            fill: Fill::Theme,
+            empty_style: None,
+        }
+    }
+
+    /// Sets the glyphs repeated for the filled and the empty part, such as
+    /// `"="` and `" "`. Each may be several characters wide.
+    pub fn glyphs(self, filled: &str, empty: &str) -> Self {
+        ProgressBar {
+            filled: filled.to_string(),
+            empty: empty.to_string(),
+            ..self
+        }
+    }
+
+    /// Colors the filled part with one style.
+    pub fn style(self, style: impl Into<Style>) -> Self {
+        ProgressBar {
+            fill: Fill::Style(style.into()),
+            ..self
+        }
+    }
+
+    /// Colors the filled part along a gradient from `start` at the left edge
+    /// of the bar to `end` at the right edge.
+    ///
+    /// Each glyph keeps its color as the bar grows, so a nearly full bar shows
+    /// most of the gradient.
+    pub fn gradient(self, start: (u8, u8, u8), end: (u8, u8, u8)) -> Self {
+        ProgressBar {
+            fill: Fill::Gradient(start, end),
+            ..self
+        }
+    }
+
+    /// Colors the filled part by how far along the bar is: with the style of
+    /// the highest threshold at or below the current fraction, such as red
+    /// from `0.0`, yellow from `0.5` and green from `0.9`.
+    ///
+    /// Below the lowest threshold the filled part is unstyled.
+    pub fn thresholds(self, thresholds: impl IntoIterator<Item = (f64, Style)>) -> Self {
+        let mut thresholds: Vec<(f64, Style)> = thresholds.into_iter().collect();
+        thresholds.sort_by(|a, b| a.0.total_cmp(&b.0));
+        ProgressBar {
+            fill: Fill::Thresholds(thresholds),
+            ..self
+        }
+    }
+
+    /// Sets the style of the empty part.
+    pub fn empty_style(self, style: impl Into<Style>) -> Self {
+        ProgressBar {
+            empty_style: Some(style.into()),
+            ..self
+        }
+    }
+
+    /// Returns the bar `width` cells wide, filled to `fraction`, which is
+    /// clamped to `0.0..=1.0`.
+    ///
+    /// Only whole glyphs are drawn; cells left over when a glyph is wider than
+    /// one cell are padded with spaces. The result ends with a reset if any
+    /// style was applied.
+    pub fn render(&self, fraction: f64, width: usize) -> String {
+        let fraction = if fraction.is_nan() {
+            0.0
+        } else {
+            fraction.clamp(0.0, 1.0)
+        };
+        let filled_width = visible_width(&self.filled).max(1);
+        let empty_width = visible_width(&self.empty).max(1);
+        let filled_count = (fraction * width as f64) as usize / filled_width;
+        let empty_count = (width - filled_count * filled_width) / empty_width;
+        let padding = width - filled_count * filled_width - empty_count * empty_width;
+
+        let mut bar = String::with_capacity(width * 4);
+        if !colors_enabled() {
+            bar.push_str(&self.filled.repeat(filled_count));
+            bar.push_str(&self.empty.repeat(empty_count));
+            bar.push_str(&" ".repeat(padding));
+            return bar;
+        }
+
+        let theme = theme();
+        if filled_count > 0 {
+            match &self.fill {
+                Fill::Gradient(start, end) => {
+                    let steps = (width / filled_width).saturating_sub(1).max(1);
+                    let mut last_code = String::new();
+                    for i in 0..filled_count {
+                        let (r, g, b) = lerp_color(*start, *end, i as f64 / steps as f64);
+                        let code = rgb_code(r, g, b);
+                        if code != last_code {
+                            bar.push_str(&code);
+                            last_code = code;
+                        }
+                        bar.push_str(&self.filled);
+                    }
+                    bar.push_str(codes::RESET);
+                }
+                Fill::Theme => {
+                    push_styled(&mut bar, theme.success, &self.filled.repeat(filled_count))
+                }
+                Fill::Style(style) => {
+                    push_styled(&mut bar, *style, &self.filled.repeat(filled_count))
+                }
+                Fill::Thresholds(thresholds) => {
+                    let style = thresholds
+                        .iter()
+                        .rev()
+                        .find(|(at, _)| fraction >= *at)
+                        .map(|(_, style)| *style)
+                        .unwrap_or_default();
+                    push_styled(&mut bar, style, &self.filled.repeat(filled_count));
+                }
+            }
+        }
+        if empty_count > 0 {
+            let style = self.empty_style.unwrap_or(theme.muted);
+            push_styled(&mut bar, style, &self.empty.repeat(empty_count));
+        }
+        bar.push_str(&" ".repeat(padding));
+        bar
+    }
+}
+
+impl Default for ProgressBar {
+    fn default() -> Self {
+        ProgressBar::new()
+    }
+}
+
+/// A spinner that returns one styled frame at a time.
+///
+/// Each call to [`Spinner::tick`] returns the next frame; timing and
+/// redrawing are up to the caller, for example printing `\r` and the frame
+/// every 80 milliseconds. Frames are padded to the widest one with
+/// [`visible_width`], so text after the spinner does not jump around.
+///
+/// Frames cycle through the colors set with [`Spinner::colors`], one per
+/// frame, or use the `accent` role of the global [`Theme`](crate::Theme).
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{strip_ansi, Colours, Spinner};
+///
+/// let mut spinner = Spinner::line().colors([Colours::Cyan, Colours::Blue]);
+/// let frames: Vec<String> = (0..5).map(|_| strip_ansi(&spinner.tick())).collect();
+/// assert_eq!(frames, ["-", "\\", "|", "/", "-"]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Spinner {
+    frames: Vec<String>,
+    colors: Vec<Style>,
+    width: usize,
+    tick: usize,
+}
+
+impl Spinner {
+    /// The braille dots used by most command-line tools.
+    pub const DOTS: [&'static str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+    /// A line turning in ASCII, for terminals without Unicode fonts.
+    pub const LINE: [&'static str; 4] = ["-", "\\", "|", "/"];
+    /// A quarter circle going round.
+    pub const ARC: [&'static str; 6] = ["◜", "◠", "◝", "◞", "◡", "◟"];
+
+    /// Creates a spinner showing `frames` in order, then starting over.
+    pub fn new<S: Into<String>>(frames: impl IntoIterator<Item = S>) -> Self {
+        let frames: Vec<String> = frames.into_iter().map(Into::into).collect();
+        let width = frames
+            .iter()
+            .map(|frame| visible_width(frame))
+            .max()
+            .unwrap_or(0);
+        Spinner {
+            frames,
+            colors: Vec::new(),
+            width,
+            tick: 0,
+        }
+    }
+
+    /// Creates a spinner with the [`Spinner::DOTS`] frames.
+    pub fn dots() -> Self {
+        Spinner::new(Spinner::DOTS)
+    }
+
+    /// Creates a spinner with the [`Spinner::LINE`] frames.
+    pub fn line() -> Self {
+        Spinner::new(Spinner::LINE)
+    }
+
+    /// Creates a spinner with the [`Spinner::ARC`] frames.
+    pub fn arc() -> Self {
+        Spinner::new(Spinner::ARC)
+    }
+
+    /// Sets the styles that successive frames cycle through.
+    pub fn colors<S: Into<Style>>(self, colors: impl IntoIterator<Item = S>) -> Self {
+        Spinner {
+            colors: colors.into_iter().map(Into::into).collect(),
+            ..self
+        }
+    }
+
+    /// Returns the next frame and advances the spinner.
+    pub fn tick(&mut self) -> String {
+        let frame = self.frame_at(self.tick);
+        self.tick = self.tick.wrapping_add(1);
+        frame
+    }
+
+    /// Returns frame `n`, counted from the first, without advancing the
+    /// spinner; useful for picking the frame from the time elapsed.
+    pub fn frame_at(&self, n: usize) -> String {
+        if self.frames.is_empty() {
+            return String::new();
+        }
+        let frame = &self.frames[n % self.frames.len()];
+        let padding = " ".repeat(self.width - visible_width(frame));
+        if !colors_enabled() {
+            return format!("{}{}", frame, padding);
+        }
+
+        let style = match self.colors.len() {
+            0 => theme().accent,
+            len => self.colors[n % len],
+        };
+        let mut output = String::new();
+        push_styled(&mut output, style, frame);
+        output.push_str(&padding);
+        output
+    }
+}
+
+/// Appends `text` in `style`, followed by a reset unless the style is plain.
+fn push_styled(output: &mut String, style: Style, text: &str) {
+    if style.is_plain() {
+        output.push_str(text);
+    } else {
+        output.push_str(&style.prefix());
+        output.push_str(text);
+        output.push_str(codes::RESET);
+    }
+}
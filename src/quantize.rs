@@ -0,0 +1,225 @@
+//! Mapping of 24-bit colors onto the palettes of terminals without truecolor.
+
+use std::sync::RwLock;
+
+use crate::convert::rgb_to_oklab;
+use crate::{color_support, Color, ColorSupport, Colours};
+
+/// The channel levels of the 6×6×6 color cube at indices 16-231.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The xterm defaults for the 16 basic and bright colors.
+const BASIC16: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+static BASIC_PALETTE: RwLock<Option<[(u8, u8, u8); 16]>> = RwLock::new(None);
+
+/// Sets the RGB values assumed for the 16 basic and bright colors when RGB
+/// colors are downgraded for 16-color terminals, or `None` for the xterm
+/// defaults.
+///
+/// Pass the palette from [`terminal_palette`](crate::terminal_palette) so
+/// that downgrading picks the closest color in the user's actual color
+/// scheme.
+///
+/// # Example
+///
+/// ```no_run
+/// use term_ansi::{set_basic_palette, terminal_palette};
+///
+/// set_basic_palette(terminal_palette());
+/// ```
+pub fn set_basic_palette(palette: Option<[(u8, u8, u8); 16]>) {
+    *BASIC_PALETTE.write().unwrap_or_else(|e| e.into_inner()) = palette;
+}
+
+/// Returns the RGB values assumed for the 16 basic and bright colors: the
+/// palette passed to [`set_basic_palette`], or the xterm defaults.
+pub fn basic_palette() -> [(u8, u8, u8); 16] {
+    BASIC_PALETTE
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .unwrap_or(BASIC16)
+}
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (i32::from(x) - i32::from(y)).pow(2) as u32;
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+/// Converts an RGB color to the closest 256-color palette index.
+///
+/// Candidates are the 6×6×6 color cube (indices 16-231) and the grayscale ramp
+/// (232-255); the 16 basic colors are skipped because terminals theme them.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{ansi256, rgb_to_ansi256};
+///
+/// assert_eq!(rgb_to_ansi256(255, 0, 0), 196);
+/// assert_eq!(rgb_to_ansi256(128, 128, 128), 244);
+///
+/// for i in 0..=10u8 {
+///     print!("{}", ansi256!(rgb_to_ansi256(i * 25, 0, 255 - i * 25), "█"));
+/// }
+/// println!();
+/// ```
+pub fn rgb_to_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    let cube_index = |v: u8| match v {
+        0..=47 => 0,
+        48..=114 => 1,
+        _ => (v - 35) / 40,
+    };
+    let (ri, gi, bi) = (cube_index(r), cube_index(g), cube_index(b));
+    let cube = (
+        CUBE_LEVELS[usize::from(ri)],
+        CUBE_LEVELS[usize::from(gi)],
+        CUBE_LEVELS[usize::from(bi)],
+    );
+
+    let average = (u32::from(r) + u32::from(g) + u32::from(b)) / 3;
+    let gray_index = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray_level = 8 + 10 * gray_index;
+    let gray = (gray_level, gray_level, gray_level);
+
+    if distance((r, g, b), gray) < distance((r, g, b), cube) {
+        232 + gray_index
+    } else {
+        16 + 36 * ri + 6 * gi + bi
+    }
+}
+
+/// Converts an RGB color to the closest of the 16 basic and bright colors.
+///
+/// Closeness is measured in OKLab, so the match follows perceived color rather
+/// than raw channel differences. The palette assumed is [`basic_palette`],
+/// the xterm default unless changed with [`set_basic_palette`].
+/// The result is a [`Color::Basic`] or [`Color::BrightBasic`].
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{rgb_to_basic16, Color, Colours};
+///
+/// assert_eq!(rgb_to_basic16(200, 10, 10), Color::Basic(Colours::Red));
+/// assert_eq!(rgb_to_basic16(255, 250, 80), Color::BrightBasic(Colours::Yellow));
+/// ```
+pub fn rgb_to_basic16(r: u8, g: u8, b: u8) -> Color {
+    let palette = basic_palette();
+    let (l, a, b) = rgb_to_oklab(r, g, b);
+    let distance = |&(pr, pg, pb): &(u8, u8, u8)| {
+        let (pl, pa, pb) = rgb_to_oklab(pr, pg, pb);
+        (l - pl).powi(2) + (a - pa).powi(2) + (b - pb).powi(2)
+    };
+    let index = (0..palette.len())
+        .min_by(|&i, &j| distance(&palette[i]).total_cmp(&distance(&palette[j])))
+        .unwrap_or(0);
+
+    let colour = Colours::ALL[index % 8];
+    if index < 8 {
+        Color::Basic(colour)
+    } else {
+        Color::BrightBasic(colour)
+    }
+}
+
+/// Returns the xterm default RGB value of a 256-color palette index.
+fn ansi256_to_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => basic_palette()[usize::from(index)],
+        16..=231 => {
+            let i = index - 16;
+            (
+                CUBE_LEVELS[usize::from(i / 36)],
+                CUBE_LEVELS[usize::from(i / 6 % 6)],
+                CUBE_LEVELS[usize::from(i % 6)],
+            )
+        }
+        _ => {
+            let level = 8 + 10 * (index - 232);
+            (level, level, level)
+        }
+    }
+}
+
+/// Returns the RGB value `color` is displayed as, taking the 16 basic and
+/// bright colors from [`basic_palette`].
+pub(crate) fn color_to_rgb(color: Color) -> (u8, u8, u8) {
+    match color {
+        Color::Basic(colour) => basic_palette()[colour as usize],
+        Color::BrightBasic(colour) => basic_palette()[colour as usize + 8],
+        Color::Ansi256(index) => ansi256_to_rgb(index),
+        Color::Rgb(r, g, b) => (r, g, b),
+    }
+}
+
+/// Returns the closest color the given support level can display.
+pub(crate) fn downgrade(color: Color, support: ColorSupport) -> Color {
+    match (support, color) {
+        (ColorSupport::Ansi256, Color::Rgb(r, g, b)) => Color::Ansi256(rgb_to_ansi256(r, g, b)),
+        (ColorSupport::Basic16, Color::Rgb(r, g, b)) => rgb_to_basic16(r, g, b),
+        (ColorSupport::Basic16, Color::Ansi256(index @ 0..=7)) => {
+            Color::Basic(Colours::ALL[usize::from(index)])
+        }
+        (ColorSupport::Basic16, Color::Ansi256(index @ 8..=15)) => {
+            Color::BrightBasic(Colours::ALL[usize::from(index - 8)])
+        }
+        (ColorSupport::Basic16, Color::Ansi256(index)) => {
+            let (r, g, b) = ansi256_to_rgb(index);
+            rgb_to_basic16(r, g, b)
+        }
+        _ => color,
+    }
+}
+
+/// Returns the SGR parameters selecting an RGB color at the given support level.
+pub(crate) fn rgb_params(r: u8, g: u8, b: u8, background: bool, support: ColorSupport) -> String {
+    let color = downgrade(Color::Rgb(r, g, b), support);
+    if background {
+        color.bg_params()
+    } else {
+        color.fg_params()
+    }
+}
+
+/// Returns the foreground escape code for an RGB color.
+///
+/// The color is quantized to the nearest palette entry when [`color_support`]
+/// reports a 256-color or 16-color terminal. This is the code used by
+/// [`rgb!`](crate::rgb) and every macro built on it.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{rgb_code, set_color_support, ColorSupport};
+///
+/// set_color_support(Some(ColorSupport::Ansi256));
+/// assert_eq!(rgb_code(255, 135, 0), "\x1b[38;5;208m");
+/// set_color_support(Some(ColorSupport::TrueColor));
+/// assert_eq!(rgb_code(255, 135, 0), "\x1b[38;2;255;135;0m");
+/// ```
+pub fn rgb_code(r: u8, g: u8, b: u8) -> String {
+    format!("\x1b[{}m", rgb_params(r, g, b, false, color_support()))
+}
+
+/// Returns the background escape code for an RGB color, quantized like [`rgb_code`].
+pub fn bg_rgb_code(r: u8, g: u8, b: u8) -> String {
+    format!("\x1b[{}m", rgb_params(r, g, b, true, color_support()))
+}
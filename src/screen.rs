@@ -0,0 +1,168 @@
+//! Switching to the alternate screen and saving the cursor, with guards that
+//! undo both.
+
+use std::io;
+
+/// Returns the sequence that switches to the alternate screen, a blank
+/// buffer without scrollback that full-screen programs draw on.
+///
+/// [`leave_alt_screen`] brings back the normal screen as it was. Prefer
+/// [`AltScreen`], which leaves even when the program panics.
+pub fn enter_alt_screen() -> &'static str {
+    "\x1b[?1049h"
+}
+
+/// Returns the sequence that switches back from the alternate screen to the
+/// normal one and its previous content.
+pub fn leave_alt_screen() -> &'static str {
+    "\x1b[?1049l"
+}
+
+/// Returns the sequence that saves the cursor position and style (DECSC).
+///
+/// Terminals keep a single saved position, so a second save replaces the
+/// first. Prefer [`SavedCursor`], which restores even when the program panics.
+pub fn save_cursor() -> &'static str {
+    "\x1b7"
+}
+
+/// Returns the sequence that moves the cursor back to the position saved by
+/// [`save_cursor`] and restores its style (DECRC).
+pub fn restore_cursor() -> &'static str {
+    "\x1b8"
+}
+
+/// A guard that shows the alternate screen until it is dropped.
+///
+/// Creating it switches `W` to the alternate screen, and dropping it switches
+/// back, including while unwinding from a panic, so an interactive picker
+/// cannot leave the terminal on a blank screen. Draw on it by writing to the
+/// guard or straight to the terminal.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::io::Write;
+/// use term_ansi::{clear_screen, AltScreen};
+///
+/// let mut screen = AltScreen::enter()?;
+/// write!(screen, "{}Pick a branch:", clear_screen!())?;
+/// screen.flush()?;
+/// // ... read the choice ...
+/// drop(screen);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct AltScreen<W: io::Write = io::Stdout> {
+    writer: W,
+}
+
+impl AltScreen {
+    /// Switches standard output to the alternate screen.
+    pub fn enter() -> io::Result<Self> {
+        AltScreen::enter_on(io::stdout())
+    }
+}
+
+impl<W: io::Write> AltScreen<W> {
+    /// Switches the terminal behind `writer` to the alternate screen.
+    pub fn enter_on(mut writer: W) -> io::Result<Self> {
+        writer.write_all(enter_alt_screen().as_bytes())?;
+        writer.flush()?;
+        Ok(AltScreen { writer })
+    }
+
+    /// Returns a reference to the wrapped writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Returns a mutable reference to the wrapped writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+}
+
+impl<W: io::Write> io::Write for AltScreen<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writer.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+impl<W: io::Write> Drop for AltScreen<W> {
+    fn drop(&mut self) {
+        // There is no way to report an error from a drop, and the terminal is
+        // most likely gone if writing fails.
+        let _ = self.writer.write_all(leave_alt_screen().as_bytes());
+        let _ = self.writer.flush();
+    }
+}
+
+/// A guard that moves the cursor back to where it was created when dropped.
+///
+/// Useful for drawing somewhere else on the screen, such as a status line at
+/// the bottom, and then continuing where the output left off. The position
+/// is restored while unwinding from a panic too. As the terminal keeps a
+/// single saved position, guards should not overlap.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::io::Write;
+/// use term_ansi::{clear_line, cursor, SavedCursor};
+///
+/// let mut saved = SavedCursor::save()?;
+/// write!(saved, "{}{}3 of 5 done", cursor::move_to(1, 1), clear_line!())?;
+/// drop(saved);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct SavedCursor<W: io::Write = io::Stdout> {
+    writer: W,
+}
+
+impl SavedCursor {
+    /// Saves the cursor position of standard output.
+    pub fn save() -> io::Result<Self> {
+        SavedCursor::save_on(io::stdout())
+    }
+}
+
+impl<W: io::Write> SavedCursor<W> {
+    /// Saves the cursor position of the terminal behind `writer`.
+    pub fn save_on(mut writer: W) -> io::Result<Self> {
+        writer.write_all(save_cursor().as_bytes())?;
+        Ok(SavedCursor { writer })
+    }
+
+    /// Returns a reference to the wrapped writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Returns a mutable reference to the wrapped writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+}
+
+impl<W: io::Write> io::Write for SavedCursor<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writer.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+impl<W: io::Write> Drop for SavedCursor<W> {
+    fn drop(&mut self) {
+        let _ = self.writer.write_all(restore_cursor().as_bytes());
+        let _ = self.writer.flush();
+    }
+}
@@ -0,0 +1,230 @@
+//! Tracking of the Select Graphic Rendition state set by escape codes.
+
+use crate::Color;
+
+/// The complete SGR state in effect at some point of a string, or at one
+/// level of the color context.
+///
+/// Every slot holds the raw parameters that set it, so codes this crate has
+/// no dedicated type for (curly underlines, underline colors, ...) survive a
+/// round trip unchanged.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct SgrState {
+    fg: Option<String>,
+    bg: Option<String>,
+    underline_color: Option<String>,
+    attributes: Vec<String>,
+}
+
+impl SgrState {
+    pub(crate) fn apply_code(&mut self, code: &str) {
+        for params in sgr_params(code) {
+            self.apply_params(params);
+        }
+    }
+
+    /// Applies the parameters of one SGR sequence, returning whether they include a reset.
+    pub(crate) fn apply_params(&mut self, params: &str) -> bool {
+        let mut reset = false;
+        for param in sgr_tokens(params) {
+            match param.code() {
+                Some(0) => {
+                    *self = SgrState::default();
+                    reset = true;
+                }
+                Some(30..=38 | 90..=97) => self.fg = Some(param.raw.to_string()),
+                Some(40..=48 | 100..=107) => self.bg = Some(param.raw.to_string()),
+                Some(58) => self.underline_color = Some(param.raw.to_string()),
+                Some(39) => self.fg = None,
+                Some(49) => self.bg = None,
+                Some(59) => self.underline_color = None,
+                Some(n) => self.apply_attribute(n, param.raw),
+                None => {}
+            }
+        }
+        reset
+    }
+
+    fn apply_attribute(&mut self, n: u8, token: &str) {
+        let cleared = cleared_slots(n);
+        if !cleared.is_empty() {
+            self.attributes
+                .retain(|attribute| !cleared.contains(&attribute_slot(attribute)));
+            return;
+        }
+        let slot = attribute_slot(token);
+        self.attributes
+            .retain(|attribute| attribute_slot(attribute) != slot);
+        self.attributes.push(token.to_string());
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.fg.is_none()
+            && self.bg.is_none()
+            && self.underline_color.is_none()
+            && self.attributes.is_empty()
+    }
+
+    /// Returns the SGR parameters that establish this state from a reset.
+    pub(crate) fn to_params(&self) -> String {
+        let params: Vec<&str> = self
+            .attributes
+            .iter()
+            .chain(&self.fg)
+            .chain(&self.bg)
+            .chain(&self.underline_color)
+            .map(String::as_str)
+            .collect();
+        params.join(";")
+    }
+
+    pub(crate) fn to_code(&self) -> String {
+        if self.is_empty() {
+            String::new()
+        } else {
+            format!("\x1b[{}m", self.to_params())
+        }
+    }
+}
+
+/// Returns the parameter lists of every SGR sequence in `code`.
+pub(crate) fn sgr_params(code: &str) -> impl Iterator<Item = &str> {
+    code.split("\x1b[").skip(1).filter_map(|sequence| {
+        let end = sequence.find(|c: char| !(c.is_ascii_digit() || c == ';' || c == ':'))?;
+        sequence[end..].starts_with('m').then(|| &sequence[..end])
+    })
+}
+
+/// Splits the parameters of one SGR sequence into [`SgrParam`]s.
+///
+/// This is the one place that knows how parameters are laid out, for
+/// [`SgrState`], [`Style`](crate::Style) parsing and color depth rewriting
+/// alike.
+pub(crate) fn sgr_tokens(params: &str) -> SgrTokens<'_> {
+    SgrTokens {
+        params,
+        next: Some(0),
+    }
+}
+
+/// The iterator returned by [`sgr_tokens`].
+pub(crate) struct SgrTokens<'a> {
+    params: &'a str,
+    /// The start of the next parameter, or `None` once all are read.
+    next: Option<usize>,
+}
+
+impl SgrTokens<'_> {
+    /// Returns the end of the `;`-separated value starting at `start`.
+    fn value_end(&self, start: usize) -> usize {
+        self.params[start..]
+            .find(';')
+            .map_or(self.params.len(), |i| start + i)
+    }
+}
+
+impl<'a> Iterator for SgrTokens<'a> {
+    type Item = SgrParam<'a>;
+
+    fn next(&mut self) -> Option<SgrParam<'a>> {
+        let start = self.next?;
+        let len = self.params.len();
+        let mut end = self.value_end(start);
+        let token = &self.params[start..end];
+        // The semicolon form of an extended color spreads its mode and
+        // values over the following parameters.
+        if matches!(token, "38" | "48" | "58") && end < len {
+            end = self.value_end(end + 1);
+            let count = match &self.params[start + token.len() + 1..end] {
+                "5" => 1,
+                "2" => 3,
+                _ => 0,
+            };
+            for _ in 0..count {
+                if end < len {
+                    end = self.value_end(end + 1);
+                }
+            }
+        }
+        self.next = (end < len).then_some(end + 1);
+        Some(SgrParam {
+            raw: &self.params[start..end],
+        })
+    }
+}
+
+/// One parameter of an SGR sequence, such as `1`, `4:3`, `38;5;208` or
+/// `38:2::255:135:0`, with the values of an extended color kept together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct SgrParam<'a> {
+    /// The parameter as written.
+    pub(crate) raw: &'a str,
+}
+
+impl SgrParam<'_> {
+    /// Returns the parameter number, reading an empty parameter as `0`, or
+    /// `None` if it is not a number.
+    pub(crate) fn code(&self) -> Option<u8> {
+        match self.raw.split([';', ':']).next().unwrap_or_default() {
+            "" => Some(0),
+            head => head.parse().ok(),
+        }
+    }
+
+    /// Returns the first colon sub-parameter, such as `3` in `4:3`.
+    pub(crate) fn sub(&self) -> Option<&str> {
+        self.raw.split(':').nth(1)
+    }
+
+    /// Returns the color of an extended color parameter (`38`, `48` or `58`).
+    ///
+    /// The colon form of truecolor may carry a color space id before the
+    /// channels, so the last three values are used.
+    pub(crate) fn color(&self) -> Option<Color> {
+        let values: Vec<&str> = self
+            .raw
+            .split([';', ':'])
+            .skip(1)
+            .filter(|value| !value.is_empty())
+            .collect();
+        let number = |i: usize| values.get(i).and_then(|value| value.parse::<u8>().ok());
+        match values.first() {
+            Some(&"5") => number(1).map(Color::Ansi256),
+            Some(&"2") if values.len() >= 4 => {
+                let n = values.len();
+                match (number(n - 3), number(n - 2), number(n - 1)) {
+                    (Some(r), Some(g), Some(b)) => Some(Color::Rgb(r, g, b)),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Groups attribute parameters that replace one another, such as the
+/// underline variants `4`, `4:3` and `21`.
+fn attribute_slot(param: &str) -> u8 {
+    let head = param.split(':').next().unwrap_or_default();
+    match head.parse::<u8>() {
+        Ok(21) => 4,
+        Ok(6) => 5,
+        Ok(n) => n,
+        Err(_) => 0,
+    }
+}
+
+/// Returns the attribute slots turned off by SGR parameter `n`.
+fn cleared_slots(n: u8) -> &'static [u8] {
+    match n {
+        22 => &[1, 2],
+        23 => &[3],
+        24 => &[4],
+        25 => &[5],
+        27 => &[7],
+        28 => &[8],
+        29 => &[9],
+        55 => &[53],
+        _ => &[],
+    }
+}
@@ -0,0 +1,418 @@
+//! A runtime-configurable combination of colors and text attributes.
+
+use std::fmt;
+
+use crate::quantize::downgrade;
+use crate::sgr::sgr_tokens;
+use crate::{codes, color_support, Color, ColorSupport, Colours};
+
+/// A text attribute that can be toggled on a [`Style`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Attribute {
+    Bold,
+    Dim,
+    Italic,
+    Underline,
+    Blink,
+    Reverse,
+    Conceal,
+    Strikethrough,
+    DoubleUnderline,
+    Overline,
+}
+
+impl Attribute {
+    /// All attributes, in the order their SGR parameters are emitted.
+    pub const ALL: [Attribute; 10] = [
+        Attribute::Bold,
+        Attribute::Dim,
+        Attribute::Italic,
+        Attribute::Underline,
+        Attribute::Blink,
+        Attribute::Reverse,
+        Attribute::Conceal,
+        Attribute::Strikethrough,
+        Attribute::DoubleUnderline,
+        Attribute::Overline,
+    ];
+
+    /// Returns the attribute with the given lowercase name, such as `"bold"`
+    /// or `"double_underline"`.
+    fn from_name(name: &str) -> Option<Attribute> {
+        match name {
+            "bold" => Some(Attribute::Bold),
+            "dim" => Some(Attribute::Dim),
+            "italic" => Some(Attribute::Italic),
+            "underline" => Some(Attribute::Underline),
+            "blink" => Some(Attribute::Blink),
+            "reverse" => Some(Attribute::Reverse),
+            "conceal" | "hidden" => Some(Attribute::Conceal),
+            "strikethrough" => Some(Attribute::Strikethrough),
+            "double_underline" => Some(Attribute::DoubleUnderline),
+            "overline" => Some(Attribute::Overline),
+            _ => None,
+        }
+    }
+
+    /// Returns the SGR parameter that enables this attribute.
+    pub fn sgr(self) -> u8 {
+        match self {
+            Attribute::Bold => 1,
+            Attribute::Dim => 2,
+            Attribute::Italic => 3,
+            Attribute::Underline => 4,
+            Attribute::Blink => 5,
+            Attribute::Reverse => 7,
+            Attribute::Conceal => 8,
+            Attribute::Strikethrough => 9,
+            Attribute::DoubleUnderline => 21,
+            Attribute::Overline => 53,
+        }
+    }
+
+    fn bit(self) -> u16 {
+        1 << self as u16
+    }
+}
+
+/// A foreground color, background color and set of attributes, built at runtime.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// let style = Style::new().fg(Colours::Red).bg(Color::Ansi256(236)).bold();
+/// println!("{}", style.apply("Error: something went wrong"));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Style {
+    fg: Option<Color>,
+    bg: Option<Color>,
+    attributes: u16,
+}
+
+impl Style {
+    /// Creates an empty style that leaves text unchanged.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses a style spec such as `"bold red on #202020"`.
+    ///
+    /// The spec is a list of words separated by spaces or commas. Attribute
+    /// names (`bold`, `italic`, `underline`, ...) enable that attribute, a
+    /// color (anything [`Color::parse`] accepts) sets the foreground, and a
+    /// color after `on` sets the background. Returns `None` if any word is
+    /// not understood; an empty spec gives a plain style.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use term_ansi::{Color, Colours, Style};
+    ///
+    /// assert_eq!(
+    ///     Style::parse("bold red on #202020"),
+    ///     Some(Style::new().bold().fg(Colours::Red).bg(Color::Rgb(32, 32, 32)))
+    /// );
+    /// assert_eq!(Style::parse("bold sparkly"), None);
+    /// ```
+    pub fn parse(spec: &str) -> Option<Style> {
+        let mut style = Style::new();
+        let mut words = spec
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|word| !word.is_empty());
+        while let Some(word) = words.next() {
+            let word = word.to_ascii_lowercase();
+            if word == "on" {
+                style = style.bg(Color::parse(words.next()?)?);
+            } else if let Some(attribute) = Attribute::from_name(&word) {
+                style = style.attr(attribute);
+            } else {
+                style = style.fg(Color::parse(&word)?);
+            }
+        }
+        Some(style)
+    }
+
+    /// Sets the foreground color.
+    pub fn fg(mut self, color: impl Into<Color>) -> Self {
+        self.fg = Some(color.into());
+        self
+    }
+
+    /// Sets the background color.
+    pub fn bg(mut self, color: impl Into<Color>) -> Self {
+        self.bg = Some(color.into());
+        self
+    }
+
+    /// Enables the given attribute.
+    pub fn attr(mut self, attribute: Attribute) -> Self {
+        self.attributes |= attribute.bit();
+        self
+    }
+
+    /// Enables bold.
+    pub fn bold(self) -> Self {
+        self.attr(Attribute::Bold)
+    }
+
+    /// Enables dim (faint) text.
+    pub fn dim(self) -> Self {
+        self.attr(Attribute::Dim)
+    }
+
+    /// Enables italic.
+    pub fn italic(self) -> Self {
+        self.attr(Attribute::Italic)
+    }
+
+    /// Enables underline.
+    pub fn underline(self) -> Self {
+        self.attr(Attribute::Underline)
+    }
+
+    /// Enables blinking.
+    pub fn blink(self) -> Self {
+        self.attr(Attribute::Blink)
+    }
+
+    /// Enables reverse video.
+    pub fn reverse(self) -> Self {
+        self.attr(Attribute::Reverse)
+    }
+
+    /// Enables concealed text.
+    pub fn conceal(self) -> Self {
+        self.attr(Attribute::Conceal)
+    }
+
+    /// Enables strikethrough.
+    pub fn strikethrough(self) -> Self {
+        self.attr(Attribute::Strikethrough)
+    }
+
+    /// Enables double underline.
+    pub fn double_underline(self) -> Self {
+        self.attr(Attribute::DoubleUnderline)
+    }
+
+    /// Enables overline.
+    pub fn overline(self) -> Self {
+        self.attr(Attribute::Overline)
+    }
+
+    /// Returns the foreground color, if set.
+    pub fn foreground(&self) -> Option<Color> {
+        self.fg
+    }
+
+    /// Returns the background color, if set.
+    pub fn background(&self) -> Option<Color> {
+        self.bg
+    }
+
+    /// Returns whether the given attribute is enabled.
+    pub fn has(&self, attribute: Attribute) -> bool {
+        self.attributes & attribute.bit() != 0
+    }
+
+    /// Returns whether the style has no colors and no attributes.
+    pub fn is_plain(&self) -> bool {
+        self.fg.is_none() && self.bg.is_none() && self.attributes == 0
+    }
+
+    /// Updates the style with the parameters of one SGR sequence, as a terminal would.
+    ///
+    /// Parameters without a counterpart in [`Style`], such as underline colors,
+    /// are skipped; extended underline styles count as [`Attribute::Underline`].
+    pub(crate) fn apply_sgr(&mut self, params: &str) {
+        for param in sgr_tokens(params) {
+            let Some(code) = param.code() else {
+                continue;
+            };
+            match code {
+                0 => *self = Style::default(),
+                4 => match param.sub() {
+                    Some("0") => self.clear(&[Attribute::Underline, Attribute::DoubleUnderline]),
+                    Some("2") => self.set(Attribute::DoubleUnderline),
+                    _ => self.set(Attribute::Underline),
+                },
+                6 => self.set(Attribute::Blink),
+                22 => self.clear(&[Attribute::Bold, Attribute::Dim]),
+                23 => self.clear(&[Attribute::Italic]),
+                24 => self.clear(&[Attribute::Underline, Attribute::DoubleUnderline]),
+                25 => self.clear(&[Attribute::Blink]),
+                27 => self.clear(&[Attribute::Reverse]),
+                28 => self.clear(&[Attribute::Conceal]),
+                29 => self.clear(&[Attribute::Strikethrough]),
+                55 => self.clear(&[Attribute::Overline]),
+                30..=37 => self.fg = Some(Color::Basic(Colours::ALL[usize::from(code - 30)])),
+                90..=97 => self.fg = Some(Color::BrightBasic(Colours::ALL[usize::from(code - 90)])),
+                40..=47 => self.bg = Some(Color::Basic(Colours::ALL[usize::from(code - 40)])),
+                100..=107 => {
+                    self.bg = Some(Color::BrightBasic(Colours::ALL[usize::from(code - 100)]))
+                }
+                39 => self.fg = None,
+                49 => self.bg = None,
+                38 => self.fg = param.color().or(self.fg),
+                48 => self.bg = param.color().or(self.bg),
+                _ => {
+                    if let Some(attribute) = Attribute::ALL.into_iter().find(|a| a.sgr() == code) {
+                        self.set(attribute);
+                    }
+                }
+            }
+        }
+    }
+
+    fn set(&mut self, attribute: Attribute) {
+        self.attributes |= attribute.bit();
+    }
+
+    fn clear(&mut self, attributes: &[Attribute]) {
+        for attribute in attributes {
+            self.attributes &= !attribute.bit();
+        }
+    }
+
+    /// Writes the combined SGR parameters for this style without allocating,
+    /// with colors quantized to `support`.
+    fn write_params(&self, f: &mut dyn fmt::Write, support: ColorSupport) -> fmt::Result {
+        let mut first: bool = true;
+        let mut separator = |f: &mut dyn fmt::Write| {
+            if first {
+                first = false;
+                Ok(())
+            } else {
+                f.write_char(';')
+            }
+        };
+
+        for attribute in Attribute::ALL {
+            if self.has(attribute) {
+                separator(f)?;
+                write!(f, "{}", attribute.sgr())?;
+            }
+        }
+        if let Some(fg) = self.fg {
+            separator(f)?;
+            downgrade(fg, support).write_params(f, false)?;
+        }
+        if let Some(bg) = self.bg {
+            separator(f)?;
+            downgrade(bg, support).write_params(f, true)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the combined SGR parameters for this style, e.g. `"1;4;31"`.
+    ///
+    /// RGB and 256-color values are quantized to the [`color_support`] level.
+    pub fn sgr_params(&self) -> String {
+        let mut params: String = String::new();
+        let _ = self.write_params(&mut params, color_support());
+        params
+    }
+
+    /// Writes the escape sequence that enables this style, with colors
+    /// quantized to `support`, or nothing for a plain style.
+    pub(crate) fn write_code(&self, f: &mut dyn fmt::Write, support: ColorSupport) -> fmt::Result {
+        if self.is_plain() {
+            return Ok(());
+        }
+        f.write_str("\x1b[")?;
+        self.write_params(f, support)?;
+        f.write_char('m')
+    }
+
+    /// Returns the single escape sequence that enables this style, or an empty string.
+    pub fn prefix(&self) -> String {
+        self.to_string()
+    }
+
+    /// Applies the style to `text`, participating in the color context like the macros do.
+    pub fn apply(&self, text: &str) -> String {
+        if self.is_plain() {
+            return text.to_string();
+        }
+        crate::apply_color!(self.prefix(), "{}", text)
+    }
+}
+
+/// Writes the escape sequence that enables the style, with colors quantized
+/// to the [`color_support`] level.
+impl fmt::Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_code(f, color_support())
+    }
+}
+
+impl From<Color> for Style {
+    fn from(color: Color) -> Self {
+        Style::new().fg(color)
+    }
+}
+
+impl From<Colours> for Style {
+    fn from(colour: Colours) -> Self {
+        Style::new().fg(colour)
+    }
+}
+
+/// A value paired with a [`Style`], rendered lazily by its `Display` impl.
+///
+/// The escape sequence, the value and the reset are written straight into the
+/// formatter, so no intermediate `String` is built. Unlike the macros, a `Styled`
+/// value does not consult the color context and always ends with a plain reset.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::*;
+///
+/// let count = 3;
+/// println!("{} files changed", styled(Colours::Green, count));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Styled<T> {
+    style: Style,
+    value: T,
+}
+
+impl<T> Styled<T> {
+    /// Returns the style that will be applied.
+    pub fn style(&self) -> Style {
+        self.style
+    }
+
+    /// Returns the wrapped value.
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// Unwraps the value, discarding the style.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Styled<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.style.is_plain() || !crate::colors_enabled() {
+            return self.value.fmt(f);
+        }
+        write!(f, "{}", self.style)?;
+        self.value.fmt(f)?;
+        f.write_str(codes::RESET)
+    }
+}
+
+/// Wraps `value` so that it is rendered with `style` when displayed.
+pub fn styled<T: fmt::Display>(style: impl Into<Style>, value: T) -> Styled<T> {
+    Styled {
+        style: style.into(),
+        value,
+    }
+}
@@ -0,0 +1,292 @@
+//! Decisions about whether, and how much, color should be emitted.
+
+use std::ffi::OsString;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+const COLORS_AUTO: u8 = 0;
+const COLORS_ON: u8 = 1;
+const COLORS_OFF: u8 = 2;
+
+static COLORS_ENABLED: AtomicU8 = AtomicU8::new(COLORS_AUTO);
+
+const SUPPORT_AUTO: u8 = u8::MAX;
+
+static COLOR_SUPPORT: AtomicU8 = AtomicU8::new(SUPPORT_AUTO);
+
+/// The resolved decision on whether to emit color, as reported by [`color_choice`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorChoice {
+    /// Emit color even when the output is not a terminal.
+    Always,
+    /// Emit color only when the output is a terminal.
+    Auto,
+    /// Never emit color.
+    Never,
+}
+
+/// How many colors the terminal can display, as reported by [`detect_color_support`].
+///
+/// Levels are ordered, so `support >= ColorSupport::Ansi256` checks for at
+/// least the 256-color palette.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ColorSupport {
+    /// No color at all.
+    None,
+    /// The 16 basic and bright colors.
+    Basic16,
+    /// The 256-color palette (`38;5;n`).
+    Ansi256,
+    /// 24-bit RGB (`38;2;r;g;b`).
+    TrueColor,
+}
+
+impl ColorSupport {
+    const ALL: [ColorSupport; 4] = [
+        ColorSupport::None,
+        ColorSupport::Basic16,
+        ColorSupport::Ansi256,
+        ColorSupport::TrueColor,
+    ];
+}
+
+/// A standard output stream, for per-stream color decisions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+impl Stream {
+    /// Returns whether the stream is attached to a terminal.
+    ///
+    /// Always `true` without the `tty` feature, since the stream cannot be checked.
+    pub fn is_terminal(self) -> bool {
+        #[cfg(feature = "tty")]
+        {
+            use std::io::IsTerminal;
+
+            match self {
+                Stream::Stdout => std::io::stdout().is_terminal(),
+                Stream::Stderr => std::io::stderr().is_terminal(),
+            }
+        }
+        #[cfg(not(feature = "tty"))]
+        {
+            true
+        }
+    }
+}
+
+/// Turns escape code output on or off for the whole process.
+///
+/// While colors are disabled every macro returns just its formatted text, and
+/// [`Styled`](crate::Styled) values display without escape codes. Calling this
+/// overrides the `NO_COLOR`, `CLICOLOR`, `CLICOLOR_FORCE` and `FORCE_COLOR`
+/// environment variables.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{bold, red, set_colors_enabled};
+///
+/// set_colors_enabled(false);
+/// assert_eq!(red!("Error: {}", bold!("disk full")), "Error: disk full");
+/// set_colors_enabled(true);
+/// assert_eq!(red!("Error"), "\x1b[31mError\x1b[0m\x1b[37m");
+/// ```
+pub fn set_colors_enabled(enabled: bool) {
+    let value = if enabled { COLORS_ON } else { COLORS_OFF };
+    COLORS_ENABLED.store(value, Ordering::Relaxed);
+}
+
+/// Returns whether escape codes are currently emitted.
+///
+/// This is `false` only when [`color_choice`] resolves to [`ColorChoice::Never`].
+/// The macros cannot see where their output goes, so they treat
+/// [`ColorChoice::Auto`] as enabled.
+pub fn colors_enabled() -> bool {
+    color_choice() != ColorChoice::Never
+}
+
+/// Returns the color decision resolved from [`set_colors_enabled`] and the environment.
+///
+/// An explicit [`set_colors_enabled`] call wins. Otherwise the environment is
+/// consulted in this order:
+///
+/// 1. `NO_COLOR` set to a non-empty value: [`ColorChoice::Never`].
+/// 2. `CLICOLOR_FORCE` set to anything but `0`, or `FORCE_COLOR` set to anything
+///    but `0` or `false`: [`ColorChoice::Always`].
+/// 3. `CLICOLOR=0`, `FORCE_COLOR=0` or `FORCE_COLOR=false`: [`ColorChoice::Never`].
+/// 4. Otherwise: [`ColorChoice::Auto`].
+///
+/// The environment is read on the first call and cached.
+pub fn color_choice() -> ColorChoice {
+    match COLORS_ENABLED.load(Ordering::Relaxed) {
+        COLORS_ON => ColorChoice::Always,
+        COLORS_OFF => ColorChoice::Never,
+        _ => {
+            static DETECTED: OnceLock<ColorChoice> = OnceLock::new();
+            *DETECTED.get_or_init(|| env_color_choice(|name| std::env::var_os(name)))
+        }
+    }
+}
+
+/// Returns whether output written to `stream` should be colored.
+///
+/// [`ColorChoice::Auto`] colors only streams attached to a terminal, so
+/// `myapp | grep` receives plain text on stdout while stderr stays colored.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{red, should_colorize, Stream};
+///
+/// if should_colorize(Stream::Stderr) {
+///     eprintln!("{}", red!("error: file not found"));
+/// } else {
+///     eprintln!("error: file not found");
+/// }
+/// ```
+pub fn should_colorize(stream: Stream) -> bool {
+    choice_allows(color_choice(), || stream.is_terminal())
+}
+
+/// Applies `choice`, checking for a terminal only when it is [`ColorChoice::Auto`].
+pub(crate) fn choice_allows(choice: ColorChoice, is_terminal: impl FnOnce() -> bool) -> bool {
+    match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => is_terminal(),
+    }
+}
+
+/// Resolves a [`ColorChoice`] from environment variables looked up with `var`.
+///
+/// Empty values count as unset; see <https://no-color.org> and
+/// <https://bixense.com/clicolors/>.
+pub(crate) fn env_color_choice(var: impl Fn(&str) -> Option<OsString>) -> ColorChoice {
+    let set = |name: &str| var(name).filter(|value| !value.is_empty());
+    let force_color = set("FORCE_COLOR");
+    let force_color_off = force_color
+        .as_ref()
+        .is_some_and(|value| value == "0" || value == "false");
+
+    if set("NO_COLOR").is_some() {
+        ColorChoice::Never
+    } else if set("CLICOLOR_FORCE").is_some_and(|value| value != "0")
+        || (force_color.is_some() && !force_color_off)
+    {
+        ColorChoice::Always
+    } else if set("CLICOLOR").is_some_and(|value| value == "0") || force_color_off {
+        ColorChoice::Never
+    } else {
+        ColorChoice::Auto
+    }
+}
+
+/// Detects the color depth of the terminal from the environment.
+///
+/// Returns [`ColorSupport::None`] when colors are disabled (see [`color_choice`]).
+/// Otherwise `FORCE_COLOR` levels `1` to `3` are honored, then `COLORTERM`,
+/// `TERM`, `TERM_PROGRAM` and Windows Terminal / ConEmu hints are consulted.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{detect_color_support, red, rgb, ColorSupport};
+///
+/// let warning = if detect_color_support() == ColorSupport::TrueColor {
+///     rgb!(255, 165, 0, "warning")
+/// } else {
+///     red!("warning")
+/// };
+/// println!("{}", warning);
+/// ```
+pub fn detect_color_support() -> ColorSupport {
+    if color_choice() == ColorChoice::Never {
+        return ColorSupport::None;
+    }
+    env_color_support(|name| std::env::var_os(name))
+}
+
+/// Resolves a [`ColorSupport`] level from environment variables looked up with `var`.
+pub(crate) fn env_color_support(var: impl Fn(&str) -> Option<OsString>) -> ColorSupport {
+    let get = |name: &str| {
+        var(name)
+            .map(|value| value.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    };
+
+    match get("FORCE_COLOR").as_str() {
+        "1" | "true" => return ColorSupport::Basic16,
+        "2" => return ColorSupport::Ansi256,
+        "3" => return ColorSupport::TrueColor,
+        _ => {}
+    }
+
+    let colorterm = get("COLORTERM");
+    if colorterm == "truecolor" || colorterm == "24bit" {
+        return ColorSupport::TrueColor;
+    }
+
+    let term = get("TERM");
+    let term_program = get("TERM_PROGRAM");
+    if term == "dumb" {
+        ColorSupport::None
+    } else if term.ends_with("-direct")
+        || term.contains("truecolor")
+        || matches!(
+            term_program.as_str(),
+            "iTerm.app" | "WezTerm" | "vscode" | "ghostty"
+        )
+        || var("WT_SESSION").is_some()
+    {
+        ColorSupport::TrueColor
+    } else if term.contains("256") || term_program == "Apple_Terminal" {
+        ColorSupport::Ansi256
+    } else if !term.is_empty() || !colorterm.is_empty() || get("ConEmuANSI") == "ON" {
+        ColorSupport::Basic16
+    } else {
+        ColorSupport::None
+    }
+}
+
+/// Overrides the color depth that RGB output is quantized to.
+///
+/// `None` restores detection with [`detect_color_support`].
+pub fn set_color_support(support: Option<ColorSupport>) {
+    let value = support.map_or(SUPPORT_AUTO, |support| support as u8);
+    COLOR_SUPPORT.store(value, Ordering::Relaxed);
+}
+
+/// Returns the color depth used for RGB output.
+///
+/// This is the value set with [`set_color_support`], or else the result of
+/// [`detect_color_support`]. The environment is read once and cached, but
+/// whether colors are disabled is checked on every call.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{color_support, set_colors_enabled, ColorSupport};
+///
+/// # std::env::set_var("FORCE_COLOR", "2");
+/// set_colors_enabled(false);
+/// assert_eq!(color_support(), ColorSupport::None);
+/// set_colors_enabled(true);
+/// assert_eq!(color_support(), ColorSupport::Ansi256);
+/// ```
+pub fn color_support() -> ColorSupport {
+    match ColorSupport::ALL
+        .into_iter()
+        .find(|&support| support as u8 == COLOR_SUPPORT.load(Ordering::Relaxed))
+    {
+        Some(support) => support,
+        None if color_choice() == ColorChoice::Never => ColorSupport::None,
+        None => {
+            static DETECTED: OnceLock<ColorSupport> = OnceLock::new();
+            *DETECTED.get_or_init(|| env_color_support(|name| std::env::var_os(name)))
+        }
+    }
+}
@@ -0,0 +1,322 @@
+//! Tables and boxes of styled text, aligned by visible width.
+
+use std::fmt;
+
+use crate::{codes, linewise, optimize_ansi, styled, visible_width, Style};
+
+/// The horizontal alignment of text within a column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Align {
+    #[default]
+    Left,
+    Right,
+    Center,
+}
+
+/// The line drawing characters of a [`Table`] or a [`boxed!`](crate::boxed) frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Border {
+    /// Thin lines: `┌─┐`.
+    #[default]
+    Single,
+    /// Thin lines with rounded corners: `╭─╮`.
+    Rounded,
+    /// Double lines: `╔═╗`.
+    Double,
+    /// Thick lines: `┏━┓`.
+    Heavy,
+    /// Plain ASCII: `+-+`, for terminals and logs without box drawing characters.
+    Ascii,
+    /// No lines; columns are separated by spaces only.
+    None,
+}
+
+/// The characters of one [`Border`] kind.
+pub(crate) struct Glyphs {
+    pub(crate) horizontal: &'static str,
+    pub(crate) vertical: &'static str,
+    /// The left, middle and right characters of the top line.
+    pub(crate) top: [&'static str; 3],
+    /// The left, middle and right characters of a separator line.
+    pub(crate) middle: [&'static str; 3],
+    /// The left, middle and right characters of the bottom line.
+    pub(crate) bottom: [&'static str; 3],
+}
+
+impl Border {
+    /// Returns the characters of this border, or `None` for [`Border::None`].
+    pub(crate) fn glyphs(self) -> Option<Glyphs> {
+        let (horizontal, vertical, top, middle, bottom) = match self {
+            Border::Single => ("─", "│", ["┌", "┬", "┐"], ["├", "┼", "┤"], ["└", "┴", "┘"]),
+            Border::Rounded => ("─", "│", ["╭", "┬", "╮"], ["├", "┼", "┤"], ["╰", "┴", "╯"]),
+            Border::Double => ("═", "║", ["╔", "╦", "╗"], ["╠", "╬", "╣"], ["╚", "╩", "╝"]),
+            Border::Heavy => ("━", "┃", ["┏", "┳", "┓"], ["┣", "╋", "┫"], ["┗", "┻", "┛"]),
+            Border::Ascii => ("-", "|", ["+", "+", "+"], ["+", "+", "+"], ["+", "+", "+"]),
+            Border::None => return None,
+        };
+        Some(Glyphs {
+            horizontal,
+            vertical,
+            top,
+            middle,
+            bottom,
+        })
+    }
+}
+
+/// Pads `text` with spaces to `width` visible columns, placed as `align` says.
+///
+/// Text at least `width` columns wide is returned unchanged.
+pub(crate) fn pad(text: &str, width: usize, align: Align) -> String {
+    let missing = width.saturating_sub(visible_width(text));
+    let (left, right) = match align {
+        Align::Left => (0, missing),
+        Align::Right => (missing, 0),
+        Align::Center => (missing / 2, missing - missing / 2),
+    };
+    format!("{}{}{}", " ".repeat(left), text, " ".repeat(right))
+}
+
+/// A table builder for styled text.
+///
+/// Column widths come from [`visible_width`], so cells produced by the color
+/// macros line up with plain ones, and with wide characters when the
+/// `unicode-width` feature is on. Cells are single lines; rows with fewer
+/// cells than the widest row are filled with empty ones. The border can be
+/// drawn in a style of its own, and the header row in another.
+///
+/// The table is rendered by its `Display` impl, one line per row, each ending
+/// with a newline.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{green, red, strip_ansi, Align, Color, Colours, Table};
+///
+/// let table = Table::new()
+///     .header(["test", "time"])
+///     .row([green!("ok"), String::from("12ms")])
+///     .row([red!("FAILED"), String::from("3ms")])
+///     .align(1, Align::Right)
+///     .border_style(Color::BrightBasic(Colours::Black));
+/// println!("{}", table);
+/// assert_eq!(
+///     strip_ansi(&table.to_string()),
+///     "┌────────┬──────┐\n\
+///      │ test   │ time │\n\
+///      ├────────┼──────┤\n\
+///      │ ok     │ 12ms │\n\
+///      │ FAILED │  3ms │\n\
+///      └────────┴──────┘\n"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Table {
+    header: Option<Vec<String>>,
+    rows: Vec<Vec<String>>,
+    aligns: Vec<Align>,
+    border: Border,
+    border_style: Style,
+    header_style: Style,
+}
+
+impl Table {
+    /// Creates an empty table with a [`Border::Single`] border.
+    pub fn new() -> Self {
+        Table::default()
+    }
+
+    /// Sets the header row, separated from the other rows by a line.
+    pub fn header<T: fmt::Display>(self, cells: impl IntoIterator<Item = T>) -> Self {
+        Table {
+            header: Some(cells.into_iter().map(|cell| cell.to_string()).collect()),
+            ..self
+        }
+    }
+
+    /// Appends a row.
+    pub fn row<T: fmt::Display>(mut self, cells: impl IntoIterator<Item = T>) -> Self {
+        self.rows
+            .push(cells.into_iter().map(|cell| cell.to_string()).collect());
+        self
+    }
+
+    /// Sets the alignment of the column at `column`, counting from 0.
+    /// Columns are left-aligned by default.
+    pub fn align(mut self, column: usize, align: Align) -> Self {
+        if self.aligns.len() <= column {
+            self.aligns.resize(column + 1, Align::Left);
+        }
+        self.aligns[column] = align;
+        self
+    }
+
+    /// Sets the line drawing characters.
+    pub fn border(self, border: Border) -> Self {
+        Table { border, ..self }
+    }
+
+    /// Sets the style the border is drawn in.
+    pub fn border_style(self, style: impl Into<Style>) -> Self {
+        Table {
+            border_style: style.into(),
+            ..self
+        }
+    }
+
+    /// Sets the style applied to every header cell.
+    pub fn header_style(self, style: impl Into<Style>) -> Self {
+        Table {
+            header_style: style.into(),
+            ..self
+        }
+    }
+
+    /// Writes one row of cells in `style`, padded to `widths`.
+    fn write_row(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        cells: &[String],
+        widths: &[usize],
+        style: Style,
+    ) -> fmt::Result {
+        let vertical = self.border.glyphs().map(|glyphs| glyphs.vertical);
+        for (i, &width) in widths.iter().enumerate() {
+            let cell = cells.get(i).map_or("", String::as_str);
+            let align = self.aligns.get(i).copied().unwrap_or_default();
+            let padded = pad(&styled(style, cell).to_string(), width, align);
+            match vertical {
+                Some(vertical) => write!(f, "{} {} ", styled(self.border_style, vertical), padded)?,
+                None if i == 0 => f.write_str(&padded)?,
+                None => write!(f, "  {}", padded)?,
+            }
+        }
+        match vertical {
+            Some(vertical) => writeln!(f, "{}", styled(self.border_style, vertical)),
+            None => writeln!(f),
+        }
+    }
+
+    /// Writes a horizontal line with the given left, junction and right characters.
+    fn write_line(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        widths: &[usize],
+        horizontal: &str,
+        [left, join, right]: [&str; 3],
+    ) -> fmt::Result {
+        let segments: Vec<String> = widths
+            .iter()
+            .map(|width| horizontal.repeat(width + 2))
+            .collect();
+        let line = format!("{}{}{}", left, segments.join(join), right);
+        writeln!(f, "{}", styled(self.border_style, line))
+    }
+}
+
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let columns = self
+            .header
+            .iter()
+            .chain(&self.rows)
+            .map(Vec::len)
+            .max()
+            .unwrap_or(0);
+        if columns == 0 {
+            return Ok(());
+        }
+        let mut widths = vec![0; columns];
+        for row in self.header.iter().chain(&self.rows) {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(visible_width(cell));
+            }
+        }
+
+        let glyphs = self.border.glyphs();
+        if let Some(glyphs) = &glyphs {
+            self.write_line(f, &widths, glyphs.horizontal, glyphs.top)?;
+        }
+        if let Some(header) = &self.header {
+            self.write_row(f, header, &widths, self.header_style)?;
+            if let Some(glyphs) = &glyphs {
+                self.write_line(f, &widths, glyphs.horizontal, glyphs.middle)?;
+            }
+        }
+        for row in &self.rows {
+            self.write_row(f, row, &widths, Style::new())?;
+        }
+        if let Some(glyphs) = &glyphs {
+            self.write_line(f, &widths, glyphs.horizontal, glyphs.bottom)?;
+        }
+        Ok(())
+    }
+}
+
+/// Draws a `border` in `style` around `text`, with one space of padding.
+///
+/// `text` may span several lines and contain escape codes: the frame is
+/// sized by [`visible_width`], and styles are closed at the end of each line
+/// and re-opened on the next, as [`linewise`] does, so they never color the
+/// frame. [`Border::None`] gives just the padding. The result has no
+/// trailing newline. See [`boxed!`](crate::boxed!) for the macro form.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{boxed, Border, Style};
+///
+/// assert_eq!(
+///     boxed("Saved\n3 files", Border::Rounded, Style::new()),
+///     "╭─────────╮\n│ Saved   │\n│ 3 files │\n╰─────────╯"
+/// );
+/// ```
+pub fn boxed(text: &str, border: Border, style: impl Into<Style>) -> String {
+    let style = style.into();
+    let text = linewise(text);
+    let mut lines: Vec<String> = text
+        .lines()
+        .map(|line| {
+            if line.contains('\x1b') {
+                optimize_ansi(&format!("{}{}", line, codes::RESET))
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    if lines.is_empty() {
+        lines.push(String::new());
+    }
+    let width = lines
+        .iter()
+        .map(|line| visible_width(line))
+        .max()
+        .unwrap_or(0);
+
+    let Some(glyphs) = border.glyphs() else {
+        let lines: Vec<String> = lines
+            .iter()
+            .map(|line| format!(" {} ", pad(line, width, Align::Left)))
+            .collect();
+        return lines.join("\n");
+    };
+    let edge = |[left, _, right]: [&str; 3]| {
+        styled(
+            style,
+            format!("{}{}{}", left, glyphs.horizontal.repeat(width + 2), right),
+        )
+        .to_string()
+    };
+    let vertical = styled(style, glyphs.vertical);
+    let mut framed = vec![edge(glyphs.top)];
+    framed.extend(lines.iter().map(|line| {
+        format!(
+            "{} {} {}",
+            vertical,
+            pad(line, width, Align::Left),
+            vertical
+        )
+    }));
+    framed.push(edge(glyphs.bottom));
+    framed.join("\n")
+}
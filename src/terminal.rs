@@ -0,0 +1,243 @@
+//! Talking to the terminal itself: querying its background and palette, and
+//! redefining the palette.
+
+use std::sync::OnceLock;
+
+use crate::{colors_enabled, readable_fg};
+
+/// How long to wait for the terminal to answer a query, in milliseconds.
+#[cfg(all(unix, feature = "tty"))]
+const TIMEOUT_MS: u64 = 200;
+
+/// Sends `request` to the controlling terminal and returns everything it
+/// writes back, or `None` without a terminal or an answer.
+///
+/// A device attributes request (`ESC [ c`) follows `request`. Virtually every
+/// terminal answers it, so reading stops as soon as that answer arrives rather
+/// than waiting out the timeout when `request` itself is not supported.
+#[cfg(all(unix, feature = "tty"))]
+pub(crate) fn query(request: &str) -> Option<String> {
+    use std::fs::{File, OpenOptions};
+    use std::io::{Read, Write};
+    use std::process::{Command, Stdio};
+    use std::time::{Duration, Instant};
+
+    let stty = |args: &[&str]| -> Option<String> {
+        let output = Command::new("stty")
+            .args(args)
+            .stdin(File::open("/dev/tty").ok()?)
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+        let settings = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        output.status.success().then_some(settings)
+    };
+
+    let mut tty = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+    let saved = stty(&["-g"])?;
+    // Reads return after at most a tenth of a second, even with no input.
+    stty(&["raw", "-echo", "min", "0", "time", "1"])?;
+
+    let mut reply = Vec::new();
+    let sent = tty
+        .write_all(format!("{}\x1b[c", request).as_bytes())
+        .and_then(|_| tty.flush());
+    if sent.is_ok() {
+        let deadline = Instant::now() + Duration::from_millis(TIMEOUT_MS);
+        let mut buffer = [0; 256];
+        while Instant::now() < deadline && !has_device_attributes(&reply) {
+            match tty.read(&mut buffer) {
+                Ok(n) => reply.extend_from_slice(&buffer[..n]),
+                Err(_) => break,
+            }
+        }
+    }
+    stty(&[&saved]);
+
+    let reply = String::from_utf8_lossy(&reply).into_owned();
+    (!reply.is_empty()).then_some(reply)
+}
+
+#[cfg(not(all(unix, feature = "tty")))]
+pub(crate) fn query(_request: &str) -> Option<String> {
+    None
+}
+
+/// Returns whether `reply` ends with the answer to a device attributes request.
+#[cfg(all(unix, feature = "tty"))]
+fn has_device_attributes(reply: &[u8]) -> bool {
+    reply.ends_with(b"c") && reply.windows(3).any(|window| window == b"\x1b[?")
+}
+
+/// Extracts the color from an OSC color reply that starts with `prefix`,
+/// such as `ESC ] 11 ; rgb:1e1e/1e1e/2e2e BEL`.
+///
+/// Channels have one to four hex digits each and are scaled to 0-255.
+pub(crate) fn parse_color_reply(reply: &str, prefix: &str) -> Option<(u8, u8, u8)> {
+    let start = reply.find(prefix)? + prefix.len();
+    let rest = &reply[start..];
+    let end = rest.find(['\x07', '\x1b']).unwrap_or(rest.len());
+    let spec = &rest[..end];
+    let channels = spec
+        .strip_prefix("rgb:")
+        .or_else(|| spec.strip_prefix("rgba:"))?;
+
+    let mut channels = channels.split('/').map(|digits| {
+        if digits.is_empty() || digits.len() > 4 {
+            return None;
+        }
+        let value = u32::from_str_radix(digits, 16).ok()?;
+        let max = (1 << (4 * digits.len())) - 1;
+        Some(((value * 255 + max / 2) / max) as u8)
+    });
+    Some((channels.next()??, channels.next()??, channels.next()??))
+}
+
+/// Returns whether the `COLORFGBG` value (`"15;0"`, set by rxvt, Konsole and
+/// others) names a dark background, or `None` if it can't be read.
+pub(crate) fn colorfgbg_is_dark(value: &str) -> Option<bool> {
+    let background: u8 = value.rsplit(';').next()?.trim().parse().ok()?;
+    Some(matches!(background, 0..=6 | 8))
+}
+
+/// Returns the terminal's background color, asked for with an OSC 11 query.
+///
+/// Returns `None` when there is no controlling terminal, the terminal does not
+/// answer within a fraction of a second, or the `tty` feature is disabled. The
+/// query needs to put the terminal in raw mode briefly, so it is only made once
+/// and the answer is cached. Only Unix-like systems are supported.
+///
+/// # Example
+///
+/// ```no_run
+/// use term_ansi::terminal_background;
+///
+/// if let Some((r, g, b)) = terminal_background() {
+///     println!("background is #{:02x}{:02x}{:02x}", r, g, b);
+/// }
+/// ```
+pub fn terminal_background() -> Option<(u8, u8, u8)> {
+    static BACKGROUND: OnceLock<Option<(u8, u8, u8)>> = OnceLock::new();
+    *BACKGROUND.get_or_init(|| {
+        let reply = query("\x1b]11;?\x1b\\")?;
+        parse_color_reply(&reply, "\x1b]11;")
+    })
+}
+
+/// Returns whether the terminal has a dark background.
+///
+/// The background from [`terminal_background`] is used when the terminal
+/// answers; otherwise the `COLORFGBG` variable is consulted. When neither is
+/// available a dark background, the most common setup, is assumed.
+///
+/// # Example
+///
+/// ```no_run
+/// use term_ansi::{set_global_theme, terminal_is_dark, Theme};
+///
+/// set_global_theme(if terminal_is_dark() { Theme::dark() } else { Theme::light() });
+/// ```
+pub fn terminal_is_dark() -> bool {
+    match terminal_background() {
+        Some(background) => readable_fg(background) == (255, 255, 255),
+        None => std::env::var("COLORFGBG")
+            .ok()
+            .and_then(|value| colorfgbg_is_dark(&value))
+            .unwrap_or(true),
+    }
+}
+
+/// Returns the terminal's 16 basic and bright colors, asked for with OSC 4
+/// queries.
+///
+/// Entries are in ANSI order: black, red, green, yellow, blue, magenta, cyan
+/// and white, then their bright variants. Like [`terminal_background`], the
+/// answer is cached and `None` is returned when the terminal does not report
+/// every entry. Pass the result to [`set_basic_palette`](crate::set_basic_palette)
+/// to downgrade colors against it, or use the entries in contrast checks.
+///
+/// # Example
+///
+/// ```no_run
+/// use term_ansi::{contrast_ratio, terminal_background, terminal_palette};
+///
+/// if let (Some(palette), Some(background)) = (terminal_palette(), terminal_background()) {
+///     println!("red contrast: {:.1}", contrast_ratio(palette[1], background));
+/// }
+/// ```
+pub fn terminal_palette() -> Option<[(u8, u8, u8); 16]> {
+    static PALETTE: OnceLock<Option<[(u8, u8, u8); 16]>> = OnceLock::new();
+    *PALETTE.get_or_init(|| {
+        let request: String = (0..16).map(|i| format!("\x1b]4;{};?\x1b\\", i)).collect();
+        parse_palette_reply(&query(&request)?)
+    })
+}
+
+/// Extracts all 16 entries from the replies to OSC 4 palette queries.
+pub(crate) fn parse_palette_reply(reply: &str) -> Option<[(u8, u8, u8); 16]> {
+    let mut palette = [(0, 0, 0); 16];
+    for (index, entry) in palette.iter_mut().enumerate() {
+        *entry = parse_color_reply(reply, &format!("\x1b]4;{};", index))?;
+    }
+    Some(palette)
+}
+
+/// Returns the OSC 4 sequence that redefines palette entry `index` as `rgb`.
+///
+/// Entries 0-15 are the basic and bright colors, so every `red!` or
+/// `bright_blue!` on screen changes along with them. The change lasts for the
+/// terminal session; undo it with [`reset_palette`]. Returns an empty string
+/// when colors are disabled (see [`colors_enabled`]).
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::set_palette_entry;
+///
+/// print!("{}", set_palette_entry(1, (235, 80, 70)));
+/// ```
+pub fn set_palette_entry(index: u8, rgb: (u8, u8, u8)) -> String {
+    if !colors_enabled() {
+        return String::new();
+    }
+    format!(
+        "\x1b]4;{};rgb:{:02x}/{:02x}/{:02x}\x1b\\",
+        index, rgb.0, rgb.1, rgb.2
+    )
+}
+
+/// Returns the OSC 4 sequences that redefine all 16 basic and bright colors,
+/// in ANSI order, for installing a branded palette in a full-screen tool.
+///
+/// # Example
+///
+/// ```no_run
+/// use term_ansi::{reset_palette, set_basic_palette, set_palette, OKABE_ITO};
+///
+/// let mut palette = [(0, 0, 0); 16];
+/// palette[..8].copy_from_slice(&OKABE_ITO);
+/// palette[8..].copy_from_slice(&OKABE_ITO);
+/// print!("{}", set_palette(&palette));
+/// set_basic_palette(Some(palette));
+/// // ... run the application ...
+/// print!("{}", reset_palette());
+/// ```
+pub fn set_palette(palette: &[(u8, u8, u8); 16]) -> String {
+    (0..)
+        .zip(palette)
+        .map(|(index, &rgb)| set_palette_entry(index, rgb))
+        .collect()
+}
+
+/// Returns the OSC 104 sequence that restores the terminal's own palette
+/// after [`set_palette_entry`] or [`set_palette`].
+pub fn reset_palette() -> String {
+    if !colors_enabled() {
+        return String::new();
+    }
+    String::from("\x1b]104\x1b\\")
+}
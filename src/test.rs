@@ -1,5 +1,39 @@
+use std::borrow::Cow;
+use std::ffi::OsString;
+
+use crate::compare::ciede2000;
+use crate::hyperlink::{encode_url, env_hyperlink_support};
+use crate::quantize::rgb_params;
+use crate::sgr::{sgr_tokens, SgrParam};
+use crate::support::{choice_allows, env_color_choice, env_color_support};
+use crate::terminal::{colorfgbg_is_dark, parse_color_reply, parse_palette_reply};
+use crate::writer::downgrade_params;
 use crate::{
-    bg_green, bg_hsl, bg_hsv, black, blue, bold, cyan, green, hsl, magenta, red, rgb, white, yellow,
+    analogous, ansi256, ansi_to_bbcode, ansi_to_html, ansi_to_irc, ansi_to_svg, apply_color,
+    auto_fg, basic_palette, bg, bg_ansi256, bg_blue, bg_bright_black, bg_bright_blue,
+    bg_bright_cyan, bg_bright_green, bg_bright_magenta, bg_bright_red, bg_bright_white,
+    bg_bright_yellow, bg_cmyk, bg_cyan, bg_gradient, bg_grayscale, bg_green, bg_hex, bg_hsl,
+    bg_hsluv, bg_hsv, bg_hwb, bg_lab, bg_named, bg_oklch, bg_rgb, bg_yellow, black, blink, blue,
+    bold, boxed, bright_black, bright_blue, bright_cyan, bright_green, bright_magenta, bright_red,
+    bright_white, bright_yellow, clear_line, clear_screen, cmyk, cmyk_to_rgb, color, color_for,
+    colorize_markup, colorize_template, complement, conceal, contrast_ratio, curly_underline,
+    cursor, cwrite, cwriteln, cyan, darken, dashed_underline, delta_e, desaturate, dim,
+    dotted_underline, double_underline, env_extended_underline, extended_double_underline,
+    extended_underline_code, failure, gradient, grayscale, grayscale_index, green, hex, hex_to_rgb,
+    hsl, hsl_to_rgb, hsluv, hsluv_to_rgb, hsv_to_rgb, hwb, hwb_to_rgb, hyperlink, info_msg, invert,
+    italic, lab, lab_to_rgb, lerp_color, lerp_color_oklab, lighten, linear_to_srgb, linewise,
+    magenta, markup, meets_aa, meets_aaa, mix, named, named_to_rgb, nearest_named, oklch,
+    oklch_to_rgb, optimize_ansi, overline, paint, paint_bg, rapid_blink, readable_fg, red,
+    reset_palette, restore_cursor, reverse, rgb, rgb_to_ansi256, rgb_to_basic16, rgb_to_hsl,
+    rgb_to_hsv, rgb_to_lab, rgb_to_xyz, saturate, save_cursor, set_color_support,
+    set_hyperlinks_supported, set_palette, set_palette_entry, set_title, sgr, shades, simulate,
+    slice_ansi, split_complement, srgb_to_linear, strikethrough, strip_ansi, strip_ansi_cow, style,
+    styled, success, tetradic, theme, themed, tints, triadic, underline_ansi256, underline_rgb,
+    underline_style_code, vgradient, visible_width, warn_msg, white, xyz_to_rgb, yellow, Align,
+    AltScreen, AnsiParser, AnsiString, AnsiWriter, Attribute, Border, Color, ColorChoice,
+    ColorContext, ColorSupport, Colorize, Colours, Deficiency, LevelStyles, PrintScope,
+    ProgressBar, SavedCursor, SavedTitle, Spinner, Style, Table, TeeWriter, Theme, CSS_COLORS,
+    OKABE_ITO, TOL_BRIGHT, TOL_MUTED, TOL_VIBRANT,
 };
 
 #[test]
@@ -23,7 +57,7 @@ fn test_complex_nesting() {
             red!("A"),
             green!("B with {}", blue!("C"))
         ),
-        "\x1b[37mOuter \x1b[31mA\x1b[0m\x1b[37m, Inner \x1b[32mB with \x1b[34mC\x1b[0m\x1b[32m\x1b[0m\x1b[37m\x1b[0m\u{1b}[37m"
+        "\x1b[37mOuter \x1b[31mA\x1b[0m\x1b[37m, Inner \x1b[32mB with \x1b[34mC\x1b[0m\u{1b}[37m"
     );
 }
 
@@ -31,7 +65,7 @@ fn test_complex_nesting() {
 fn test_multiple_colors_in_line() {
     assert_eq!(
         red!("Error: {}, {}", blue!("File not found"), green!("Please check your path")),
-        "\x1b[31mError: \x1b[34mFile not found\x1b[0m\x1b[31m, \x1b[32mPlease check your path\x1b[0m\x1b[31m\x1b[0m\u{1b}[37m"
+        "\x1b[31mError: \x1b[34mFile not found\x1b[0m\x1b[31m, \x1b[32mPlease check your path\x1b[0m\u{1b}[37m"
     );
 }
 
@@ -65,6 +99,7 @@ fn test_cyan_color() {
 
 #[test]
 fn test_rgb_color() {
+    set_color_support(Some(ColorSupport::TrueColor));
     assert_eq!(
         rgb!(255, 0, 0, "Red RGB"),
         "\x1b[38;2;255;0;0mRed RGB\x1b[0m\u{1b}[37m"
@@ -81,6 +116,7 @@ fn test_rgb_color() {
 
 #[test]
 fn test_bg_hsl() {
+    set_color_support(Some(ColorSupport::TrueColor));
     assert_eq!(
         bg_hsl!(120.0, 1.0, 0.5, "HSL Green background"),
         "\x1b[48;2;0;255;0mHSL Green background\x1b[0m\u{1b}[37m"
@@ -89,6 +125,7 @@ fn test_bg_hsl() {
 
 #[test]
 fn test_bg_hsv() {
+    set_color_support(Some(ColorSupport::TrueColor));
     assert_eq!(
         bg_hsv!(240.0, 1.0, 1.0, "HSV Blue background"),
         "\x1b[48;2;0;0;255mHSV Blue background\x1b[0m\u{1b}[37m"
@@ -99,14 +136,1653 @@ fn test_bg_hsv() {
 fn test_nested_formatting() {
     assert_eq!(
         red!("{}", bg_green!("{}", bold!("Red text on green background"))),
-        "\x1b[31m\x1b[42m\x1b[1mRed text on green background\x1b[0m\x1b[42m\x1b[0m\x1b[31m\x1b[0m\u{1b}[37m"
+        "\x1b[31m\x1b[42m\x1b[1mRed text on green background\x1b[0m\u{1b}[37m"
     );
 }
 
 #[test]
 fn test_nested_hsl_hsv() {
+    set_color_support(Some(ColorSupport::TrueColor));
     assert_eq!(
         hsl!(0.0, 1.0, 0.5, "{}", bg_hsv!(120.0, 1.0, 1.0, "Red text on green background")),
-        "\x1b[38;2;255;0;0m\x1b[48;2;0;255;0mRed text on green background\x1b[0m\x1b[38;2;255;0;0m\x1b[0m\u{1b}[37m"
+        "\x1b[38;2;255;0;0m\x1b[48;2;0;255;0mRed text on green background\x1b[0m\u{1b}[37m"
+    );
+}
+
+#[test]
+fn test_strikethrough() {
+    assert_eq!(
+        strikethrough!("Deprecated"),
+        "\x1b[9mDeprecated\x1b[0m\u{1b}[37m"
+    );
+    assert_eq!(
+        red!("{}", strikethrough!("--old-flag")),
+        "\x1b[31m\x1b[9m--old-flag\x1b[0m\u{1b}[37m"
+    );
+}
+
+#[test]
+fn test_dim() {
+    assert_eq!(dim!("Secondary"), "\x1b[2mSecondary\x1b[0m\u{1b}[37m");
+    assert_eq!(
+        cyan!("{} {}", "main", dim!("detail")),
+        "\x1b[36mmain \x1b[2mdetail\x1b[0m\u{1b}[37m"
+    );
+}
+
+#[test]
+fn test_blink() {
+    assert_eq!(blink!("Alert"), "\x1b[5mAlert\x1b[0m\u{1b}[37m");
+    assert_eq!(rapid_blink!("Alert"), "\x1b[6mAlert\x1b[0m\u{1b}[37m");
+    assert_eq!(
+        red!("{}!", blink!("ALERT")),
+        "\x1b[31m\x1b[5mALERT\x1b[0m\x1b[31m!\x1b[0m\u{1b}[37m"
+    );
+}
+
+#[test]
+fn test_reverse() {
+    assert_eq!(reverse!("Selected"), "\x1b[7mSelected\x1b[0m\u{1b}[37m");
+    assert_eq!(
+        bg_green!("[{}] other", reverse!("item")),
+        "\x1b[42m[\x1b[7mitem\x1b[0m\x1b[42m] other\x1b[0m\u{1b}[37m"
+    );
+}
+
+#[test]
+fn test_conceal() {
+    assert_eq!(conceal!("hunter2"), "\x1b[8mhunter2\x1b[0m\u{1b}[37m");
+    assert_eq!(
+        yellow!("Password: {}", conceal!("hunter2")),
+        "\x1b[33mPassword: \x1b[8mhunter2\x1b[0m\u{1b}[37m"
+    );
+}
+
+#[test]
+fn test_double_underline() {
+    assert_eq!(
+        double_underline!("Heading"),
+        "\x1b[21mHeading\x1b[0m\u{1b}[37m"
+    );
+    assert_eq!(
+        blue!("{}", double_underline!("Heading")),
+        "\x1b[34m\x1b[21mHeading\x1b[0m\u{1b}[37m"
+    );
+}
+
+#[test]
+fn test_overline() {
+    assert_eq!(overline!("Name"), "\x1b[53mName\x1b[0m\u{1b}[37m");
+    assert_eq!(
+        bold!("{} | {}", overline!("Name"), overline!("Size")),
+        "\x1b[1m\x1b[53mName\x1b[0m\x1b[1m | \x1b[53mSize\x1b[0m\u{1b}[37m"
+    );
+}
+
+#[test]
+fn test_underline_color() {
+    assert_eq!(
+        underline_rgb!(255, 0, 0, "teh"),
+        "\x1b[4;58;2;255;0;0mteh\x1b[0m\u{1b}[37m"
+    );
+    assert_eq!(
+        underline_ansi256!(196, "teh"),
+        "\x1b[4;58;5;196mteh\x1b[0m\u{1b}[37m"
+    );
+    assert_eq!(
+        green!("{} word", underline_rgb!(255, 0, 0, "teh")),
+        "\x1b[32m\x1b[4;58;2;255;0;0mteh\x1b[0m\x1b[32m word\x1b[0m\u{1b}[37m"
+    );
+}
+
+#[test]
+fn test_underline_styles() {
+    assert_eq!(extended_underline_code(2, true), "\x1b[4:2m");
+    assert_eq!(extended_underline_code(3, true), "\x1b[4:3m");
+    assert_eq!(extended_underline_code(5, true), "\x1b[4:5m");
+    assert_eq!(extended_underline_code(3, false), "\x1b[4m");
+
+    assert_eq!(
+        extended_double_underline!("total"),
+        apply_color!(underline_style_code(2), "total")
+    );
+    assert_eq!(
+        curly_underline!("typo"),
+        apply_color!(underline_style_code(3), "typo")
+    );
+    assert_eq!(
+        dotted_underline!("link"),
+        apply_color!(underline_style_code(4), "link")
+    );
+    assert_eq!(
+        dashed_underline!("note"),
+        apply_color!(underline_style_code(5), "note")
+    );
+
+    let supported = |vars: &[(&str, &str)]| env_extended_underline(fake_env(vars));
+    assert!(!supported(&[]));
+    assert!(!supported(&[("TERM", "xterm-256color")]));
+    assert!(supported(&[("TERM", "xterm-kitty")]));
+    assert!(supported(&[("TERM_PROGRAM", "WezTerm")]));
+    assert!(supported(&[("VTE_VERSION", "6003")]));
+    assert!(!supported(&[("VTE_VERSION", "5000")]));
+}
+
+#[test]
+fn test_bright_colors() {
+    assert_eq!(bright_black!("Gray"), "\x1b[90mGray\x1b[0m\u{1b}[37m");
+    assert_eq!(bright_red!("Red"), "\x1b[91mRed\x1b[0m\u{1b}[37m");
+    assert_eq!(bright_green!("Green"), "\x1b[92mGreen\x1b[0m\u{1b}[37m");
+    assert_eq!(bright_yellow!("Yellow"), "\x1b[93mYellow\x1b[0m\u{1b}[37m");
+    assert_eq!(bright_blue!("Blue"), "\x1b[94mBlue\x1b[0m\u{1b}[37m");
+    assert_eq!(
+        bright_magenta!("Magenta"),
+        "\x1b[95mMagenta\x1b[0m\u{1b}[37m"
+    );
+    assert_eq!(bright_cyan!("Cyan"), "\x1b[96mCyan\x1b[0m\u{1b}[37m");
+    assert_eq!(bright_white!("White"), "\x1b[97mWhite\x1b[0m\u{1b}[37m");
+}
+
+#[test]
+fn test_nested_bright_colors() {
+    assert_eq!(
+        red!("Error: {}", bright_red!("disk full")),
+        "\x1b[31mError: \x1b[91mdisk full\x1b[0m\u{1b}[37m"
+    );
+}
+
+#[test]
+fn test_bright_backgrounds() {
+    assert_eq!(bg_bright_black!("Gray"), "\x1b[100mGray\x1b[0m\u{1b}[37m");
+    assert_eq!(bg_bright_red!("Red"), "\x1b[101mRed\x1b[0m\u{1b}[37m");
+    assert_eq!(bg_bright_green!("Green"), "\x1b[102mGreen\x1b[0m\u{1b}[37m");
+    assert_eq!(
+        bg_bright_yellow!("Yellow"),
+        "\x1b[103mYellow\x1b[0m\u{1b}[37m"
+    );
+    assert_eq!(bg_bright_blue!("Blue"), "\x1b[104mBlue\x1b[0m\u{1b}[37m");
+    assert_eq!(
+        bg_bright_magenta!("Magenta"),
+        "\x1b[105mMagenta\x1b[0m\u{1b}[37m"
+    );
+    assert_eq!(bg_bright_cyan!("Cyan"), "\x1b[106mCyan\x1b[0m\u{1b}[37m");
+    assert_eq!(bg_bright_white!("White"), "\x1b[107mWhite\x1b[0m\u{1b}[37m");
+}
+
+#[test]
+fn test_ansi256() {
+    assert_eq!(
+        ansi256!(208, "Orange"),
+        "\x1b[38;5;208mOrange\x1b[0m\u{1b}[37m"
+    );
+    assert_eq!(
+        ansi256!(27, "{}", bold!("Blue")),
+        "\x1b[38;5;27m\x1b[1mBlue\x1b[0m\u{1b}[37m"
+    );
+}
+
+#[test]
+fn test_bg_ansi256() {
+    assert_eq!(
+        bg_ansi256!(24, "Banner"),
+        "\x1b[48;5;24mBanner\x1b[0m\u{1b}[37m"
+    );
+    assert_eq!(
+        ansi256!(231, "{}", bg_ansi256!(24, "Banner")),
+        "\x1b[38;5;231m\x1b[48;5;24mBanner\x1b[0m\u{1b}[37m"
+    );
+}
+
+#[test]
+fn test_grayscale() {
+    assert_eq!(grayscale_index(0), 232);
+    assert_eq!(grayscale_index(23), 255);
+    assert_eq!(grayscale_index(200), 255);
+    assert_eq!(
+        grayscale!(12, "Gray"),
+        "\x1b[38;5;244mGray\x1b[0m\u{1b}[37m"
+    );
+    assert_eq!(
+        bg_grayscale!(4, "Shaded"),
+        "\x1b[48;5;236mShaded\x1b[0m\u{1b}[37m"
+    );
+}
+
+#[test]
+fn test_hex_to_rgb() {
+    assert_eq!(hex_to_rgb("#ff8800"), Some((255, 136, 0)));
+    assert_eq!(hex_to_rgb("1E1E2E"), Some((30, 30, 46)));
+    assert_eq!(hex_to_rgb("#f80"), Some((255, 136, 0)));
+    assert_eq!(hex_to_rgb("#ff880"), None);
+    assert_eq!(hex_to_rgb("#gg8800"), None);
+    assert_eq!(hex_to_rgb("#+f+f+f"), None);
+}
+
+#[test]
+fn test_hex_color() {
+    set_color_support(Some(ColorSupport::TrueColor));
+    assert_eq!(
+        hex!("#ff8800", "Orange"),
+        "\x1b[38;2;255;136;0mOrange\x1b[0m\u{1b}[37m"
+    );
+    assert_eq!(
+        hex!("#0f0", "Green"),
+        "\x1b[38;2;0;255;0mGreen\x1b[0m\u{1b}[37m"
+    );
+}
+
+#[test]
+#[should_panic(expected = "invalid hex color")]
+fn test_hex_color_invalid() {
+    let _ = hex!("#nothex", "Oops");
+}
+
+#[test]
+fn test_bg_hex() {
+    set_color_support(Some(ColorSupport::TrueColor));
+    assert_eq!(
+        bg_hex!("#1e1e2e", "Dark"),
+        "\x1b[48;2;30;30;46mDark\x1b[0m\u{1b}[37m"
+    );
+    assert_eq!(
+        hex!("#fff", "{}", bg_hex!("#1e1e2e", "Dark")),
+        "\x1b[38;2;255;255;255m\x1b[48;2;30;30;46mDark\x1b[0m\u{1b}[37m"
+    );
+}
+
+#[test]
+#[should_panic(expected = "invalid hex color: \"#12345\"")]
+fn test_bg_hex_invalid() {
+    let _ = bg_hex!("#12345", "Oops");
+}
+
+#[test]
+fn test_named_to_rgb() {
+    assert_eq!(CSS_COLORS.len(), 148);
+    assert!(CSS_COLORS.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    assert_eq!(named_to_rgb("tomato"), Some((255, 99, 71)));
+    assert_eq!(named_to_rgb("SteelBlue"), Some((70, 130, 180)));
+    assert_eq!(named_to_rgb("rebeccapurple"), Some((102, 51, 153)));
+    assert_eq!(named_to_rgb("notacolor"), None);
+}
+
+#[test]
+fn test_named_color() {
+    set_color_support(Some(ColorSupport::TrueColor));
+    assert_eq!(
+        named!("tomato", "Tomato"),
+        "\x1b[38;2;255;99;71mTomato\x1b[0m\u{1b}[37m"
+    );
+    assert_eq!(
+        bg_named!("navy", "Navy"),
+        "\x1b[48;2;0;0;128mNavy\x1b[0m\u{1b}[37m"
+    );
+}
+
+#[test]
+#[should_panic(expected = "unknown color name")]
+fn test_named_color_invalid() {
+    let _ = named!("blurple", "Oops");
+}
+
+#[test]
+fn test_cmyk_to_rgb() {
+    assert_eq!(cmyk_to_rgb(0.0, 0.0, 0.0, 0.0), (255, 255, 255));
+    assert_eq!(cmyk_to_rgb(0.0, 0.0, 0.0, 1.0), (0, 0, 0));
+    assert_eq!(cmyk_to_rgb(0.0, 1.0, 1.0, 0.0), (255, 0, 0));
+    assert_eq!(cmyk_to_rgb(1.0, 0.5, 0.0, 0.2), (0, 102, 204));
+    assert_eq!(cmyk_to_rgb(-1.0, 2.0, 0.0, 0.0), (255, 0, 255));
+}
+
+#[test]
+fn test_cmyk_color() {
+    set_color_support(Some(ColorSupport::TrueColor));
+    assert_eq!(
+        cmyk!(0.0, 1.0, 1.0, 0.0, "Red"),
+        "\x1b[38;2;255;0;0mRed\x1b[0m\u{1b}[37m"
+    );
+    assert_eq!(
+        bg_cmyk!(1.0, 0.5, 0.0, 0.2, "Brand"),
+        "\x1b[48;2;0;102;204mBrand\x1b[0m\u{1b}[37m"
+    );
+}
+
+#[test]
+fn test_lab_to_rgb() {
+    assert_eq!(lab_to_rgb(100.0, 0.0, 0.0), (255, 255, 255));
+    assert_eq!(lab_to_rgb(0.0, 0.0, 0.0), (0, 0, 0));
+    assert_eq!(lab_to_rgb(53.24, 80.09, 67.2), (255, 0, 0));
+    assert_eq!(lab_to_rgb(87.73, -86.18, 83.18), (0, 255, 0));
+    assert_eq!(lab_to_rgb(32.3, 79.19, -107.86), (0, 0, 255));
+}
+
+#[test]
+fn test_lab_color() {
+    set_color_support(Some(ColorSupport::TrueColor));
+    assert_eq!(
+        lab!(53.24, 80.09, 67.2, "Red"),
+        "\x1b[38;2;255;0;0mRed\x1b[0m\u{1b}[37m"
+    );
+    assert_eq!(
+        bg_lab!(100.0, 0.0, 0.0, "White"),
+        "\x1b[48;2;255;255;255mWhite\x1b[0m\u{1b}[37m"
+    );
+}
+
+#[test]
+fn test_oklch_to_rgb() {
+    assert_eq!(oklch_to_rgb(1.0, 0.0, 0.0), (255, 255, 255));
+    assert_eq!(oklch_to_rgb(0.0, 0.0, 0.0), (0, 0, 0));
+    assert_eq!(oklch_to_rgb(0.62796, 0.25768, 29.234), (255, 0, 0));
+    assert_eq!(oklch_to_rgb(0.86644, 0.29483, 142.495), (0, 255, 0));
+    assert_eq!(oklch_to_rgb(0.45201, 0.31321, 264.052), (0, 0, 255));
+}
+
+#[test]
+fn test_oklch_color() {
+    set_color_support(Some(ColorSupport::TrueColor));
+    assert_eq!(
+        oklch!(0.62796, 0.25768, 29.234, "Red"),
+        "\x1b[38;2;255;0;0mRed\x1b[0m\u{1b}[37m"
+    );
+    assert_eq!(
+        bg_oklch!(0.0, 0.0, 0.0, "Black"),
+        "\x1b[48;2;0;0;0mBlack\x1b[0m\u{1b}[37m"
+    );
+}
+
+#[test]
+fn test_hwb_to_rgb() {
+    assert_eq!(hwb_to_rgb(0.0, 0.0, 0.0), (255, 0, 0));
+    assert_eq!(hwb_to_rgb(120.0, 0.0, 0.5), (0, 128, 0));
+    assert_eq!(hwb_to_rgb(240.0, 0.2, 0.0), (51, 51, 255));
+    assert_eq!(hwb_to_rgb(480.0, 0.0, 0.0), (0, 255, 0));
+    assert_eq!(hwb_to_rgb(90.0, 0.6, 0.6), (128, 128, 128));
+    assert_eq!(hwb_to_rgb(0.0, 1.0, 0.0), (255, 255, 255));
+}
+
+#[test]
+fn test_hwb_color() {
+    set_color_support(Some(ColorSupport::TrueColor));
+    assert_eq!(
+        hwb!(0.0, 0.0, 0.0, "Red"),
+        "\x1b[38;2;255;0;0mRed\x1b[0m\u{1b}[37m"
+    );
+    assert_eq!(
+        bg_hwb!(120.0, 0.0, 0.5, "Green"),
+        "\x1b[48;2;0;128;0mGreen\x1b[0m\u{1b}[37m"
+    );
+}
+
+#[test]
+fn test_hsluv_to_rgb() {
+    assert_eq!(hsluv_to_rgb(0.0, 0.0, 100.0), (255, 255, 255));
+    assert_eq!(hsluv_to_rgb(0.0, 0.0, 0.0), (0, 0, 0));
+    assert_eq!(hsluv_to_rgb(12.177, 100.0, 53.237), (255, 0, 0));
+    assert_eq!(hsluv_to_rgb(127.715, 100.0, 87.737), (0, 255, 0));
+    assert_eq!(hsluv_to_rgb(265.874, 100.0, 32.301), (0, 0, 255));
+}
+
+#[test]
+fn test_hsluv_color() {
+    set_color_support(Some(ColorSupport::TrueColor));
+    assert_eq!(
+        hsluv!(12.177, 100.0, 53.237, "Red"),
+        "\x1b[38;2;255;0;0mRed\x1b[0m\u{1b}[37m"
+    );
+    assert_eq!(
+        bg_hsluv!(265.874, 100.0, 32.301, "Blue"),
+        "\x1b[48;2;0;0;255mBlue\x1b[0m\u{1b}[37m"
+    );
+}
+
+#[test]
+fn test_linear_srgb() {
+    assert_eq!(srgb_to_linear(0.0), 0.0);
+    assert!((srgb_to_linear(1.0) - 1.0).abs() < 1e-9);
+    assert!((srgb_to_linear(0.5) - 0.214041).abs() < 1e-6);
+    for i in 0..=255 {
+        let c: f64 = i as f64 / 255.0;
+        assert!((linear_to_srgb(srgb_to_linear(c)) - c).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn test_xyz_round_trip() {
+    let (x, y, z) = rgb_to_xyz(255, 255, 255);
+    assert!((x - 0.95047).abs() < 1e-4);
+    assert!((y - 1.0).abs() < 1e-4);
+    assert!((z - 1.08883).abs() < 1e-4);
+
+    for rgb in [(255, 0, 0), (0, 128, 255), (12, 34, 56), (200, 200, 10)] {
+        let (x, y, z) = rgb_to_xyz(rgb.0, rgb.1, rgb.2);
+        assert_eq!(xyz_to_rgb(x, y, z), rgb);
+    }
+}
+
+#[test]
+fn test_rgb_to_hsl() {
+    assert_eq!(rgb_to_hsl(255, 0, 0), (0.0, 1.0, 0.5));
+    assert_eq!(rgb_to_hsl(0, 255, 0), (120.0, 1.0, 0.5));
+    assert_eq!(rgb_to_hsl(0, 0, 255), (240.0, 1.0, 0.5));
+    assert_eq!(rgb_to_hsl(255, 255, 255), (0.0, 0.0, 1.0));
+
+    for rgb in [(255, 128, 0), (30, 30, 46), (10, 200, 150), (255, 0, 128)] {
+        let (h, s, l) = rgb_to_hsl(rgb.0, rgb.1, rgb.2);
+        assert_eq!(hsl_to_rgb(h, s, l), rgb);
+    }
+}
+
+#[test]
+fn test_rgb_to_hsv() {
+    assert_eq!(rgb_to_hsv(255, 0, 0), (0.0, 1.0, 1.0));
+    assert_eq!(rgb_to_hsv(0, 0, 0), (0.0, 0.0, 0.0));
+    assert_eq!(rgb_to_hsv(0, 0, 255), (240.0, 1.0, 1.0));
+
+    for rgb in [(255, 128, 0), (30, 30, 46), (10, 200, 150), (255, 0, 128)] {
+        let (h, s, v) = rgb_to_hsv(rgb.0, rgb.1, rgb.2);
+        assert_eq!(hsv_to_rgb(h, s, v), rgb);
+    }
+}
+
+#[test]
+fn test_color_codes() {
+    set_color_support(Some(ColorSupport::TrueColor));
+    assert_eq!(Color::Basic(Colours::Red).fg_code(), "\x1b[31m");
+    assert_eq!(Color::Basic(Colours::Red).bg_code(), "\x1b[41m");
+    assert_eq!(Color::BrightBasic(Colours::Cyan).fg_code(), "\x1b[96m");
+    assert_eq!(Color::BrightBasic(Colours::Cyan).bg_code(), "\x1b[106m");
+    assert_eq!(Color::Ansi256(208).fg_code(), "\x1b[38;5;208m");
+    assert_eq!(Color::Ansi256(208).bg_code(), "\x1b[48;5;208m");
+    assert_eq!(Color::Rgb(1, 2, 3).fg_code(), "\x1b[38;2;1;2;3m");
+    assert_eq!(Color::Rgb(1, 2, 3).bg_code(), "\x1b[48;2;1;2;3m");
+    assert_eq!(Color::from(Colours::Blue), Color::Basic(Colours::Blue));
+    assert_eq!(Color::from((1, 2, 3)), Color::Rgb(1, 2, 3));
+}
+
+#[test]
+fn test_apply_color_with_color() {
+    set_color_support(Some(ColorSupport::TrueColor));
+    let color = Color::Basic(Colours::Red);
+    assert_eq!(apply_color!(color, "Hello"), red!("Hello"));
+    assert_eq!(
+        apply_color!(Color::Rgb(255, 0, 0), "Red RGB"),
+        rgb!(255, 0, 0, "Red RGB")
+    );
+    assert_eq!(
+        apply_color!(
+            Color::Ansi256(24).bg_code(),
+            "{}",
+            apply_color!(Colours::Yellow, "Warn")
+        ),
+        "\x1b[48;5;24m\x1b[33mWarn\x1b[0m\u{1b}[37m"
+    );
+}
+
+#[test]
+fn test_style_builder() {
+    set_color_support(Some(ColorSupport::TrueColor));
+    let style = Style::new()
+        .fg(Colours::Red)
+        .bg(Color::Ansi256(236))
+        .bold()
+        .underline();
+    assert!(style.has(Attribute::Bold));
+    assert!(!style.has(Attribute::Italic));
+    assert_eq!(style.foreground(), Some(Color::Basic(Colours::Red)));
+    assert_eq!(style.sgr_params(), "1;4;31;48;5;236");
+    assert_eq!(style.prefix(), "\x1b[1;4;31;48;5;236m");
+    assert_eq!(style.to_string(), style.prefix());
+    assert_eq!(Style::new().prefix(), "");
+    assert!(Style::new().is_plain());
+}
+
+#[test]
+fn test_style_apply() {
+    set_color_support(Some(ColorSupport::TrueColor));
+    let style = Style::new().fg((255, 0, 0)).italic();
+    assert_eq!(
+        style.apply("Error"),
+        "\x1b[3;38;2;255;0;0mError\x1b[0m\u{1b}[37m"
+    );
+    assert_eq!(Style::new().apply("Plain"), "Plain");
+    assert_eq!(
+        blue!("{}!", Style::new().bold().apply("Hi")),
+        "\x1b[34m\x1b[1mHi\x1b[0m\x1b[34m!\x1b[0m\u{1b}[37m"
+    );
+}
+
+#[test]
+fn test_colorize() {
+    assert_eq!("Hello".red(), red!("Hello"));
+    assert_eq!(42.to_string().green(), green!("42"));
+    assert_eq!(3.5.bg_bright_blue(), "\x1b[104m3.5\x1b[0m\u{1b}[37m");
+    assert_eq!(
+        "error".red().bold(),
+        "\x1b[1m\x1b[31merror\x1b[0m\x1b[37m\x1b[0m\u{1b}[37m"
+    );
+    assert_eq!("x".rgb(1, 2, 3), rgb!(1, 2, 3, "x"));
+    assert_eq!("x".color(Colours::Cyan), cyan!("x"));
+    assert_eq!("x".on_color(Colours::Cyan), bg_cyan!("x"));
+    assert_eq!("x".style(Style::new().bold()), "\x1b[1mx\x1b[0m\u{1b}[37m");
+}
+
+#[test]
+fn test_styled() {
+    assert_eq!(styled(Colours::Red, 42).to_string(), "\x1b[31m42\x1b[0m");
+    assert_eq!(
+        format!("{:>5}", styled(Style::new().bold(), 7)),
+        "\x1b[1m    7\x1b[0m"
+    );
+    assert_eq!(styled(Style::new(), "plain").to_string(), "plain");
+    assert_eq!(styled(Color::Rgb(1, 2, 3), "x").into_inner(), "x");
+}
+
+#[test]
+fn test_paint() {
+    assert_eq!(paint(Color::Basic(Colours::Red), "Hello"), red!("Hello"));
+    assert_eq!(paint(Colours::Green, "Hello"), green!("Hello"));
+    assert_eq!(paint_bg(Colours::Blue, "Hello"), bg_blue!("Hello"));
+    assert_eq!(
+        paint_bg(Color::Rgb(100, 150, 200), "Hello"),
+        bg_rgb!(100, 150, 200, "Hello")
+    );
+
+    let painted: Vec<String> = ["a", "b"].iter().map(|s| paint(Colours::Cyan, s)).collect();
+    assert_eq!(painted, vec![cyan!("a"), cyan!("b")]);
+}
+
+#[test]
+fn test_context_restores_full_style() {
+    assert_eq!(
+        bg_yellow!("{}", bold!("a {} b", red!("c"))),
+        "\x1b[43m\x1b[1ma \x1b[31mc\x1b[0m\x1b[1;43m b\x1b[0m\x1b[37m"
+    );
+
+    ColorContext::push("\x1b[4:3;58;5;196m");
+    ColorContext::push("\x1b[38;2;1;2;3m\x1b[24m");
+    assert_eq!(ColorContext::current_color(), "\x1b[38;2;1;2;3;58;5;196m");
+    ColorContext::pop();
+    assert_eq!(ColorContext::current_color(), "\x1b[4:3;58;5;196m");
+    ColorContext::pop();
+    assert_eq!(ColorContext::current_color(), "\x1b[37m");
+}
+
+#[test]
+fn test_context_depth_and_snapshot() {
+    assert_eq!(ColorContext::depth(), 0);
+    ColorContext::push("\x1b[41m");
+    let snapshot = ColorContext::snapshot();
+    assert_eq!(snapshot.depth(), 1);
+
+    ColorContext::push("\x1b[1m");
+    ColorContext::push("\x1b[3m");
+    assert_eq!(ColorContext::depth(), 3);
+    ColorContext::restore(snapshot.clone());
+    assert_eq!(ColorContext::depth(), 1);
+    assert_eq!(ColorContext::current_color(), "\x1b[41m");
+
+    ColorContext::clear();
+    assert_eq!(ColorContext::depth(), 0);
+    assert_eq!(red!("Hello"), "\x1b[31mHello\x1b[0m\x1b[37m");
+    ColorContext::restore(snapshot);
+    assert_eq!(red!("Hello"), "\x1b[31mHello\x1b[0m\x1b[41m");
+    ColorContext::clear();
+}
+
+#[test]
+fn test_context_with() {
+    let lines = ColorContext::with(Colours::Blue, || {
+        assert_eq!(ColorContext::depth(), 1);
+        vec![bold!("a"), italic!("b")]
+    });
+    assert_eq!(
+        lines,
+        vec![
+            "\x1b[1ma\x1b[0m\x1b[34m".to_string(),
+            "\x1b[3mb\x1b[0m\x1b[34m".to_string()
+        ]
+    );
+    assert_eq!(ColorContext::depth(), 0);
+
+    let result = std::panic::catch_unwind(|| {
+        ColorContext::with(Colours::Red, || {
+            ColorContext::push("\x1b[1m");
+            panic!("boom");
+        })
+    });
+    assert!(result.is_err());
+    assert_eq!(ColorContext::depth(), 0);
+    assert_eq!(ColorContext::current_color(), "\x1b[37m");
+}
+
+#[test]
+fn test_context_snapshot_across_threads() {
+    ColorContext::push("\x1b[44m");
+    let snapshot = ColorContext::snapshot();
+    ColorContext::clear();
+
+    let (nested, top) = std::thread::spawn(move || {
+        let nested = snapshot.enter(|| red!("a"));
+        (nested, red!("a"))
+    })
+    .join()
+    .unwrap();
+    assert_eq!(nested, "\x1b[31ma\x1b[0m\x1b[44m");
+    assert_eq!(top, "\x1b[31ma\x1b[0m\x1b[37m");
+
+    let snapshot = ColorContext::snapshot();
+    let result = std::panic::catch_unwind(|| {
+        ColorContext::snapshot().enter(|| {
+            ColorContext::push("\x1b[1m");
+            panic!("boom");
+        })
+    });
+    assert!(result.is_err());
+    assert_eq!(ColorContext::snapshot(), snapshot);
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn test_context_task_scope() {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        let first = tokio::spawn(ColorContext::scope(async {
+            ColorContext::push("\x1b[41m");
+            tokio::task::yield_now().await;
+            red!("a")
+        }));
+        let second = tokio::spawn(ColorContext::scope(async {
+            ColorContext::push("\x1b[42m");
+            tokio::task::yield_now().await;
+            red!("a")
+        }));
+        assert_eq!(first.await.unwrap(), "\x1b[31ma\x1b[0m\x1b[41m");
+        assert_eq!(second.await.unwrap(), "\x1b[31ma\x1b[0m\x1b[42m");
+    });
+    assert_eq!(ColorContext::depth(), 0);
+}
+
+/// Returns a variable lookup that finds `vars`, for the `env_*` detection helpers.
+fn fake_env<'a>(vars: &'a [(&str, &str)]) -> impl Fn(&str) -> Option<OsString> + 'a {
+    move |name| {
+        vars.iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| OsString::from(value))
+    }
+}
+
+#[test]
+fn test_env_color_choice() {
+    let choice = |vars: &[(&str, &str)]| env_color_choice(fake_env(vars));
+
+    assert_eq!(choice(&[]), ColorChoice::Auto);
+    assert_eq!(choice(&[("NO_COLOR", "1")]), ColorChoice::Never);
+    assert_eq!(choice(&[("NO_COLOR", "")]), ColorChoice::Auto);
+    assert_eq!(choice(&[("CLICOLOR", "0")]), ColorChoice::Never);
+    assert_eq!(choice(&[("CLICOLOR", "1")]), ColorChoice::Auto);
+    assert_eq!(choice(&[("CLICOLOR_FORCE", "1")]), ColorChoice::Always);
+    assert_eq!(choice(&[("CLICOLOR_FORCE", "0")]), ColorChoice::Auto);
+    assert_eq!(
+        choice(&[("CLICOLOR", "0"), ("CLICOLOR_FORCE", "1")]),
+        ColorChoice::Always
+    );
+    assert_eq!(choice(&[("FORCE_COLOR", "3")]), ColorChoice::Always);
+    assert_eq!(choice(&[("FORCE_COLOR", "false")]), ColorChoice::Never);
+    assert_eq!(
+        choice(&[("NO_COLOR", "1"), ("FORCE_COLOR", "1")]),
+        ColorChoice::Never
+    );
+}
+
+#[test]
+fn test_choice_allows() {
+    let unreachable = || -> bool { panic!("terminal check should be skipped") };
+    assert!(choice_allows(ColorChoice::Always, unreachable));
+    assert!(!choice_allows(ColorChoice::Never, unreachable));
+    assert!(choice_allows(ColorChoice::Auto, || true));
+    assert!(!choice_allows(ColorChoice::Auto, || false));
+}
+
+#[test]
+fn test_env_color_support() {
+    let support = |vars: &[(&str, &str)]| env_color_support(fake_env(vars));
+
+    assert_eq!(support(&[]), ColorSupport::None);
+    assert_eq!(support(&[("TERM", "dumb")]), ColorSupport::None);
+    assert_eq!(support(&[("TERM", "xterm")]), ColorSupport::Basic16);
+    assert_eq!(
+        support(&[("TERM", "xterm-256color")]),
+        ColorSupport::Ansi256
+    );
+    assert_eq!(
+        support(&[("TERM", "xterm-256color"), ("COLORTERM", "truecolor")]),
+        ColorSupport::TrueColor
+    );
+    assert_eq!(support(&[("WT_SESSION", "1")]), ColorSupport::TrueColor);
+    assert_eq!(
+        support(&[("TERM_PROGRAM", "Apple_Terminal"), ("TERM", "xterm")]),
+        ColorSupport::Ansi256
+    );
+    assert_eq!(
+        support(&[("FORCE_COLOR", "2"), ("COLORTERM", "truecolor")]),
+        ColorSupport::Ansi256
+    );
+    assert!(ColorSupport::TrueColor > ColorSupport::Ansi256);
+    assert!(ColorSupport::Basic16 > ColorSupport::None);
+}
+
+#[test]
+fn test_rgb_downgrade() {
+    set_color_support(Some(ColorSupport::TrueColor));
+    assert_eq!(
+        rgb_params(255, 135, 0, false, ColorSupport::TrueColor),
+        "38;2;255;135;0"
+    );
+    assert_eq!(
+        rgb_params(255, 135, 0, false, ColorSupport::Ansi256),
+        "38;5;208"
+    );
+    assert_eq!(
+        rgb_params(128, 128, 128, true, ColorSupport::Ansi256),
+        "48;5;244"
+    );
+    assert_eq!(rgb_params(250, 10, 10, false, ColorSupport::Basic16), "91");
+    assert_eq!(rgb_params(200, 0, 0, true, ColorSupport::Basic16), "41");
+    assert_eq!(rgb_params(1, 2, 3, false, ColorSupport::None), "38;2;1;2;3");
+    assert_eq!(
+        rgb!(255, 135, 0, "x"),
+        "\x1b[38;2;255;135;0mx\x1b[0m\x1b[37m"
+    );
+
+    let mut code = String::new();
+    Style::new()
+        .fg(Color::Rgb(255, 135, 0))
+        .bg(Color::Ansi256(196))
+        .write_code(&mut code, ColorSupport::Basic16)
+        .unwrap();
+    assert_eq!(code, "\x1b[91;101m");
+}
+
+#[test]
+fn test_rgb_to_ansi256() {
+    assert_eq!(rgb_to_ansi256(0, 0, 0), 16);
+    assert_eq!(rgb_to_ansi256(255, 255, 255), 231);
+    assert_eq!(rgb_to_ansi256(255, 0, 0), 196);
+    assert_eq!(rgb_to_ansi256(0, 95, 135), 24);
+    assert_eq!(rgb_to_ansi256(8, 8, 8), 232);
+    assert_eq!(rgb_to_ansi256(238, 238, 238), 255);
+    assert_eq!(rgb_to_ansi256(100, 101, 99), 241);
+}
+
+#[test]
+fn test_rgb_to_basic16() {
+    assert_eq!(rgb_to_basic16(0, 0, 0), Color::Basic(Colours::Black));
+    assert_eq!(
+        rgb_to_basic16(255, 255, 255),
+        Color::BrightBasic(Colours::White)
+    );
+    assert_eq!(
+        rgb_to_basic16(130, 130, 130),
+        Color::BrightBasic(Colours::Black)
+    );
+    assert_eq!(rgb_to_basic16(0, 0, 200), Color::Basic(Colours::Blue));
+    assert_eq!(rgb_to_basic16(0, 190, 190), Color::Basic(Colours::Cyan));
+    assert_eq!(
+        rgb_to_basic16(255, 60, 60),
+        Color::BrightBasic(Colours::Red)
+    );
+}
+
+#[test]
+fn test_strip_ansi() {
+    assert_eq!(strip_ansi(&red!("a {} b", bold!("c"))), "a c b");
+    assert_eq!(strip_ansi(&rgb!(1, 2, 3, "x")), "x");
+    assert_eq!(
+        strip_ansi("\x1b]8;;https://example.com\x1b\\link\x1b]8;;\x07 \x1b[2K\x1b7ok"),
+        "link ok"
+    );
+    assert_eq!(strip_ansi("tail\x1b[31"), "tail");
+    assert!(matches!(strip_ansi_cow("héllo"), Cow::Borrowed("héllo")));
+    assert_eq!(strip_ansi_cow("\x1b[1mhé\x1b[0mllo"), "héllo");
+}
+
+#[test]
+fn test_visible_width() {
+    assert_eq!(visible_width(""), 0);
+    assert_eq!(visible_width(&red!("a {} b", bold!("c"))), 5);
+    assert_eq!(visible_width(&hex!("#ff8800", "héllo")), 5);
+    #[cfg(feature = "unicode-width")]
+    assert_eq!(visible_width(&green!("日本")), 4);
+    #[cfg(not(feature = "unicode-width"))]
+    assert_eq!(visible_width(&green!("日本")), 2);
+}
+
+#[test]
+fn test_slice_ansi() {
+    let line = format!("ab{}ef", red!("c{}d", bold!("X")));
+    assert_eq!(slice_ansi(&line, 3..5), "\x1b[1;31mX\x1b[0;31md\x1b[0m");
+    assert_eq!(slice_ansi(&line, 0..2), "ab");
+    assert_eq!(slice_ansi(&line, 2..3), "\x1b[31mc\x1b[0m");
+    assert_eq!(strip_ansi(&slice_ansi(&line, 1..)), "bcXdef");
+    assert_eq!(strip_ansi(&slice_ansi(&line, ..=usize::MAX)), "abcXdef");
+    assert_eq!(slice_ansi(&line, 10..20), "");
+    assert_eq!(slice_ansi("héllo", 1..=3), "éll");
+    #[cfg(feature = "unicode-width")]
+    assert_eq!(slice_ansi("a日b", 2..4), " b");
+}
+
+#[test]
+fn test_linewise() {
+    assert_eq!(
+        linewise!("{}", bg_blue!("a\nb{}", bold!("c\r\nd"))),
+        "\x1b[44ma\x1b[0m\n\x1b[44mb\x1b[1mc\x1b[0m\r\n\x1b[1;44md\x1b[0;37m"
+    );
+    assert_eq!(linewise("plain\ntext"), "plain\ntext");
+    assert_eq!(linewise(&red!("x\n")), "\x1b[31mx\x1b[0m\n\x1b[0;37m");
+}
+
+#[test]
+fn test_sgr_tokens() {
+    let raw: Vec<&str> = sgr_tokens("1;38;5;208;4:3;48:2::1:2:3;58;2;9;8;7;;39")
+        .map(|param| param.raw)
+        .collect();
+    assert_eq!(
+        raw,
+        ["1", "38;5;208", "4:3", "48:2::1:2:3", "58;2;9;8;7", "", "39"]
+    );
+
+    let params: Vec<SgrParam> = sgr_tokens("38;5;208;48:2::1:2:3;4:3;x").collect();
+    assert_eq!(params[0].color(), Some(Color::Ansi256(208)));
+    assert_eq!(params[1].color(), Some(Color::Rgb(1, 2, 3)));
+    assert_eq!((params[2].code(), params[2].sub()), (Some(4), Some("3")));
+    assert_eq!(params[3].code(), None);
+    assert_eq!(sgr_tokens("").next().map(|param| param.code()), Some(Some(0)));
+}
+
+#[test]
+fn test_ansi_parser() {
+    let text = "a\x1b[1;38;5;208mb\x1b[22;48:2::1:2:3mc\x1b]8;;x\x07d\x1b[0m\x1b[94;4:3me";
+    let spans: Vec<(Style, &str)> = AnsiParser::new(text).collect();
+    assert_eq!(
+        spans,
+        vec![
+            (Style::new(), "a"),
+            (Style::new().fg(Color::Ansi256(208)).bold(), "b"),
+            (
+                Style::new().fg(Color::Ansi256(208)).bg(Color::Rgb(1, 2, 3)),
+                "c"
+            ),
+            (
+                Style::new().fg(Color::Ansi256(208)).bg(Color::Rgb(1, 2, 3)),
+                "d"
+            ),
+            (
+                Style::new()
+                    .fg(Color::BrightBasic(Colours::Blue))
+                    .underline(),
+                "e"
+            ),
+        ]
+    );
+
+    let styled_text = Style::new().fg(Colours::Green).italic().apply("x");
+    let mut parser = AnsiParser::new(&styled_text);
+    assert_eq!(
+        parser.next(),
+        Some((Style::new().fg(Colours::Green).italic(), "x"))
+    );
+    assert_eq!(parser.next(), None);
+    assert_eq!(parser.style(), Style::new().fg(Colours::White));
+    assert_eq!(AnsiParser::new("").next(), None);
+}
+
+#[test]
+fn test_ansi_string() {
+    set_color_support(Some(ColorSupport::TrueColor));
+    let red = Style::new().fg(Colours::Red);
+    let mut text = AnsiString::styled(red, "ab");
+    text.push_styled(red, "c");
+    text.push_str("d");
+    text += AnsiString::styled(Color::Rgb(255, 135, 0), "é");
+    assert_eq!(text.as_str(), "abcdé");
+    assert_eq!(text.len(), 6);
+    assert_eq!(text.width(), 5);
+    assert_eq!(text.spans().count(), 3);
+
+    assert_eq!(
+        text.render(ColorSupport::TrueColor),
+        "\x1b[31mabc\x1b[0md\x1b[38;2;255;135;0mé\x1b[0m"
+    );
+    assert_eq!(
+        text.render(ColorSupport::Ansi256),
+        "\x1b[31mabc\x1b[0md\x1b[38;5;208mé\x1b[0m"
+    );
+    assert_eq!(text.render(ColorSupport::None), "abcdé");
+    assert_eq!(text.to_string(), text.render(ColorSupport::TrueColor));
+
+    let sliced = text.slice(2..4);
+    assert_eq!(sliced.render(ColorSupport::TrueColor), "\x1b[31mc\x1b[0md");
+    assert_eq!(text.slice(..=usize::MAX), text);
+    #[cfg(feature = "unicode-width")]
+    {
+        let wide = AnsiString::styled(red, "a日b");
+        for columns in [2..4, 0..2] {
+            let sliced = wide.slice(columns.clone());
+            assert_eq!(sliced.width(), 2);
+            assert_eq!(sliced.to_string(), slice_ansi(&wide.to_string(), columns));
+        }
+    }
+    assert_eq!(AnsiString::parse(&text.to_string()), text);
+    assert_eq!(
+        AnsiString::parse(&format!("{}y", red!("x"))),
+        AnsiString::styled(red, "x") + AnsiString::styled(Colours::White, "y")
+    );
+}
+
+#[test]
+fn test_optimize_ansi() {
+    assert_eq!(
+        optimize_ansi(&red!("a {} b", bold!("c"))),
+        "\x1b[31ma \x1b[1mc\x1b[0;31m b\x1b[0;37m"
+    );
+    assert_eq!(
+        optimize_ansi(&red!("{}", bg_green!("{}", bold!("x")))),
+        "\x1b[31;42;1mx\x1b[0;37m"
+    );
+    assert_eq!(optimize_ansi("\x1b[0mA\x1b[31m\x1b[0mB"), "\x1b[0mAB");
+    assert_eq!(
+        optimize_ansi("\x1b[38;5;0m\x1b]8;;x\x07\x1b[1mC\x1b[2K"),
+        "\x1b[38;5;0m\x1b]8;;x\x07\x1b[1mC\x1b[2K"
+    );
+    assert_eq!(optimize_ansi("\x1b[0;1m\x1b[4mA"), "\x1b[0;1;4mA");
+    assert_eq!(optimize_ansi("plain"), "plain");
+}
+
+#[test]
+fn test_gradient() {
+    set_color_support(Some(ColorSupport::TrueColor));
+    assert_eq!(
+        gradient("a b\x1b[1mc", (0, 0, 0), (255, 0, 255)),
+        "\x1b[38;2;0;0;0ma \x1b[38;2;170;0;170mb\x1b[1m\x1b[38;2;255;0;255mc"
+    );
+    assert_eq!(
+        gradient!((0, 0, 0), (0, 0, 0), "{}", "ab"),
+        "\x1b[38;2;0;0;0mab\x1b[0m\x1b[37m"
+    );
+    assert_eq!(
+        bg_gradient("a \n", (0, 0, 0), (0, 0, 200)),
+        "\x1b[48;2;0;0;0ma\x1b[48;2;0;0;100m \n"
+    );
+}
+
+#[test]
+fn test_vgradient() {
+    set_color_support(Some(ColorSupport::TrueColor));
+    assert_eq!(
+        vgradient(" a\nb\n\nc\n", (0, 0, 0), (0, 0, 3)),
+        " \x1b[38;2;0;0;0ma\n\x1b[38;2;0;0;1mb\n\n\x1b[38;2;0;0;3mc\n"
+    );
+}
+
+#[test]
+fn test_lerp_color() {
+    assert_eq!(lerp_color((10, 20, 30), (20, 40, 60), 0.5), (15, 30, 45));
+    assert_eq!(lerp_color((10, 20, 30), (20, 40, 60), 2.0), (20, 40, 60));
+    assert_eq!(lerp_color_oklab((0, 0, 0), (255, 255, 255), 0.0), (0, 0, 0));
+    let (r, g, b) = lerp_color_oklab((0, 0, 0), (255, 255, 255), 0.5);
+    assert!(r == g && g == b && r > 90 && r < 110);
+}
+
+#[test]
+fn test_mix() {
+    assert_eq!(mix((0, 0, 0), (255, 255, 255), 0.0), (255, 255, 255));
+    assert_eq!(mix((200, 100, 50), (200, 100, 50), 0.3), (200, 100, 50));
+    assert_eq!(mix((255, 0, 0), (0, 0, 255), 0.5), (188, 0, 188));
+}
+
+#[test]
+fn test_lighten_darken() {
+    let (r, g, b) = lighten((40, 110, 200), 0.1);
+    assert!(r > 40 && g > 110 && b > 200);
+    assert_eq!(darken(lighten((40, 110, 200), 0.1), 0.1), (40, 110, 200));
+    assert_eq!(lighten((40, 110, 200), 0.0), (40, 110, 200));
+}
+
+#[test]
+fn test_saturate_desaturate() {
+    let (r, g, b) = desaturate((200, 80, 60), 1.0);
+    assert!(r.abs_diff(g) <= 1 && g.abs_diff(b) <= 1);
+    assert_eq!(saturate((128, 128, 128), 0.5), (128, 128, 128));
+    let (r, g, _) = saturate((160, 110, 100), 0.5);
+    assert!(r > 160 && g < 110);
+}
+
+#[test]
+fn test_invert_complement() {
+    assert_eq!(invert((0, 128, 255)), (255, 127, 0));
+    assert_eq!(invert(invert((12, 34, 56))), (12, 34, 56));
+    assert_eq!(complement((0, 0, 255)), (255, 255, 0));
+    assert_eq!(complement((200, 150, 100)), (100, 150, 200));
+}
+
+#[test]
+fn test_shades_tints() {
+    let dark = shades((200, 100, 50), 5);
+    assert_eq!(dark.len(), 5);
+    assert_eq!(dark[0], (200, 100, 50));
+    assert!(dark.windows(2).all(|pair| pair[1].0 < pair[0].0));
+    assert!(dark[4] != (0, 0, 0));
+    let light = tints((200, 100, 50), 5);
+    assert!(light.windows(2).all(|pair| pair[1].2 > pair[0].2));
+    assert!(shades((1, 2, 3), 0).is_empty());
+}
+
+#[test]
+fn test_harmony_palettes() {
+    assert_eq!(triadic((255, 0, 0)), vec![(255, 0, 0), (0, 255, 0), (0, 0, 255)]);
+    assert_eq!(
+        tetradic((255, 0, 0)),
+        vec![(255, 0, 0), (128, 255, 0), (0, 255, 255), (128, 0, 255)]
+    );
+    assert_eq!(
+        split_complement((255, 0, 0)),
+        vec![(255, 0, 0), (0, 255, 128), (0, 128, 255)]
+    );
+    assert_eq!(analogous((9, 9, 9)), vec![(9, 9, 9); 3]);
+}
+
+#[test]
+fn test_color_for() {
+    assert_eq!(color_for("alice"), color_for("alice"));
+    assert_ne!(color_for("alice"), color_for("bob"));
+    assert_eq!(color_for("alice"), (241, 125, 89));
+    let (r, g, b) = color_for("");
+    let (_, _, lightness) = rgb_to_hsl(r, g, b);
+    assert!(lightness > 0.3 && lightness < 0.9);
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn test_random_pleasant_color() {
+    for _ in 0..100 {
+        let (r, g, b) = crate::random_pleasant_color();
+        let (_, _, lightness) = rgb_to_hsl(r, g, b);
+        assert!(lightness > 0.3 && lightness < 0.9);
+    }
+}
+
+#[test]
+fn test_delta_e() {
+    let pairs = [
+        ((50.0, 2.6772, -79.7751), (50.0, 0.0, -82.7485), 2.0425),
+        ((50.0, 2.5, 0.0), (50.0, 0.0, -2.5), 4.3065),
+        ((50.0, 2.5, 0.0), (73.0, 25.0, -18.0), 27.1492),
+        ((90.8027, -2.0831, 1.441), (91.1528, -1.6435, 0.0447), 1.4441),
+    ];
+    for (lab1, lab2, expected) in pairs {
+        assert!((ciede2000(lab1, lab2) - expected).abs() < 1e-4);
+    }
+    assert_eq!(delta_e((1, 2, 3), (1, 2, 3)), 0.0);
+    let (l, a, b) = rgb_to_lab(255, 255, 255);
+    assert!((l - 100.0).abs() < 1e-3 && a.abs() < 1e-2 && b.abs() < 1e-2);
+    assert_eq!(nearest_named((255, 99, 71)), "tomato");
+    assert_eq!(nearest_named((128, 128, 128)), "gray");
+}
+
+#[test]
+fn test_contrast_ratio() {
+    assert_eq!(contrast_ratio((255, 255, 255), (0, 0, 0)), 21.0);
+    assert_eq!(contrast_ratio((90, 90, 90), (90, 90, 90)), 1.0);
+    assert!((contrast_ratio((118, 118, 118), (255, 255, 255)) - 4.54).abs() < 0.01);
+    assert!(meets_aa((118, 118, 118), (255, 255, 255)));
+    assert!(!meets_aa((130, 130, 130), (255, 255, 255)));
+    assert!(meets_aaa((0, 0, 0), (200, 200, 200)));
+    assert!(!meets_aaa((255, 0, 0), (0, 0, 0)));
+}
+
+#[test]
+fn test_auto_fg() {
+    set_color_support(Some(ColorSupport::TrueColor));
+    assert_eq!(
+        auto_fg!(255, 220, 0, "v{}", 2),
+        "\x1b[38;2;0;0;0m\x1b[48;2;255;220;0mv2\x1b[0m\x1b[37m"
+    );
+    assert_eq!(readable_fg((0, 0, 0)), (255, 255, 255));
+    assert_eq!(readable_fg((255, 255, 255)), (0, 0, 0));
+}
+
+#[test]
+fn test_simulate_deficiency() {
+    for deficiency in [
+        Deficiency::Protanopia,
+        Deficiency::Deuteranopia,
+        Deficiency::Tritanopia,
+    ] {
+        assert_eq!(simulate((0, 0, 0), deficiency), (0, 0, 0));
+        assert_eq!(simulate((255, 255, 255), deficiency), (255, 255, 255));
+    }
+    let red = simulate((255, 0, 0), Deficiency::Deuteranopia);
+    let green = simulate((0, 255, 0), Deficiency::Deuteranopia);
+    assert!(delta_e(red, green) < delta_e((255, 0, 0), (0, 255, 0)));
+    assert_eq!(simulate((255, 0, 0), Deficiency::Protanopia), (109, 95, 0));
+}
+
+#[test]
+fn test_accessible_palettes() {
+    for palette in [&OKABE_ITO[..], &TOL_BRIGHT, &TOL_VIBRANT, &TOL_MUTED] {
+        for (i, &a) in palette.iter().enumerate() {
+            for &b in &palette[i + 1..] {
+                assert!(delta_e(a, b) > 5.0);
+            }
+        }
+    }
+    assert_eq!(OKABE_ITO[1], (230, 159, 0));
+}
+
+#[test]
+fn test_semantic_messages() {
+    assert_eq!(theme(), Theme::default());
+    assert_eq!(success!("ok"), "\x1b[32mok\x1b[0m\x1b[37m");
+    assert_eq!(
+        failure!("{} failed", bold!("x")),
+        "\x1b[1;31m\x1b[1mx\x1b[0m\x1b[1;31m failed\x1b[0m\x1b[37m"
+    );
+    assert_eq!(warn_msg!("w"), "\x1b[33mw\x1b[0m\x1b[37m");
+    assert_eq!(info_msg!("i"), "\x1b[36mi\x1b[0m\x1b[37m");
+}
+
+#[test]
+fn test_theme_roles() {
+    let mut theme = Theme::default();
+    for role in Theme::ROLES {
+        assert!(theme.get(role).is_some());
+    }
+    *theme.get_mut("muted").unwrap() = Style::new().fg(Colours::Black);
+    assert_eq!(theme.muted, Style::new().fg(Colours::Black));
+    assert!(theme.get_mut("nope").is_none());
+    assert_eq!(theme.apply("muted", "m"), "\x1b[30mm\x1b[0m\x1b[37m");
+    assert_eq!(theme.apply("nope", "m"), "m");
+}
+
+#[test]
+fn test_style_parse() {
+    assert_eq!(
+        Style::parse("Bold, bright-cyan on 236"),
+        Some(
+            Style::new()
+                .bold()
+                .fg(Color::BrightBasic(Colours::Cyan))
+                .bg(Color::Ansi256(236))
+        )
+    );
+    assert_eq!(Style::parse("  "), Some(Style::new()));
+    assert_eq!(Style::parse("red on"), None);
+    assert_eq!(Color::parse("#F80"), Some(Color::Rgb(255, 136, 0)));
+    assert_eq!(Color::parse("256"), None);
+}
+
+#[test]
+fn test_theme_env_overrides() {
+    let shared = "error=blue:bogus=red:muted=nonsense:info=on red";
+    let theme = Theme::default().env_overrides("myapp", |name| match name {
+        "TERM_ANSI_THEME" => Some(OsString::from(shared)),
+        "MYAPP_COLOR_ERROR" => Some(OsString::from("#ff5555")),
+        "MYAPP_COLOR_ACCENT" => Some(OsString::from("italic")),
+        _ => None,
+    });
+    assert_eq!(theme.error, Style::new().fg(Color::Rgb(255, 85, 85)));
+    assert_eq!(theme.info, Style::new().bg(Colours::Red));
+    assert_eq!(theme.accent, Style::new().italic());
+    assert_eq!(theme.muted, Theme::default().muted);
+}
+
+#[test]
+fn test_themed() {
+    assert_eq!(
+        themed!("accent", "{}!", "hi"),
+        "\x1b[1;35mhi!\x1b[0m\x1b[37m"
+    );
+    assert_eq!(themed!("unknown", "x"), "x");
+}
+
+#[test]
+fn test_background_reply() {
+    let reply = "\x1b]11;rgb:1e1e/2020/fefe\x1b\\\x1b[?62;22c";
+    assert_eq!(parse_color_reply(reply, "\x1b]11;"), Some((30, 32, 254)));
+    assert_eq!(parse_color_reply("\x1b]11;rgb:f/80/000\x07", "\x1b]11;"), Some((255, 128, 0)));
+    assert_eq!(parse_color_reply("\x1b[?62;22c", "\x1b]11;"), None);
+    assert_eq!(colorfgbg_is_dark("15;0"), Some(true));
+    assert_eq!(colorfgbg_is_dark("0;default;15"), Some(false));
+    assert_eq!(colorfgbg_is_dark("default"), None);
+    assert_eq!(Theme::default(), Theme::dark());
+    assert_ne!(Theme::light().info, Theme::dark().info);
+}
+
+#[test]
+fn test_palette_reply() {
+    let reply: String = (0..16)
+        .rev()
+        .map(|i| format!("\x1b]4;{};rgb:{:02x}/00/ff\x07", i, i * 16))
+        .chain(["\x1b[?62c".to_string()])
+        .collect();
+    let palette = parse_palette_reply(&reply).unwrap();
+    assert_eq!(palette[0], (0, 0, 255));
+    assert_eq!(palette[1], (16, 0, 255));
+    assert_eq!(palette[15], (240, 0, 255));
+    assert_eq!(parse_palette_reply("\x1b]4;0;rgb:00/00/00\x07"), None);
+    assert_eq!(basic_palette()[1], (205, 0, 0));
+}
+
+#[test]
+fn test_set_palette() {
+    assert_eq!(
+        set_palette_entry(9, (255, 16, 0)),
+        "\x1b]4;9;rgb:ff/10/00\x1b\\"
+    );
+    let palette = set_palette(&[(1, 2, 3); 16]);
+    assert!(palette.starts_with("\x1b]4;0;rgb:01/02/03\x1b\\\x1b]4;1;"));
+    assert_eq!(parse_palette_reply(&palette), Some([(1, 2, 3); 16]));
+    assert_eq!(reset_palette(), "\x1b]104\x1b\\");
+}
+
+#[test]
+fn test_colorize_markup() {
+    set_color_support(Some(ColorSupport::TrueColor));
+    assert_eq!(
+        colorize_markup("<bold #ff0000 on blue>x</> a < b <nope>"),
+        "\x1b[1;38;2;255;0;0;44mx\x1b[0m\x1b[37m a < b <nope>"
+    );
+    assert_eq!(
+        red!("{}", markup!("<u>{}</i></u>", 1)),
+        "\x1b[31m\x1b[4m1</i>\x1b[0m\x1b[37m"
+    );
+    assert_eq!(colorize_markup("<i>open"), "\x1b[3mopen\x1b[0m\x1b[37m");
+    assert_eq!(ColorContext::depth(), 0);
+}
+
+#[test]
+fn test_colorize_template() {
+    assert_eq!(
+        colorize_template("{{red}} {error}x{/red}{/error} {n} {red"),
+        "{{red}} \x1b[1;31mx{/red}\x1b[0m\x1b[37m {n} {red"
+    );
+    assert_eq!(
+        colorize_template("{u}{i}a{/i}b"),
+        "\x1b[4m\x1b[3ma\x1b[0m\x1b[4mb\x1b[0m\x1b[37m"
+    );
+    assert_eq!(colorize_markup("<warning>w</warning>"), "\x1b[33mw\x1b[0m\x1b[37m");
+    assert_eq!(ColorContext::depth(), 0);
+}
+
+#[test]
+fn test_style_macro() {
+    set_color_support(Some(ColorSupport::TrueColor));
+    let x = 1;
+    assert_eq!(
+        style!(fg: red, bg: black, bold, underline; "text {}", x),
+        "\x1b[1;4;31;40mtext 1\x1b[0m\x1b[37m"
+    );
+    assert_eq!(
+        style!(bg: Colours::Blue, fg: (255, 136, 0), italic, ; "{x}"),
+        "\x1b[3;38;2;255;136;0;44m1\x1b[0m\x1b[37m"
+    );
+    assert_eq!(style!(; "plain {}", x), "plain 1");
+}
+
+#[test]
+fn test_color_macro() {
+    assert_eq!(color!(Colours::Red, "{}", 1), red!("1"));
+    assert_eq!(color!(Colours::ALL[4], "{}", bold!("b")), blue!("{}", bold!("b")));
+}
+
+#[test]
+fn test_bg_macro() {
+    for colour in Colours::ALL {
+        assert_eq!(colour.bg_code(), Color::Basic(colour).bg_code());
+    }
+    assert_eq!(bg!(Colours::Blue, "{}", 1), bg_blue!("1"));
+}
+
+#[test]
+fn test_sgr_macro() {
+    assert_eq!(sgr!([1, 31], "a{}", 1), "\x1b[1;31ma1\x1b[0m\x1b[37m");
+    assert_eq!(
+        red!("{}", sgr!([4, 58, 5, 208,], "u")),
+        "\x1b[31m\x1b[4;58;5;208mu\x1b[0m\x1b[37m"
+    );
+    assert_eq!(sgr!([], "plain"), "plain");
+}
+
+#[test]
+fn test_print_scope() {
+    let scope = PrintScope::new(Colours::Red, true);
+    let text = scope.wrap(format_args!("{} x", bold!("b")), "\n").to_string();
+    drop(scope);
+    assert_eq!(text, "\x1b[31m\x1b[1mb\x1b[0m\x1b[31m x\x1b[0m\x1b[37m\n");
+    assert_eq!(ColorContext::depth(), 0);
+
+    let scope = PrintScope::new(Style::new().bold(), false);
+    assert_eq!(scope.wrap(format_args!("{}", 1), "").to_string(), "1");
+}
+
+#[test]
+fn test_cwrite() {
+    use std::fmt::Write as _;
+    use std::io::Write as _;
+
+    let mut buffer = String::new();
+    cwrite!(buffer, bright_red, "{}", 1).unwrap();
+    cwriteln!(&mut buffer, Style::new().bold(), "{}", blue!("2")).unwrap();
+    assert_eq!(
+        buffer,
+        format!("{}{}\n", bright_red!("1"), bold!("{}", blue!("2")))
+    );
+
+    let mut bytes: Vec<u8> = Vec::new();
+    let colour = Colours::Green;
+    cwriteln!(bytes, colour, "ok").unwrap();
+    assert_eq!(bytes, format!("{}\n", green!("ok")).into_bytes());
+}
+
+#[test]
+fn test_ansi_writer() {
+    use std::io::Write;
+
+    let mut writer = AnsiWriter::new(Vec::new(), ColorSupport::Ansi256);
+    for chunk in ["a\x1b[1;38;2;255;", "135;0mb\x1b", "[0m\x1b]8;;x\x1b", "\\c"] {
+        writer.write_all(chunk.as_bytes()).unwrap();
+    }
+    assert_eq!(writer.into_inner(), b"a\x1b[1;38;5;208mb\x1b[0m\x1b]8;;x\x1b\\c");
+
+    let mut writer = AnsiWriter::new(Vec::new(), ColorSupport::None);
+    writer.write_all(b"\x1b[31ma\x1b]8;;x\x07b\x1b[0m").unwrap();
+    assert_eq!(writer.into_inner(), b"ab");
+
+    assert_eq!(downgrade_params("4;58:2::250:10:10;48;5;196", ColorSupport::Basic16), "4;101");
+    assert_eq!(downgrade_params("58;2;255;135;0", ColorSupport::Ansi256), "58;5;208");
+    assert_eq!(downgrade_params("", ColorSupport::Basic16), "");
+}
+
+#[test]
+fn test_tee_writer() {
+    use std::io::Write;
+
+    let mut tee = TeeWriter::new(Vec::new(), Vec::new());
+    for chunk in ["\x1b[1", "mok\x1b[0m", " done\n"] {
+        tee.write_all(chunk.as_bytes()).unwrap();
+    }
+    tee.flush().unwrap();
+    let (styled, plain) = tee.into_inner();
+    assert_eq!(styled, b"\x1b[1mok\x1b[0m done\n");
+    assert_eq!(plain, b"ok done\n");
+}
+
+#[test]
+fn test_ansi_to_html() {
+    assert_eq!(ansi_to_html("a&b\n"), "a&amp;b\n");
+    assert_eq!(
+        ansi_to_html("\x1b[7;38;5;208;48;2;1;2;3m\"x'\x1b[0m"),
+        "<span style=\"color:#010203;background-color:#ff8700\">&quot;x&#39;</span>"
+    );
+    assert_eq!(
+        ansi_to_html("\x1b[2;3;4;9;53mx"),
+        "<span style=\"opacity:0.6;font-style:italic;\
+         text-decoration:underline overline line-through\">x</span>"
+    );
+}
+
+#[test]
+fn test_ansi_to_irc_bbcode() {
+    let text = "\x1b[1;4;38;2;250;10;10;44mhi\x1b[0m \x1b[3;9;104mx";
+    assert_eq!(
+        ansi_to_irc(text),
+        "\x0304,02\x02\x1fhi\x0f \x0399,12\x1d\x1ex\x0f"
+    );
+    assert_eq!(
+        ansi_to_bbcode(text),
+        "[color=#fa0a0a][b][u]hi[/u][/b][/color] [i][s]x[/s][/i]"
+    );
+    assert_eq!(ansi_to_irc("plain"), "plain");
+    assert_eq!(
+        ansi_to_irc("\x1b[31m,5 items\x1b[0m"),
+        "\x0305\x02\x02,5 items\x0f"
+    );
+}
+
+#[test]
+fn test_ansi_to_svg() {
+    let svg = ansi_to_svg("a<\x1b[7;1mb\x1b[0m\n\n\x1b[8mhidden\x1b[0m\n");
+    let lines: Vec<&str> = svg.lines().collect();
+    assert_eq!(
+        lines[0],
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"54\" height=\"60\" \
+         viewBox=\"0 0 54 60\" font-family=\"monospace\" font-size=\"15\" xml:space=\"preserve\">"
+    );
+    assert_eq!(
+        &lines[1..],
+        [
+            "<rect width=\"100%\" height=\"100%\" fill=\"#000000\"/>",
+            "<rect x=\"18\" y=\"0\" width=\"9\" height=\"20\" fill=\"#e5e5e5\"/>",
+            "<text x=\"0\" y=\"15\" fill=\"#e5e5e5\">a&lt;</text>",
+            "<text x=\"18\" y=\"15\" fill=\"#000000\" font-weight=\"bold\">b</text>",
+            "</svg>",
+        ]
+    );
+}
+
+#[test]
+fn test_level_styles() {
+    let theme = Theme::light();
+    let mut levels = LevelStyles::from_theme(&theme);
+    assert_eq!(levels.warn, theme.warning);
+    assert_eq!(levels.get("TRACE"), Some(theme.muted));
+    assert_eq!(levels.get("Warning"), Some(theme.warning));
+    assert_eq!(levels.get("fatal"), None);
+
+    *levels.get_mut("debug").unwrap() = Style::new().italic();
+    assert_eq!(levels.apply("DEBUG", "x"), "\x1b[3mx\x1b[0m\x1b[37m");
+    assert_eq!(levels.apply("fatal", "x"), "x");
+    assert_eq!(LevelStyles::default().error, Style::new().fg(Colours::Red).bold());
+}
+
+#[test]
+fn test_hyperlink() {
+    set_hyperlinks_supported(Some(true));
+    assert_eq!(
+        hyperlink!("https://example.com/a b", "{} {}", "read", "more"),
+        "\x1b]8;;https://example.com/a%20b\x1b\\read more\x1b]8;;\x1b\\"
+    );
+    assert_eq!(encode_url("https://example.com"), "https://example.com");
+    assert_eq!(encode_url("/tmp/ü"), "/tmp/%C3%BC");
+}
+
+#[test]
+fn test_env_hyperlink_support() {
+    let supported = |vars: &[(&str, &str)]| env_hyperlink_support(fake_env(vars));
+    assert!(!supported(&[]));
+    assert!(!supported(&[("TERM", "xterm-256color")]));
+    assert!(supported(&[("TERM", "xterm-kitty")]));
+    assert!(supported(&[("TERM_PROGRAM", "WezTerm")]));
+    assert!(supported(&[("VTE_VERSION", "6003")]));
+    assert!(!supported(&[("VTE_VERSION", "4601")]));
+    assert!(!supported(&[("TERM", "dumb"), ("WT_SESSION", "1")]));
+    assert!(supported(&[("TERM", "dumb"), ("FORCE_HYPERLINK", "1")]));
+    assert!(!supported(&[("TERM_PROGRAM", "iTerm.app"), ("FORCE_HYPERLINK", "0")]));
+}
+
+#[test]
+fn test_cursor() {
+    assert_eq!(cursor::move_up(3), "\x1b[3A");
+    assert_eq!(cursor::move_left(0), "");
+    assert_eq!(cursor::prev_line(2), "\x1b[2F");
+    assert_eq!(cursor::move_to(0, 10), "\x1b[1;10H");
+    assert_eq!(cursor::move_to_column(5), "\x1b[5G");
+    assert_eq!(strip_ansi(&format!("{}a{}", cursor::hide(), cursor::show())), "a");
+}
+
+#[test]
+fn test_clear_macros() {
+    assert_eq!(clear_line!(), "\x1b[2K");
+    assert_eq!(clear_line!(left), "\x1b[1K");
+    assert_eq!(clear_screen!(), "\x1b[2J\x1b[H");
+    assert_eq!(clear_screen!(above), "\x1b[1J");
+    assert_eq!(strip_ansi(&format!("\r{}50%", clear_line!())), "\r50%");
+}
+
+#[test]
+fn test_screen_guards() {
+    use std::io::Write;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let mut output = Vec::new();
+    {
+        let mut screen = AltScreen::enter_on(&mut output).unwrap();
+        write!(screen, "picker").unwrap();
+    }
+    assert_eq!(output, b"\x1b[?1049hpicker\x1b[?1049l");
+
+    let mut output = Vec::new();
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let _saved = SavedCursor::save_on(&mut output).unwrap();
+        panic!("redraw failed");
+    }));
+    assert!(result.is_err());
+    assert_eq!(output, format!("{}{}", save_cursor(), restore_cursor()).as_bytes());
+}
+
+#[test]
+fn test_set_title() {
+    assert_eq!(set_title!("build {}", 2), "\x1b]0;build 2\x1b\\");
+    assert_eq!(set_title("a\x1b\x07b\n"), "\x1b]0;ab\x1b\\");
+
+    let mut output = Vec::new();
+    {
+        let mut title = SavedTitle::save_on(&mut output).unwrap();
+        title.set("x").unwrap();
+    }
+    assert_eq!(output, b"\x1b[22;0t\x1b]0;x\x1b\\\x1b[23;0t");
+}
+
+#[test]
+fn test_progress_bar() {
+    set_color_support(Some(ColorSupport::TrueColor));
+    let bar = ProgressBar::new()
+        .glyphs("#", ".")
+        .style(Colours::Green)
+        .empty_style(Style::new());
+    assert_eq!(bar.render(0.5, 6), "\x1b[32m###\x1b[0m...");
+    assert_eq!(strip_ansi(&bar.render(1.5, 4)), "####");
+    assert_eq!(strip_ansi(&bar.render(f64::NAN, 4)), "....");
+
+    let wide = ProgressBar::new()
+        .glyphs("##", ".")
+        .style(Style::new())
+        .empty_style(Style::new());
+    assert_eq!(wide.render(0.5, 5), "##...");
+    assert_eq!(visible_width(&ProgressBar::new().render(0.33, 7)), 7);
+
+    let bar = ProgressBar::new()
+        .glyphs("=", " ")
+        .gradient((255, 0, 0), (0, 0, 255))
+        .empty_style(Style::new());
+    assert_eq!(
+        bar.render(0.5, 4),
+        "\x1b[38;2;255;0;0m=\x1b[38;2;170;0;85m=\x1b[0m  "
+    );
+
+    let bar = ProgressBar::new()
+        .glyphs("=", " ")
+        .thresholds([(0.5, Style::new().bold()), (0.0, Style::new().dim())])
+        .empty_style(Style::new());
+    assert_eq!(bar.render(0.25, 4), "\x1b[2m=\x1b[0m   ");
+    assert_eq!(bar.render(0.75, 4), "\x1b[1m===\x1b[0m ");
+}
+
+#[test]
+fn test_spinner() {
+    let mut spinner = Spinner::new(["a", "bb", "c"]).colors([Colours::Red, Colours::Blue]);
+    assert_eq!(spinner.tick(), "\x1b[31ma\x1b[0m ");
+    assert_eq!(spinner.tick(), "\x1b[34mbb\x1b[0m");
+    assert_eq!(spinner.tick(), "\x1b[31mc\x1b[0m ");
+    assert_eq!(spinner.tick(), "\x1b[34ma\x1b[0m ");
+    assert_eq!(spinner.frame_at(1), "\x1b[34mbb\x1b[0m");
+    assert_eq!(strip_ansi(&Spinner::dots().frame_at(12)), "⠹");
+    assert_eq!(Spinner::new(Vec::<String>::new()).tick(), "");
+}
+
+#[test]
+fn test_table() {
+    let table = Table::new()
+        .header(["a", "bb"])
+        .row([red!("xyz"), String::from("1")])
+        .row(["é"])
+        .align(1, Align::Center)
+        .border(Border::Ascii)
+        .header_style(Style::new().bold());
+    let rendered = table.to_string();
+    assert_eq!(
+        strip_ansi(&rendered),
+        "+-----+----+\n| a   | bb |\n+-----+----+\n| xyz | 1  |\n| é   |    |\n+-----+----+\n"
+    );
+    assert!(rendered.contains("| \x1b[1ma\x1b[0m   |"));
+
+    let plain = Table::new().row(["k", "value"]).row(["key", "v"]).border(Border::None);
+    assert_eq!(plain.to_string(), "k    value\nkey  v    \n");
+    assert_eq!(Table::new().to_string(), "");
+}
+
+#[test]
+fn test_boxed() {
+    assert_eq!(boxed!("hi"), "┌────┐\n│ hi │\n└────┘");
+    assert_eq!(boxed!(Border::None, Style::new(); "a\nbcd"), " a   \n bcd ");
+    assert_eq!(
+        boxed!(Border::Ascii, Colours::Blue; "{}", bold!("x\ny")),
+        "\x1b[34m+---+\x1b[0m\n\
+         \x1b[34m|\x1b[0m \x1b[1mx\x1b[0m \x1b[34m|\x1b[0m\n\
+         \x1b[34m|\x1b[0m \x1b[1my\x1b[0m \x1b[34m|\x1b[0m\n\
+         \x1b[34m+---+\x1b[0m"
+    );
+    assert_eq!(boxed("", Border::Single, Style::new()), "┌──┐\n│  │\n└──┘");
+}
+
+#[cfg(feature = "tracing")]
+#[test]
+fn test_themed_format() {
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let buffer = Buffer(Arc::default());
+    let writer = buffer.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .event_format(crate::ThemedFormat::new())
+        .with_writer(move || writer.clone())
+        .finish();
+    tracing::subscriber::with_default(subscriber, || {
+        let span = tracing::info_span!("request", id = 7);
+        let _entered = span.enter();
+        tracing::warn!(status = 503, "upstream down");
+    });
+
+    let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+    assert_eq!(
+        strip_ansi(&output),
+        " WARN request{id=7}: term_ansi::test: upstream down status=503\n"
     );
+    assert!(output.starts_with(&theme().warning.prefix()));
 }
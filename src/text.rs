@@ -0,0 +1,340 @@
+//! Helpers for working with text that contains escape sequences.
+
+use std::borrow::Cow;
+use std::ops::{Bound, RangeBounds};
+
+use crate::codes;
+use crate::sgr::{sgr_params, SgrState};
+
+const ESC: char = '\x1b';
+const BEL: char = '\x07';
+
+/// Returns the byte length of the escape sequence at the start of `text`.
+///
+/// Handles CSI sequences (`ESC [ ... final`), OSC and other string sequences
+/// (`ESC ] ... BEL` or `ESC ] ... ESC \`) and two-byte escapes. An
+/// unterminated sequence runs to the end of `text`.
+pub(crate) fn escape_len(text: &str) -> usize {
+    let mut chars = text.char_indices().skip(1);
+    match chars.next() {
+        None => 1,
+        Some((_, '[')) => chars
+            .find(|&(_, c)| ('\x40'..='\x7e').contains(&c))
+            .map_or(text.len(), |(i, c)| i + c.len_utf8()),
+        Some((_, ']' | 'P' | 'X' | '^' | '_')) => {
+            let mut previous = '\0';
+            for (i, c) in chars {
+                if c == BEL {
+                    return i + 1;
+                }
+                if previous == ESC && c == '\\' {
+                    return i + 1;
+                }
+                previous = c;
+            }
+            text.len()
+        }
+        Some((i, c)) => i + c.len_utf8(),
+    }
+}
+
+/// Removes all escape sequences from `text`, borrowing it when there are none.
+///
+/// # Example
+///
+/// ```
+/// use std::borrow::Cow;
+/// use term_ansi::{red, strip_ansi_cow};
+///
+/// assert!(matches!(strip_ansi_cow("plain"), Cow::Borrowed("plain")));
+/// assert_eq!(strip_ansi_cow(&red!("Error")), "Error");
+/// ```
+pub fn strip_ansi_cow(text: &str) -> Cow<'_, str> {
+    let Some(start) = text.find(ESC) else {
+        return Cow::Borrowed(text);
+    };
+
+    let mut stripped = String::with_capacity(text.len());
+    stripped.push_str(&text[..start]);
+    let mut rest = &text[start..];
+    while let Some(start) = rest.find(ESC) {
+        stripped.push_str(&rest[..start]);
+        rest = &rest[start..];
+        rest = &rest[escape_len(rest)..];
+    }
+    stripped.push_str(rest);
+    Cow::Owned(stripped)
+}
+
+/// Removes all escape sequences from `text`: colors and styles (CSI) as well as
+/// hyperlinks and other OSC sequences, whichever crate produced them.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{bold, red, strip_ansi};
+///
+/// let message = red!("Error: {}", bold!("disk full"));
+/// assert_eq!(strip_ansi(&message), "Error: disk full");
+/// ```
+pub fn strip_ansi(text: &str) -> String {
+    strip_ansi_cow(text).into_owned()
+}
+
+/// Returns the number of terminal cells `text` occupies, ignoring escape sequences.
+///
+/// Without the `unicode-width` feature every `char` counts as one cell. With it,
+/// wide characters such as CJK and most emoji count as two and combining marks
+/// as zero.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{green, red, visible_width};
+///
+/// let cells = [red!("FAIL"), green!("ok")];
+/// let width = cells.iter().map(|cell| visible_width(cell)).max().unwrap_or(0);
+/// assert_eq!(width, 4);
+/// for cell in &cells {
+///     let padding = " ".repeat(width - visible_width(cell));
+///     println!("[{}{}]", cell, padding);
+/// }
+/// ```
+pub fn visible_width(text: &str) -> usize {
+    let plain = strip_ansi_cow(text);
+    #[cfg(feature = "unicode-width")]
+    {
+        unicode_width::UnicodeWidthStr::width(plain.as_ref())
+    }
+    #[cfg(not(feature = "unicode-width"))]
+    {
+        plain.chars().count()
+    }
+}
+
+/// Returns the number of terminal cells a single `char` occupies.
+pub(crate) fn char_width(c: char) -> usize {
+    #[cfg(feature = "unicode-width")]
+    {
+        unicode_width::UnicodeWidthChar::width(c).unwrap_or(0)
+    }
+    #[cfg(not(feature = "unicode-width"))]
+    {
+        let _ = c;
+        1
+    }
+}
+
+/// Extracts a range of visible columns from `text`, keeping its colors intact.
+///
+/// The colors and styles active at the start of the range are re-emitted
+/// first, and a reset is appended if any are still active at its end. Escape
+/// sequences other than colors and styles are only kept inside the range. A
+/// wide character cut by either boundary is replaced with spaces so that the
+/// slice still spans the requested columns.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{red, slice_ansi, strip_ansi};
+///
+/// let line = format!("log: {}", red!("disk full"));
+/// let scrolled = slice_ansi(&line, 5..9);
+/// assert_eq!(strip_ansi(&scrolled), "disk");
+/// assert_eq!(scrolled, "\x1b[31mdisk\x1b[0m");
+/// ```
+pub fn slice_ansi(text: &str, columns: impl RangeBounds<usize>) -> String {
+    let start = match columns.start_bound() {
+        Bound::Included(&n) => n,
+        Bound::Excluded(&n) => n.saturating_add(1),
+        Bound::Unbounded => 0,
+    };
+    let end = match columns.end_bound() {
+        Bound::Included(&n) => n.saturating_add(1),
+        Bound::Excluded(&n) => n,
+        Bound::Unbounded => usize::MAX,
+    };
+
+    let mut state = SgrState::default();
+    let mut sliced = String::new();
+    let mut started = false;
+    let mut column = 0;
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if column >= end {
+            break;
+        }
+        if c == ESC {
+            let (sequence, tail) = rest.split_at(escape_len(rest));
+            state.apply_code(sequence);
+            if started {
+                sliced.push_str(sequence);
+            }
+            rest = tail;
+            continue;
+        }
+
+        let width = char_width(c);
+        let next = column + width;
+        if next > start || (width == 0 && column >= start) {
+            if !started {
+                started = true;
+                sliced.push_str(&state.to_code());
+            }
+            if column < start || next > end {
+                let visible = next.min(end) - column.max(start);
+                sliced.push_str(&" ".repeat(visible));
+            } else {
+                sliced.push(c);
+            }
+        }
+        column = next;
+        rest = &rest[c.len_utf8()..];
+    }
+
+    if started && !state.is_empty() {
+        sliced.push_str(codes::RESET);
+    }
+    optimize_ansi(&sliced)
+}
+
+/// Closes the active colors and styles before every line break in `text` and
+/// re-opens them after it.
+///
+/// Pagers such as `less -R` and many CI log viewers handle each line on its
+/// own and lose styles that span a newline. After this pass, every line
+/// carries its own escape codes. See also [`linewise!`](crate::linewise).
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{linewise, red};
+///
+/// assert_eq!(
+///     linewise(&red!("one\ntwo")),
+///     "\x1b[31mone\x1b[0m\n\x1b[31mtwo\x1b[0;37m"
+/// );
+/// ```
+pub fn linewise(text: &str) -> String {
+    let mut state = SgrState::default();
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if c == ESC {
+            let (sequence, tail) = rest.split_at(escape_len(rest));
+            state.apply_code(sequence);
+            output.push_str(sequence);
+            rest = tail;
+            continue;
+        }
+
+        let line_break = if rest.starts_with("\r\n") {
+            "\r\n"
+        } else if c == '\n' {
+            "\n"
+        } else {
+            ""
+        };
+        if line_break.is_empty() || state.is_empty() {
+            output.push(c);
+            rest = &rest[c.len_utf8()..];
+        } else {
+            output.push_str(codes::RESET);
+            output.push_str(line_break);
+            output.push_str(&state.to_code());
+            rest = &rest[line_break.len()..];
+        }
+    }
+    optimize_ansi(&output)
+}
+
+/// Rewrites `text` with the fewest color and style sequences that render the same.
+///
+/// Back-to-back sequences are merged into one, everything before a reset in
+/// such a run is dropped, and runs that leave the style unchanged are removed.
+/// Nested macro output, which re-emits the enclosing color after every reset,
+/// typically shrinks noticeably. Other escape sequences are kept as they are.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::optimize_ansi;
+///
+/// assert_eq!(optimize_ansi("\x1b[0m\x1b[32m\x1b[0m\x1b[37m\x1b[0mok"), "\x1b[0mok");
+/// assert_eq!(optimize_ansi("\x1b[0;1m\x1b[31mA\x1b[0m\x1b[1;31mB"), "\x1b[0;1;31mAB");
+/// ```
+pub fn optimize_ansi(text: &str) -> String {
+    let mut optimizer = Optimizer {
+        output: String::with_capacity(text.len()),
+        state: None,
+        run: Vec::new(),
+    };
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if c == ESC {
+            let (sequence, tail) = rest.split_at(escape_len(rest));
+            match sgr_params(sequence).next() {
+                Some(params) if sequence.ends_with('m') => optimizer.run.push(params),
+                _ => {
+                    optimizer.flush();
+                    optimizer.output.push_str(sequence);
+                }
+            }
+            rest = tail;
+        } else {
+            optimizer.flush();
+            let end = rest.find(ESC).unwrap_or(rest.len());
+            optimizer.output.push_str(&rest[..end]);
+            rest = &rest[end..];
+        }
+    }
+    optimizer.flush();
+    optimizer.output
+}
+
+struct Optimizer<'a> {
+    output: String,
+    /// The style in effect, known once a reset has been seen.
+    state: Option<SgrState>,
+    /// The parameters of consecutive SGR sequences not yet written.
+    run: Vec<&'a str>,
+}
+
+impl Optimizer<'_> {
+    /// Writes the pending run of SGR sequences as a single sequence, if it has any effect.
+    fn flush(&mut self) {
+        if self.run.is_empty() {
+            return;
+        }
+        let mut target = self.state.clone().unwrap_or_default();
+        let mut reset_at = None;
+        for (i, params) in self.run.iter().enumerate() {
+            if target.apply_params(params) {
+                reset_at = Some(i);
+            }
+        }
+
+        let merged = match reset_at {
+            Some(i) => self.run[i..].join(";"),
+            None => self.run.join(";"),
+        };
+        let merged = merged.trim_end_matches(';');
+        let known = self.state.is_some() || reset_at.is_some();
+        if self.state.as_ref() != Some(&target) {
+            let from_reset = format!("0;{}", target.to_params());
+            let from_reset = from_reset.trim_end_matches(';');
+            let shortest = if known && from_reset.len() < merged.len() {
+                from_reset
+            } else {
+                merged
+            };
+            self.output.push_str("\x1b[");
+            self.output.push_str(shortest);
+            self.output.push('m');
+        }
+        if known {
+            self.state = Some(target);
+        }
+        self.run.clear();
+    }
+}
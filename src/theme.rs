@@ -0,0 +1,347 @@
+//! Semantic styles, looked up by role at runtime.
+
+use std::ffi::OsString;
+use std::sync::RwLock;
+
+use crate::{terminal_is_dark, Color, Colours, Style};
+
+static GLOBAL_THEME: RwLock<Option<Theme>> = RwLock::new(None);
+
+/// A mapping from semantic roles, such as "error" or "muted", to styles.
+///
+/// Code that styles output by role rather than by color, like the message
+/// macros [`success!`](crate::success) and [`failure!`](crate::failure),
+/// reads the global theme at runtime. Install one with [`set_global_theme`]
+/// to restyle all of that output at once. Roles can be read and changed by
+/// field or by name; [`Theme::ROLES`] lists the names.
+///
+/// The default is [`Theme::dark`]; [`Theme::detect`] picks between it and
+/// [`Theme::light`] based on the terminal's background.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{Colours, Style, Theme};
+///
+/// let theme = Theme {
+///     success: Style::new().fg(Colours::Blue),
+///     ..Theme::default()
+/// };
+/// println!("{}", theme.success.apply("saved"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Theme {
+    /// The style of [`success!`](crate::success) messages.
+    pub success: Style,
+    /// The style of [`failure!`](crate::failure) messages.
+    pub error: Style,
+    /// The style of [`warn_msg!`](crate::warn_msg) messages.
+    pub warning: Style,
+    /// The style of [`info_msg!`](crate::info_msg) messages.
+    pub info: Style,
+    /// The style of headings, prompts and other text that should stand out.
+    pub accent: Style,
+    /// The style of secondary details such as timestamps and paths.
+    pub muted: Style,
+    /// The style of selected or matched text.
+    pub highlight: Style,
+}
+
+impl Theme {
+    /// The names of all roles, in field order.
+    pub const ROLES: [&'static str; 7] = [
+        "success",
+        "error",
+        "warning",
+        "info",
+        "accent",
+        "muted",
+        "highlight",
+    ];
+
+    /// Returns the style of the role called `role`, or `None` for an unknown name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use term_ansi::Theme;
+    ///
+    /// let theme = Theme::default();
+    /// assert_eq!(theme.get("error"), Some(theme.error));
+    /// assert_eq!(theme.get("fancy"), None);
+    /// ```
+    pub fn get(&self, role: &str) -> Option<Style> {
+        match role {
+            "success" => Some(self.success),
+            "error" => Some(self.error),
+            "warning" => Some(self.warning),
+            "info" => Some(self.info),
+            "accent" => Some(self.accent),
+            "muted" => Some(self.muted),
+            "highlight" => Some(self.highlight),
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the style of the role called `role`, or
+    /// `None` for an unknown name.
+    pub fn get_mut(&mut self, role: &str) -> Option<&mut Style> {
+        match role {
+            "success" => Some(&mut self.success),
+            "error" => Some(&mut self.error),
+            "warning" => Some(&mut self.warning),
+            "info" => Some(&mut self.info),
+            "accent" => Some(&mut self.accent),
+            "muted" => Some(&mut self.muted),
+            "highlight" => Some(&mut self.highlight),
+            _ => None,
+        }
+    }
+
+    /// Returns the theme with the overrides set in the environment applied.
+    ///
+    /// `TERM_ANSI_THEME` is read first. It holds overrides shared by every
+    /// program using this crate, as colon-separated `role=spec` entries in
+    /// the manner of `LS_COLORS`, e.g. `error=bold #ff5555:muted=bright_black`.
+    /// Then a `<APP>_COLOR_<ROLE>` variable, such as `MYAPP_COLOR_ERROR`,
+    /// overrides a single role for the program named `app`. Specs use the
+    /// syntax of [`Style::parse`]; unknown roles and invalid specs are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use term_ansi::{set_global_theme, theme, Color, Theme};
+    ///
+    /// std::env::set_var("MYAPP_COLOR_ERROR", "#ff5555");
+    /// set_global_theme(Theme::default().with_env("myapp"));
+    /// assert_eq!(theme().error.foreground(), Some(Color::Rgb(255, 85, 85)));
+    /// ```
+    pub fn with_env(self, app: &str) -> Theme {
+        self.env_overrides(app, |name| std::env::var_os(name))
+    }
+
+    /// Applies the overrides from environment variables looked up with `var`.
+    pub(crate) fn env_overrides(
+        mut self,
+        app: &str,
+        var: impl Fn(&str) -> Option<OsString>,
+    ) -> Theme {
+        let mut apply = |role: &str, spec: &str| {
+            if let (Some(slot), Some(style)) = (self.get_mut(role.trim()), Style::parse(spec)) {
+                *slot = style;
+            }
+        };
+
+        if let Some(entries) = var("TERM_ANSI_THEME") {
+            for entry in entries.to_string_lossy().split(':') {
+                if let Some((role, spec)) = entry.split_once('=') {
+                    apply(role, spec);
+                }
+            }
+        }
+        for role in Theme::ROLES {
+            let name = format!("{}_COLOR_{}", app, role).to_ascii_uppercase();
+            if let Some(spec) = var(&name) {
+                apply(role, &spec.to_string_lossy());
+            }
+        }
+        self
+    }
+
+    /// Applies the style of the role called `role` to `text`, like
+    /// [`Style::apply`]. Text for an unknown role is returned unstyled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use term_ansi::theme;
+    ///
+    /// println!("{} {}", theme().apply("accent", "Deploying"), theme().apply("muted", "(2 of 5)"));
+    /// ```
+    pub fn apply(&self, role: &str, text: &str) -> String {
+        self.get(role).unwrap_or_default().apply(text)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::dark()
+    }
+}
+
+impl Theme {
+    /// Returns the theme for light text on a dark background.
+    ///
+    /// It uses green for success, bold red for errors, yellow for warnings,
+    /// cyan for information, bold magenta for accents, dim text for muted
+    /// output and reverse video for highlights.
+    pub fn dark() -> Theme {
+        Theme {
+            success: Style::new().fg(Colours::Green),
+            error: Style::new().fg(Colours::Red).bold(),
+            warning: Style::new().fg(Colours::Yellow),
+            info: Style::new().fg(Colours::Cyan),
+            accent: Style::new().fg(Colours::Magenta).bold(),
+            muted: Style::new().dim(),
+            highlight: Style::new().reverse(),
+        }
+    }
+
+    /// Returns the theme for dark text on a light background.
+    ///
+    /// Warnings use a dark orange and information blue, since yellow and cyan
+    /// are hard to read on white; the other roles match [`Theme::dark`].
+    pub fn light() -> Theme {
+        Theme {
+            warning: Style::new().fg(Color::Ansi256(130)),
+            info: Style::new().fg(Colours::Blue),
+            ..Theme::dark()
+        }
+    }
+
+    /// Returns [`Theme::dark`] or [`Theme::light`] to match the terminal's
+    /// background, as reported by [`terminal_is_dark`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use term_ansi::{set_global_theme, Theme};
+    ///
+    /// set_global_theme(Theme::detect().with_env("myapp"));
+    /// ```
+    pub fn detect() -> Theme {
+        if terminal_is_dark() {
+            Theme::dark()
+        } else {
+            Theme::light()
+        }
+    }
+}
+
+/// Replaces the global theme, for every thread.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{set_global_theme, success, Colours, Style, Theme};
+///
+/// set_global_theme(Theme {
+///     success: Style::new().fg(Colours::Blue).bold(),
+///     ..Theme::default()
+/// });
+/// assert_eq!(success!("done"), "\x1b[1;34mdone\x1b[0m\x1b[37m");
+/// ```
+pub fn set_global_theme(theme: Theme) {
+    *GLOBAL_THEME.write().unwrap_or_else(|e| e.into_inner()) = Some(theme);
+}
+
+/// Returns the global theme: the one last passed to [`set_global_theme`], or
+/// [`Theme::default`].
+pub fn theme() -> Theme {
+    GLOBAL_THEME
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .unwrap_or_default()
+}
+
+/// The styles of the five log levels, for logging frontends that color the
+/// level of each record.
+///
+/// The default uses bold red for errors, yellow for warnings, green for
+/// information, blue for debug output and dim text for traces.
+/// [`LevelStyles::from_theme`] takes them from the roles of a [`Theme`]
+/// instead, so log output follows the rest of the application. Levels can be
+/// read and changed by field or by name, as written by `log` and `tracing`.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{theme, Colours, LevelStyles, Style};
+///
+/// let mut levels = LevelStyles::from_theme(&theme());
+/// levels.debug = Style::new().fg(Colours::Blue);
+/// println!("{} connected", levels.apply("INFO", "INFO"));
+/// assert_eq!(levels.get("Debug"), Some(levels.debug));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LevelStyles {
+    /// The style of `ERROR` records.
+    pub error: Style,
+    /// The style of `WARN` records.
+    pub warn: Style,
+    /// The style of `INFO` records.
+    pub info: Style,
+    /// The style of `DEBUG` records.
+    pub debug: Style,
+    /// The style of `TRACE` records.
+    pub trace: Style,
+}
+
+impl LevelStyles {
+    /// The names of all levels, from the most to the least severe.
+    pub const LEVELS: [&'static str; 5] = ["error", "warn", "info", "debug", "trace"];
+
+    /// Returns the level styles of `theme`: its `error`, `warning` and `info`
+    /// roles, `accent` for debug output and `muted` for traces.
+    pub fn from_theme(theme: &Theme) -> LevelStyles {
+        LevelStyles {
+            error: theme.error,
+            warn: theme.warning,
+            info: theme.info,
+            debug: theme.accent,
+            trace: theme.muted,
+        }
+    }
+
+    /// Returns the style of the level called `level`, in any case, or `None`
+    /// for an unknown name. `warning` is accepted for `warn`.
+    pub fn get(&self, level: &str) -> Option<Style> {
+        match Self::index(level)? {
+            0 => Some(self.error),
+            1 => Some(self.warn),
+            2 => Some(self.info),
+            3 => Some(self.debug),
+            _ => Some(self.trace),
+        }
+    }
+
+    /// Returns a mutable reference to the style of the level called `level`,
+    /// in any case, or `None` for an unknown name.
+    pub fn get_mut(&mut self, level: &str) -> Option<&mut Style> {
+        match Self::index(level)? {
+            0 => Some(&mut self.error),
+            1 => Some(&mut self.warn),
+            2 => Some(&mut self.info),
+            3 => Some(&mut self.debug),
+            _ => Some(&mut self.trace),
+        }
+    }
+
+    /// Applies the style of the level called `level` to `text`, like
+    /// [`Style::apply`]. Text for an unknown level is returned unstyled.
+    pub fn apply(&self, level: &str, text: &str) -> String {
+        self.get(level).unwrap_or_default().apply(text)
+    }
+
+    /// Returns the position of `level` in [`LevelStyles::LEVELS`].
+    fn index(level: &str) -> Option<usize> {
+        if level.eq_ignore_ascii_case("warning") {
+            return Some(1);
+        }
+        Self::LEVELS
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(level))
+    }
+}
+
+impl Default for LevelStyles {
+    fn default() -> Self {
+        LevelStyles {
+            error: Style::new().fg(Colours::Red).bold(),
+            warn: Style::new().fg(Colours::Yellow),
+            info: Style::new().fg(Colours::Green),
+            debug: Style::new().fg(Colours::Blue),
+            trace: Style::new().dim(),
+        }
+    }
+}
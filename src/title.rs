@@ -0,0 +1,101 @@
+//! Setting the terminal window and tab title, and putting the old one back.
+
+use std::io;
+
+/// Returns the sequence that sets the window and tab title to `title` (OSC 0).
+///
+/// Control characters are removed from `title`, as they would end the
+/// sequence early. Like the [`cursor`](crate::cursor) functions this does not
+/// depend on [`colors_enabled`](crate::colors_enabled); write it only to a
+/// terminal. There is no reliable way to read the current title, so use
+/// [`SavedTitle`] to put it back afterwards. See [`set_title!`](crate::set_title!)
+/// for the macro form.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::set_title;
+///
+/// assert_eq!(set_title("myapp"), "\x1b]0;myapp\x1b\\");
+/// ```
+pub fn set_title(title: &str) -> String {
+    let title: String = title.chars().filter(|c| !c.is_control()).collect();
+    format!("\x1b]0;{}\x1b\\", title)
+}
+
+/// Returns the sequence that pushes the current title onto the terminal's
+/// title stack (XTWINOPS 22), to be restored by [`pop_title`].
+///
+/// Supported by xterm, VTE-based terminals, kitty, WezTerm and others;
+/// terminals without a title stack ignore it.
+pub fn push_title() -> &'static str {
+    "\x1b[22;0t"
+}
+
+/// Returns the sequence that restores the title last saved by [`push_title`]
+/// (XTWINOPS 23).
+pub fn pop_title() -> &'static str {
+    "\x1b[23;0t"
+}
+
+/// A guard that puts the terminal title back when dropped.
+///
+/// Creating it pushes the current title with [`push_title`]; titles can then
+/// be set as a long-running program moves between phases, and dropping the
+/// guard restores the original, including while unwinding from a panic.
+///
+/// # Example
+///
+/// ```no_run
+/// use term_ansi::SavedTitle;
+///
+/// let mut title = SavedTitle::save()?;
+/// title.set("myapp – building…")?;
+/// // ... build ...
+/// title.set("myapp – testing…")?;
+/// // ... test ...
+/// drop(title);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct SavedTitle<W: io::Write = io::Stdout> {
+    writer: W,
+}
+
+impl SavedTitle {
+    /// Saves the title of the terminal behind standard output.
+    pub fn save() -> io::Result<Self> {
+        SavedTitle::save_on(io::stdout())
+    }
+}
+
+impl<W: io::Write> SavedTitle<W> {
+    /// Saves the title of the terminal behind `writer`.
+    pub fn save_on(mut writer: W) -> io::Result<Self> {
+        writer.write_all(push_title().as_bytes())?;
+        Ok(SavedTitle { writer })
+    }
+
+    /// Sets the title until the next call or the guard is dropped.
+    pub fn set(&mut self, title: &str) -> io::Result<()> {
+        self.writer.write_all(set_title(title).as_bytes())?;
+        self.writer.flush()
+    }
+
+    /// Returns a reference to the wrapped writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Returns a mutable reference to the wrapped writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+}
+
+impl<W: io::Write> Drop for SavedTitle<W> {
+    fn drop(&mut self) {
+        let _ = self.writer.write_all(pop_title().as_bytes());
+        let _ = self.writer.flush();
+    }
+}
@@ -0,0 +1,165 @@
+//! An event formatter for `tracing-subscriber`, colored by the global theme.
+
+use std::fmt;
+
+use tracing_core::field::{Field, Visit};
+use tracing_core::{Event, Subscriber};
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields, FormattedFields};
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::{styled, theme, LevelStyles, Style};
+
+/// A [`FormatEvent`] implementation that colors log lines with the global [`Theme`](crate::Theme).
+///
+/// Each event is written on one line as the level, the spans it is in, its
+/// target and its fields, like the default `tracing-subscriber` format
+/// without a timestamp:
+///
+/// ```text
+/// ERROR request{id=7}:db: my_app::store: query failed table="users"
+/// ```
+///
+/// Levels take the styles of [`LevelStyles::from_theme`] unless others are
+/// set with [`ThemedFormat::with_levels`]. Span names are `accent`, the target
+/// and field names `muted`. The theme is read for every event, so a later
+/// [`set_global_theme`](crate::set_global_theme) restyles the output at once.
+///
+/// Escape codes are written while [`colors_enabled`](crate::colors_enabled)
+/// is true, regardless of the layer's `with_ansi` setting. To keep a log
+/// file plain, write it through an [`AnsiWriter`](crate::AnsiWriter) at
+/// [`ColorSupport::None`](crate::ColorSupport::None).
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::ThemedFormat;
+///
+/// tracing_subscriber::fmt()
+///     .event_format(ThemedFormat::new())
+///     .init();
+///
+/// tracing::info!(user = "ada", "logged in");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ThemedFormat {
+    target: bool,
+    levels: Option<LevelStyles>,
+}
+
+impl ThemedFormat {
+    /// Creates a formatter that writes the event's target.
+    pub fn new() -> Self {
+        ThemedFormat {
+            target: true,
+            levels: None,
+        }
+    }
+
+    /// Sets whether the event's target, usually its module path, is written.
+    pub fn with_target(self, target: bool) -> Self {
+        ThemedFormat { target, ..self }
+    }
+
+    /// Colors levels with `levels` rather than with the global theme.
+    pub fn with_levels(self, levels: LevelStyles) -> Self {
+        ThemedFormat {
+            levels: Some(levels),
+            ..self
+        }
+    }
+}
+
+impl Default for ThemedFormat {
+    fn default() -> Self {
+        ThemedFormat::new()
+    }
+}
+
+impl<S, N> FormatEvent<S, N> for ThemedFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let theme = theme();
+        let metadata = event.metadata();
+        let level = metadata.level().as_str();
+        let levels = self
+            .levels
+            .unwrap_or_else(|| LevelStyles::from_theme(&theme));
+        write!(
+            writer,
+            "{:>5} ",
+            styled(levels.get(level).unwrap_or_default(), level)
+        )?;
+
+        if let Some(scope) = ctx.event_scope() {
+            let mut first = true;
+            for span in scope.from_root() {
+                if !first {
+                    writer.write_char(':')?;
+                }
+                first = false;
+                write!(writer, "{}", styled(theme.accent, span.name()))?;
+                let extensions = span.extensions();
+                if let Some(fields) = extensions.get::<FormattedFields<N>>() {
+                    if !fields.is_empty() {
+                        write!(writer, "{{{}}}", fields)?;
+                    }
+                }
+            }
+            if !first {
+                writer.write_str(": ")?;
+            }
+        }
+
+        if self.target {
+            write!(writer, "{}: ", styled(theme.muted, metadata.target()))?;
+        }
+
+        let mut visitor = FieldVisitor {
+            writer: &mut writer,
+            name_style: theme.muted,
+            first: true,
+            result: Ok(()),
+        };
+        event.record(&mut visitor);
+        visitor.result?;
+        writeln!(writer)
+    }
+}
+
+/// Writes an event's fields: the message as is, the rest as `name=value`.
+struct FieldVisitor<'a, 'w> {
+    writer: &'a mut Writer<'w>,
+    name_style: Style,
+    first: bool,
+    result: fmt::Result,
+}
+
+impl Visit for FieldVisitor<'_, '_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if self.result.is_err() {
+            return;
+        }
+        let separator = if self.first { "" } else { " " };
+        self.first = false;
+        self.result = if field.name() == "message" {
+            write!(self.writer, "{}{:?}", separator, value)
+        } else {
+            write!(
+                self.writer,
+                "{}{}={:?}",
+                separator,
+                styled(self.name_style, field.name()),
+                value
+            )
+        };
+    }
+}
@@ -0,0 +1,233 @@
+//! Color types for selecting colors at runtime instead of by macro name.
+
+use std::borrow::Cow;
+use std::fmt;
+
+use crate::quantize::downgrade;
+use crate::{codes, color_support};
+
+/// The eight standard ANSI colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Colours {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+impl Colours {
+    /// All standard colors, in ANSI order.
+    pub const ALL: [Colours; 8] = [
+        Colours::Black,
+        Colours::Red,
+        Colours::Green,
+        Colours::Yellow,
+        Colours::Blue,
+        Colours::Magenta,
+        Colours::Cyan,
+        Colours::White,
+    ];
+
+    /// Returns the color with the given lowercase name, such as `"red"`.
+    fn from_name(name: &str) -> Option<Colours> {
+        match name {
+            "black" => Some(Colours::Black),
+            "red" => Some(Colours::Red),
+            "green" => Some(Colours::Green),
+            "yellow" => Some(Colours::Yellow),
+            "blue" => Some(Colours::Blue),
+            "magenta" => Some(Colours::Magenta),
+            "cyan" => Some(Colours::Cyan),
+            "white" => Some(Colours::White),
+            _ => None,
+        }
+    }
+
+    /// Returns the ANSI escape code for this color as a foreground color.
+    pub fn fg_code(self) -> &'static str {
+        match self {
+            Colours::Black => codes::BLACK,
+            Colours::Red => codes::RED,
+            Colours::Green => codes::GREEN,
+            Colours::Yellow => codes::YELLOW,
+            Colours::Blue => codes::BLUE,
+            Colours::Magenta => codes::MAGENTA,
+            Colours::Cyan => codes::CYAN,
+            Colours::White => codes::WHITE,
+        }
+    }
+
+    /// Returns the ANSI escape code for this color as a background color.
+    pub fn bg_code(self) -> &'static str {
+        match self {
+            Colours::Black => codes::BG_BLACK,
+            Colours::Red => codes::BG_RED,
+            Colours::Green => codes::BG_GREEN,
+            Colours::Yellow => codes::BG_YELLOW,
+            Colours::Blue => codes::BG_BLUE,
+            Colours::Magenta => codes::BG_MAGENTA,
+            Colours::Cyan => codes::BG_CYAN,
+            Colours::White => codes::BG_WHITE,
+        }
+    }
+}
+
+/// A terminal color in any of the supported color spaces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    /// One of the eight standard colors (SGR 30-37 / 40-47).
+    Basic(Colours),
+    /// The high-intensity variant of a standard color (SGR 90-97 / 100-107).
+    BrightBasic(Colours),
+    /// An index into the 256-color palette.
+    Ansi256(u8),
+    /// A 24-bit truecolor value.
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    /// Writes the SGR parameters for this color without allocating.
+    pub(crate) fn write_params(&self, f: &mut dyn fmt::Write, background: bool) -> fmt::Result {
+        let (basic, bright, extended): (u8, u8, u8) = if background {
+            (40, 100, 48)
+        } else {
+            (30, 90, 38)
+        };
+        match *self {
+            Color::Basic(c) => write!(f, "{}", basic + c as u8),
+            Color::BrightBasic(c) => write!(f, "{}", bright + c as u8),
+            Color::Ansi256(idx) => write!(f, "{};5;{}", extended, idx),
+            Color::Rgb(r, g, b) => write!(f, "{};2;{};{};{}", extended, r, g, b),
+        }
+    }
+
+    /// Returns the SGR parameters that set this color as the foreground, e.g. `"38;5;208"`.
+    pub fn fg_params(&self) -> String {
+        let mut params: String = String::new();
+        let _ = self.write_params(&mut params, false);
+        params
+    }
+
+    /// Returns the SGR parameters that set this color as the background, e.g. `"48;5;208"`.
+    pub fn bg_params(&self) -> String {
+        let mut params: String = String::new();
+        let _ = self.write_params(&mut params, true);
+        params
+    }
+
+    /// Parses a color from its name, as used in style specs and themes.
+    ///
+    /// Accepts the eight standard color names (`"red"`), their bright variants
+    /// (`"bright_red"` or `"bright-red"`), a 256-color index (`"208"`), a hex
+    /// string (`"#ff8800"` or `"#f80"`) and CSS named colors (`"tomato"`).
+    /// Matching ignores ASCII case. Returns `None` for anything else.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use term_ansi::{Color, Colours};
+    ///
+    /// assert_eq!(Color::parse("bright_red"), Some(Color::BrightBasic(Colours::Red)));
+    /// assert_eq!(Color::parse("#ff8800"), Some(Color::Rgb(255, 136, 0)));
+    /// assert_eq!(Color::parse("208"), Some(Color::Ansi256(208)));
+    /// assert_eq!(Color::parse("steelblue"), Some(Color::Rgb(70, 130, 180)));
+    /// assert_eq!(Color::parse("plaid"), None);
+    /// ```
+    pub fn parse(name: &str) -> Option<Color> {
+        let name: String = name.trim().to_ascii_lowercase();
+        if let Some(hex) = name.strip_prefix('#') {
+            crate::hex_to_rgb(hex).map(Color::from)
+        } else if !name.is_empty() && name.bytes().all(|b| b.is_ascii_digit()) {
+            name.parse().ok().map(Color::Ansi256)
+        } else if let Some(colour) = Colours::from_name(&name) {
+            Some(Color::Basic(colour))
+        } else if let Some(colour) = name
+            .strip_prefix("bright_")
+            .or_else(|| name.strip_prefix("bright-"))
+            .and_then(Colours::from_name)
+        {
+            Some(Color::BrightBasic(colour))
+        } else {
+            crate::named_to_rgb(&name).map(Color::from)
+        }
+    }
+
+    /// Returns the ANSI escape code that sets this color as the foreground.
+    ///
+    /// RGB and 256-color values are quantized to the [`color_support`](crate::color_support)
+    /// level, like [`rgb_code`](crate::rgb_code) does.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use term_ansi::{set_color_support, Color, ColorSupport};
+    ///
+    /// set_color_support(Some(ColorSupport::Ansi256));
+    /// assert_eq!(Color::Rgb(255, 135, 0).fg_code(), "\x1b[38;5;208m");
+    /// set_color_support(Some(ColorSupport::TrueColor));
+    /// assert_eq!(Color::Rgb(255, 135, 0).fg_code(), "\x1b[38;2;255;135;0m");
+    /// ```
+    pub fn fg_code(&self) -> String {
+        format!("\x1b[{}m", downgrade(*self, color_support()).fg_params())
+    }
+
+    /// Returns the ANSI escape code that sets this color as the background,
+    /// quantized like [`Color::fg_code`].
+    pub fn bg_code(&self) -> String {
+        format!("\x1b[{}m", downgrade(*self, color_support()).bg_params())
+    }
+}
+
+impl From<Colours> for Color {
+    fn from(colour: Colours) -> Self {
+        Color::Basic(colour)
+    }
+}
+
+impl From<(u8, u8, u8)> for Color {
+    fn from((r, g, b): (u8, u8, u8)) -> Self {
+        Color::Rgb(r, g, b)
+    }
+}
+
+/// Anything that can be turned into an escape code by [`apply_color!`](crate::apply_color).
+///
+/// Implemented for raw escape strings and for [`Color`], which is applied as a foreground color.
+pub trait ColorCode {
+    /// Returns the escape code to emit.
+    fn color_code(&self) -> Cow<'_, str>;
+}
+
+impl ColorCode for str {
+    fn color_code(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl ColorCode for String {
+    fn color_code(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl ColorCode for Color {
+    fn color_code(&self) -> Cow<'_, str> {
+        Cow::Owned(self.fg_code())
+    }
+}
+
+impl ColorCode for Colours {
+    fn color_code(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self.fg_code())
+    }
+}
+
+impl<T: ColorCode + ?Sized> ColorCode for &T {
+    fn color_code(&self) -> Cow<'_, str> {
+        (**self).color_code()
+    }
+}
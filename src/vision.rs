@@ -0,0 +1,130 @@
+//! Color vision deficiency simulation and color-blind-safe palettes.
+
+use crate::{linear_to_srgb, srgb_to_linear};
+
+/// The Okabe-Ito palette: black, orange, sky blue, bluish green, yellow,
+/// blue, vermillion and reddish purple.
+///
+/// All eight stay distinguishable under every common color vision
+/// deficiency. Skip the first entry on dark backgrounds.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{rgb, OKABE_ITO};
+///
+/// let series = ["cpu", "memory", "disk", "network"];
+/// for (name, &(r, g, b)) in series.iter().zip(OKABE_ITO[1..].iter().cycle()) {
+///     println!("{}", rgb!(r, g, b, "■ {}", name));
+/// }
+/// ```
+pub const OKABE_ITO: [(u8, u8, u8); 8] = [
+    (0x00, 0x00, 0x00),
+    (0xe6, 0x9f, 0x00),
+    (0x56, 0xb4, 0xe9),
+    (0x00, 0x9e, 0x73),
+    (0xf0, 0xe4, 0x42),
+    (0x00, 0x72, 0xb2),
+    (0xd5, 0x5e, 0x00),
+    (0xcc, 0x79, 0xa7),
+];
+
+/// Paul Tol's bright qualitative palette: blue, cyan, green, yellow, red,
+/// purple and grey.
+pub const TOL_BRIGHT: [(u8, u8, u8); 7] = [
+    (0x44, 0x77, 0xaa),
+    (0x66, 0xcc, 0xee),
+    (0x22, 0x88, 0x33),
+    (0xcc, 0xbb, 0x44),
+    (0xee, 0x66, 0x77),
+    (0xaa, 0x33, 0x77),
+    (0xbb, 0xbb, 0xbb),
+];
+
+/// Paul Tol's vibrant qualitative palette: orange, blue, cyan, magenta, red,
+/// teal and grey.
+pub const TOL_VIBRANT: [(u8, u8, u8); 7] = [
+    (0xee, 0x77, 0x33),
+    (0x00, 0x77, 0xbb),
+    (0x33, 0xbb, 0xee),
+    (0xee, 0x33, 0x77),
+    (0xcc, 0x33, 0x11),
+    (0x00, 0x99, 0x88),
+    (0xbb, 0xbb, 0xbb),
+];
+
+/// Paul Tol's muted qualitative palette: indigo, cyan, teal, green, olive,
+/// sand, rose, wine and purple.
+///
+/// With nine colors it suits charts with more series than the other palettes.
+pub const TOL_MUTED: [(u8, u8, u8); 9] = [
+    (0x33, 0x22, 0x88),
+    (0x88, 0xcc, 0xee),
+    (0x44, 0xaa, 0x99),
+    (0x11, 0x77, 0x33),
+    (0x99, 0x99, 0x33),
+    (0xdd, 0xcc, 0x77),
+    (0xcc, 0x66, 0x77),
+    (0x88, 0x22, 0x55),
+    (0xaa, 0x44, 0x99),
+];
+
+/// A type of color vision deficiency, for use with [`simulate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Deficiency {
+    /// No working red (long-wavelength) cones; reds look dark and close to greens.
+    Protanopia,
+    /// No working green (medium-wavelength) cones, the most common form.
+    Deuteranopia,
+    /// No working blue (short-wavelength) cones; blues and greens are confused.
+    Tritanopia,
+}
+
+impl Deficiency {
+    /// Returns the linear-light RGB transform from Machado, Oliveira and
+    /// Fernandes (2009) at full severity.
+    fn matrix(self) -> [[f64; 3]; 3] {
+        match self {
+            Deficiency::Protanopia => [
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998],
+            ],
+            Deficiency::Deuteranopia => [
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ],
+            Deficiency::Tritanopia => [
+                [1.255528, -0.076749, -0.178779],
+                [-0.078411, 0.930809, 0.147602],
+                [0.004733, 0.691367, 0.303900],
+            ],
+        }
+    }
+}
+
+/// Returns how `rgb` appears to someone with the given color vision deficiency.
+///
+/// Use it to preview a theme: if two severity colors come out nearly the same,
+/// they need to differ in lightness or be paired with a symbol or style.
+///
+/// # Example
+///
+/// ```
+/// use term_ansi::{delta_e, simulate, Deficiency};
+///
+/// let (error, success) = ((220, 50, 50), (60, 160, 60));
+/// let seen = |color| simulate(color, Deficiency::Deuteranopia);
+/// println!("difference: {:.1}", delta_e(seen(error), seen(success)));
+///
+/// assert_eq!(simulate((128, 128, 128), Deficiency::Protanopia), (128, 128, 128));
+/// ```
+pub fn simulate(rgb: (u8, u8, u8), deficiency: Deficiency) -> (u8, u8, u8) {
+    let linear = [rgb.0, rgb.1, rgb.2].map(|c| srgb_to_linear(c as f64 / 255.0));
+    let [r, g, b] = deficiency.matrix().map(|row| {
+        let c: f64 = row[0] * linear[0] + row[1] * linear[1] + row[2] * linear[2];
+        (linear_to_srgb(c.clamp(0.0, 1.0)) * 255.0).round() as u8
+    });
+    (r, g, b)
+}
@@ -0,0 +1,268 @@
+//! Writers that adapt escape sequences to their destination as bytes pass through.
+
+use std::borrow::Cow;
+use std::io;
+use std::mem;
+
+use crate::quantize::downgrade;
+use crate::sgr::sgr_tokens;
+use crate::{Color, ColorSupport};
+
+const ESC: u8 = 0x1b;
+const BEL: u8 = 0x07;
+
+/// The longest unterminated escape sequence held back waiting for its end.
+const MAX_PENDING: usize = 4096;
+
+/// An [`io::Write`] adapter that rewrites escape sequences for a given color depth.
+///
+/// Colors beyond what the [`ColorSupport`] level allows are mapped to the
+/// nearest available palette entry, like [`AnsiString::render`](crate::AnsiString::render)
+/// does, and [`ColorSupport::None`] removes every escape sequence, leaving
+/// plain text. At [`ColorSupport::TrueColor`] bytes pass through unchanged.
+/// A sequence split across two writes is held back until it is complete, so
+/// any buffering upstream is fine.
+///
+/// This lets one colored output path serve terminals, pipes and log files:
+/// pick the level once, for example from [`detect_color_support`](crate::detect_color_support)
+/// or a `--color` flag, and write through the adapter.
+///
+/// # Example
+///
+/// ```
+/// use std::io::Write;
+/// use term_ansi::{rgb, set_color_support, AnsiWriter, ColorSupport};
+///
+/// set_color_support(Some(ColorSupport::TrueColor));
+/// let mut writer = AnsiWriter::new(Vec::new(), ColorSupport::Ansi256);
+/// write!(writer, "{}", rgb!(255, 135, 0, "orange")).unwrap();
+/// assert!(writer.into_inner().starts_with(b"\x1b[38;5;208morange"));
+///
+/// let mut log = AnsiWriter::new(Vec::new(), ColorSupport::None);
+/// write!(log, "{}", rgb!(255, 135, 0, "orange")).unwrap();
+/// assert_eq!(log.into_inner(), b"orange");
+/// ```
+#[derive(Debug)]
+pub struct AnsiWriter<W: io::Write> {
+    inner: W,
+    support: ColorSupport,
+    /// The start of an escape sequence split across writes.
+    pending: Vec<u8>,
+}
+
+impl<W: io::Write> AnsiWriter<W> {
+    /// Wraps `inner`, adapting escape sequences to `support`.
+    pub fn new(inner: W, support: ColorSupport) -> Self {
+        AnsiWriter {
+            inner,
+            support,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Returns the color depth output is adapted to.
+    pub fn support(&self) -> ColorSupport {
+        self.support
+    }
+
+    /// Returns a reference to the wrapped writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped writer.
+    ///
+    /// Bytes written directly to it bypass the adapter.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwraps the writer, discarding an incomplete escape sequence still
+    /// waiting for its end.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Appends `sequence`, a complete escape sequence, to `out` as it should
+    /// be written at the configured color depth.
+    fn rewrite(&self, sequence: &[u8], out: &mut Vec<u8>) {
+        if self.support == ColorSupport::None {
+            return;
+        }
+        let params = sequence
+            .strip_prefix(b"\x1b[")
+            .and_then(|rest| rest.strip_suffix(b"m"))
+            .filter(|params| {
+                params
+                    .iter()
+                    .all(|&b| b.is_ascii_digit() || b == b';' || b == b':')
+            })
+            .and_then(|params| std::str::from_utf8(params).ok());
+        match params {
+            Some(params) => {
+                let rewritten = downgrade_params(params, self.support);
+                if params.is_empty() || !rewritten.is_empty() {
+                    out.extend_from_slice(b"\x1b[");
+                    out.extend_from_slice(rewritten.as_bytes());
+                    out.push(b'm');
+                }
+            }
+            None => out.extend_from_slice(sequence),
+        }
+    }
+}
+
+impl<W: io::Write> io::Write for AnsiWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.support == ColorSupport::TrueColor {
+            return self.inner.write(buf);
+        }
+
+        let mut pending = mem::take(&mut self.pending);
+        let data: Cow<'_, [u8]> = if pending.is_empty() {
+            Cow::Borrowed(buf)
+        } else {
+            pending.extend_from_slice(buf);
+            Cow::Owned(pending)
+        };
+        let mut out = Vec::with_capacity(data.len());
+        let mut rest: &[u8] = &data;
+        while let Some(start) = rest.iter().position(|&b| b == ESC) {
+            out.extend_from_slice(&rest[..start]);
+            rest = &rest[start..];
+            match sequence_len(rest) {
+                Some(len) => {
+                    self.rewrite(&rest[..len], &mut out);
+                    rest = &rest[len..];
+                }
+                None if rest.len() < MAX_PENDING => {
+                    self.pending.extend_from_slice(rest);
+                    rest = &[];
+                }
+                None => {
+                    if self.support != ColorSupport::None {
+                        out.extend_from_slice(rest);
+                    }
+                    rest = &[];
+                }
+            }
+        }
+        out.extend_from_slice(rest);
+        self.inner.write_all(&out)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Returns the byte length of the escape sequence at the start of `bytes`, or
+/// `None` if it has not been terminated yet.
+///
+/// The byte-level, streaming counterpart of `text::escape_len`.
+fn sequence_len(bytes: &[u8]) -> Option<usize> {
+    match *bytes.get(1)? {
+        b'[' => bytes[2..]
+            .iter()
+            .position(|b| (0x40..=0x7e).contains(b))
+            .map(|i| i + 3),
+        b']' | b'P' | b'X' | b'^' | b'_' => (2..bytes.len())
+            .find(|&i| bytes[i] == BEL || (bytes[i] == b'\\' && bytes[i - 1] == ESC))
+            .map(|i| i + 1),
+        0x80.. => Some(1),
+        _ => Some(2),
+    }
+}
+
+/// Rewrites the parameters of one SGR sequence so that its colors fit `support`.
+///
+/// Underline colors have no 16-color form and are dropped at that level.
+pub(crate) fn downgrade_params(params: &str, support: ColorSupport) -> String {
+    let mut rewritten: Vec<String> = Vec::new();
+    for param in sgr_tokens(params) {
+        let code = param.code();
+        if !matches!(code, Some(38 | 48 | 58)) {
+            rewritten.push(param.raw.to_string());
+            continue;
+        }
+        let Some(color) = param.color() else {
+            continue;
+        };
+
+        match (code, downgrade(color, support)) {
+            (Some(38), color) => rewritten.push(color.fg_params()),
+            (Some(48), color) => rewritten.push(color.bg_params()),
+            (_, Color::Ansi256(index)) => rewritten.push(format!("58;5;{}", index)),
+            (_, Color::Rgb(r, g, b)) => rewritten.push(format!("58;2;{};{};{}", r, g, b)),
+            _ => {}
+        }
+    }
+    rewritten.join(";")
+}
+
+/// An [`io::Write`] adapter that sends output unchanged to one writer and
+/// with every escape sequence removed to another.
+///
+/// Covers the usual "pretty console, clean log file" setup: format once and
+/// write through the tee. The plain side is an [`AnsiWriter`] at
+/// [`ColorSupport::None`], so sequences split across writes are still removed
+/// whole.
+///
+/// # Example
+///
+/// ```
+/// use std::io::Write;
+/// use term_ansi::{red, TeeWriter};
+///
+/// let mut tee = TeeWriter::new(Vec::new(), Vec::new());
+/// writeln!(tee, "{} disk full", red!("error:")).unwrap();
+/// let (console, log) = tee.into_inner();
+/// assert!(console.starts_with(b"\x1b[31merror:"));
+/// assert_eq!(log, b"error: disk full\n");
+/// ```
+#[derive(Debug)]
+pub struct TeeWriter<A: io::Write, B: io::Write> {
+    styled: A,
+    plain: AnsiWriter<B>,
+}
+
+impl<A: io::Write, B: io::Write> TeeWriter<A, B> {
+    /// Creates a tee writing the styled output to `styled` and the plain text to `plain`.
+    pub fn new(styled: A, plain: B) -> Self {
+        TeeWriter {
+            styled,
+            plain: AnsiWriter::new(plain, ColorSupport::None),
+        }
+    }
+
+    /// Returns references to the styled and the plain writer.
+    pub fn get_ref(&self) -> (&A, &B) {
+        (&self.styled, self.plain.get_ref())
+    }
+
+    /// Returns mutable references to the styled and the plain writer.
+    ///
+    /// Bytes written directly to them bypass the tee.
+    pub fn get_mut(&mut self) -> (&mut A, &mut B) {
+        (&mut self.styled, self.plain.get_mut())
+    }
+
+    /// Unwraps the styled and the plain writer.
+    pub fn into_inner(self) -> (A, B) {
+        (self.styled, self.plain.into_inner())
+    }
+}
+
+impl<A: io::Write, B: io::Write> io::Write for TeeWriter<A, B> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.styled.write_all(buf)?;
+        self.plain.write_all(buf)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.styled.flush()?;
+        self.plain.flush()
+    }
+}